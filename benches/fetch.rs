@@ -1,6 +1,6 @@
 use bogrep::{
     errors::BogrepError, html, utils, Action, BookmarkManager, BookmarkService, Cache, CacheMode,
-    Caching, Fetch, MockClient, RunMode, ServiceConfig, Settings, TargetBookmark,
+    Caching, Fetch, FetchStrategy, MockClient, RunMode, ServiceConfig, Settings, TargetBookmark,
     TargetBookmarkBuilder, TargetBookmarks,
 };
 use chrono::Utc;
@@ -32,6 +32,18 @@ fn bench_fetch(c: &mut Criterion) {
         let runtime = tokio::runtime::Runtime::new().expect("Can't create tokio runtime");
         b.to_async(runtime).iter(|| fetch_in_parallel(500));
     });
+
+    c.bench_function("service parallel 100", |b| {
+        let runtime = tokio::runtime::Runtime::new().expect("Can't create tokio runtime");
+        b.to_async(runtime)
+            .iter(|| fetch(100, FetchStrategy::Parallel));
+    });
+
+    c.bench_function("service parallel 500", |b| {
+        let runtime = tokio::runtime::Runtime::new().expect("Can't create tokio runtime");
+        b.to_async(runtime)
+            .iter(|| fetch(500, FetchStrategy::Parallel));
+    });
 }
 
 criterion_group!(
@@ -42,6 +54,10 @@ criterion_group!(
 criterion_main!(benches);
 
 async fn fetch_concurrently(max_concurrent_requests: usize) {
+    fetch(max_concurrent_requests, FetchStrategy::Concurrent).await
+}
+
+async fn fetch(max_concurrent_requests: usize, strategy: FetchStrategy) {
     let now = Utc::now();
     let temp_dir = tempdir().unwrap();
     let temp_path = temp_dir.path();
@@ -54,6 +70,7 @@ async fn fetch_concurrently(max_concurrent_requests: usize) {
         max_concurrent_requests,
         ..Default::default()
     };
+    let target_bookmark_path = temp_path.join("bookmarks.json");
     let config = ServiceConfig::new(
         RunMode::FetchAll,
         &settings.ignored_urls,
@@ -62,8 +79,8 @@ async fn fetch_concurrently(max_concurrent_requests: usize) {
     .unwrap();
     let cache = Cache::new(&cache_path, CacheMode::Text);
     let client = MockClient::new();
-    let target_reader_writer =
-        utils::open_file_in_read_write_mode(&temp_path.join("bookmarks.json")).unwrap();
+    fs::write(&target_bookmark_path, "").unwrap();
+    let target_reader_writer = utils::open_file_in_read_write_mode(&target_bookmark_path).unwrap();
     let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
 
     for i in 0..10000 {
@@ -78,7 +95,7 @@ async fn fetch_concurrently(max_concurrent_requests: usize) {
 
     assert_eq!(bookmark_manager.target_bookmarks().len(), 10000);
 
-    let service = BookmarkService::new(config, client, cache);
+    let service = BookmarkService::new(config, client, cache).with_strategy(strategy);
 
     service.process(&mut bookmark_manager, now).await.unwrap();
 }
@@ -1,14 +1,34 @@
-use crate::cache::CacheMode;
-use clap::{ArgAction, Args as ClapArgs, Parser, Subcommand};
+use crate::{
+    cache::CacheMode,
+    cmd::{CatMode, Granularity},
+    html::TextExtractor,
+    logger::LogFormat,
+    settings::OversizedContent,
+    utils, ColorChoice, FetchStrategy, SourceFormat,
+};
+use chrono::NaiveDate;
+use clap::{
+    builder::{NonEmptyStringValueParser, RangedU64ValueParser},
+    ArgAction, Args as ClapArgs, Parser, Subcommand,
+};
+use std::{path::PathBuf, time::Duration};
 
 /// Describes the available arguments in the CLI.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
-    /// The search term.
-    pub pattern: Option<String>,
+    /// The search terms.
+    ///
+    /// Multiple patterns are separated by a whitespace.
+    pub patterns: Vec<String>,
     #[arg(short, long, action = ArgAction::Count)]
     pub verbose: u8,
+    /// The output format of the log records.
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+    /// Colorize match highlights and diffs.
+    #[arg(long, value_enum, global = true, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
     /// Search the cached bookmarks in HTML or plaintext format.
     #[arg(short, long, value_enum)]
     pub mode: Option<CacheMode>,
@@ -21,6 +41,34 @@ pub struct Args {
     /// Match only whole words.
     #[arg(short = 'w', long)]
     pub word_regexp: bool,
+    /// Prefix each matched line with its line number.
+    #[arg(short = 'n', long)]
+    pub line_number: bool,
+    /// Print statistics about the search to stderr.
+    #[arg(long)]
+    pub stats: bool,
+    /// Format the search results with a template, e.g. '{url}: {line}'.
+    ///
+    /// Available placeholders are `{url}`, `{title}`, `{line}`, and
+    /// `{line_number}`.
+    #[arg(long, conflicts_with = "files_with_matches")]
+    pub template: Option<String>,
+    /// Match bookmarks containing all of the patterns.
+    #[arg(long, conflicts_with = "or")]
+    pub and: bool,
+    /// Match bookmarks containing any of the patterns (default).
+    #[arg(long)]
+    pub or: bool,
+    /// List the bookmarks without cached content instead of searching.
+    #[arg(long, conflicts_with = "patterns")]
+    pub exclude_cached: bool,
+    /// Match and print lines, paragraphs, or sentences of the cached content.
+    #[arg(long, value_enum, default_value_t = Granularity::Line)]
+    pub granularity: Granularity,
+    /// Match the patterns against the urls of the bookmarks instead of their
+    /// cached content.
+    #[arg(long, visible_alias = "path-only", conflicts_with = "exclude_cached")]
+    pub url: bool,
     #[command(subcommand)]
     pub subcommands: Option<Subcommands>,
 }
@@ -31,7 +79,7 @@ pub enum Subcommands {
     /// Initialize the source files to import the bookmarks.
     Init(InitArgs),
     /// Configure the settings.
-    Config(ConfigArgs),
+    Config(Box<ConfigArgs>),
     /// Synchronize source and target bookmarks. Fetch and cache websites for
     /// new bookmarks; delete cache for removed bookmarks.
     Sync(SyncArgs),
@@ -45,6 +93,27 @@ pub enum Subcommands {
     Add(AddArgs),
     /// Remove a bookmark.
     Remove(RemoveArgs),
+    /// Verify that the cache is consistent with the bookmarks.
+    Verify(VerifyArgs),
+    /// Rebuild the cache in the configured cache mode. Convert cached websites
+    /// where possible; refetch websites otherwise.
+    Reindex(ReindexArgs),
+    /// Build an inverted index of the cached websites to speed up searching.
+    Index(IndexArgs),
+    /// Print the cached content of a bookmark.
+    Cat(CatArgs),
+    /// List the bookmarks, or the bookmarks which failed to cache.
+    List(ListArgs),
+    /// Synchronize and fetch bookmarks periodically until interrupted.
+    Watch(WatchArgs),
+    /// Merge the bookmarks from another `bookmarks.json`.
+    Merge(MergeArgs),
+    /// Back up the bookmarks, settings, and cache to a compressed tarball.
+    Backup(BackupArgs),
+    /// Restore the bookmarks, settings, and cache from a compressed tarball.
+    Restore(RestoreArgs),
+    /// Remove bookmarks which weren't imported for a given duration.
+    Prune(PruneArgs),
 }
 
 /// Describes the arguments for the `init` subcommand.
@@ -61,6 +130,12 @@ pub struct ConfigArgs {
     /// Run command in dry mode.
     #[arg(short = 'n', long = "dry-run")]
     pub dry_run: bool,
+    /// List the configured sources, and the sources of supported browsers
+    /// which are not configured yet.
+    #[arg(long)]
+    pub list_sources: bool,
+    #[command(flatten)]
+    pub reset_settings: ResetSettings,
     #[command(flatten)]
     pub set_source: SetSource,
     #[command(flatten)]
@@ -81,6 +156,37 @@ pub struct ConfigArgs {
     pub set_max_idle_connections_per_host: SetMaxIdleConnectionsPerHost,
     #[command(flatten)]
     pub set_idle_connections_timeout: SetIdleConnectionsTimeout,
+    #[command(flatten)]
+    pub set_max_content_size: SetMaxContentSize,
+    #[command(flatten)]
+    pub set_extractor: SetExtractor,
+    #[command(flatten)]
+    pub set_cache_path: SetCachePath,
+    #[command(flatten)]
+    pub set_headers: SetHeaders,
+    #[command(flatten)]
+    pub set_underlying_depth: SetUnderlyingDepth,
+    #[command(flatten)]
+    pub set_respect_robots: SetRespectRobots,
+    #[command(flatten)]
+    pub set_accept_language: SetAcceptLanguage,
+    #[command(flatten)]
+    pub set_cache_file_mode: SetCacheFileMode,
+    #[command(flatten)]
+    pub set_removal_grace_period: SetRemovalGracePeriod,
+    #[command(flatten)]
+    pub set_query: SetQuery,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct ResetSettings {
+    /// Restore the default settings.
+    #[arg(long)]
+    pub reset: bool,
+    /// Remove the configured sources.
+    #[arg(long)]
+    pub reset_sources: bool,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -94,6 +200,27 @@ pub struct SetSource {
     /// Multiple folders are separated by a comma.
     #[arg(long, num_args = 0.., value_delimiter = ',')]
     pub folders: Vec<String>,
+    /// The format of the bookmark file, overriding the detection by file
+    /// extension.
+    #[arg(long, value_enum)]
+    pub format: Option<SourceFormat>,
+    /// A custom label stored as source type of the imported bookmarks, e.g.
+    /// `work`, instead of the detected source type.
+    #[arg(long, value_name = "LABEL", value_parser = NonEmptyStringValueParser::new())]
+    pub source_type: Option<String>,
+    /// Set the bookmark folders to be imported for a configured source,
+    /// which is given by `--source`, or is the most recently configured
+    /// source otherwise.
+    ///
+    /// Multiple folders are separated by a comma.
+    #[arg(
+        long,
+        value_name = "FOLDERS",
+        num_args = 0..,
+        value_delimiter = ',',
+        conflicts_with_all = ["folders", "format", "source_type"]
+    )]
+    pub import_folders: Option<Vec<String>>,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -146,17 +273,118 @@ pub struct SetMaxConcurrentRequests {
     pub max_concurrent_requests: Option<usize>,
 }
 
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetExtractor {
+    /// The strategy to extract the text of websites.
+    #[arg(long, value_enum)]
+    pub extractor: Option<TextExtractor>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false, multiple = true)]
+pub struct SetCachePath {
+    /// The directory to cache the fetched websites.
+    #[arg(long, value_name = "DIR")]
+    pub cache_path: Option<PathBuf>,
+    /// Move the cached websites to the configured cache path.
+    #[arg(long, requires = "cache_path")]
+    pub migrate: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetHeaders {
+    /// Send an additional HTTP header, e.g. a cookie, to the domain and its
+    /// subdomains.
+    ///
+    /// The header is specified as `Name: value`.
+    #[arg(long = "header", num_args = 2, value_names = ["DOMAIN", "HEADER"])]
+    pub headers: Vec<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetUnderlyingDepth {
+    /// The maximum number of levels to resolve underlyings of underlyings.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub underlying_depth: Option<usize>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetRespectRobots {
+    /// Skip urls which are disallowed by the `robots.txt` of their host.
+    #[arg(long, value_name = "BOOL", action = ArgAction::Set)]
+    pub respect_robots: Option<bool>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetRemovalGracePeriod {
+    /// Keep bookmarks which were removed from the sources, together with
+    /// their cache, for the given duration, e.g. `30d`, until they are
+    /// cleaned up via `bogrep clean --expired`.
+    #[arg(long, value_name = "DURATION", value_parser = utils::parse_duration)]
+    pub removal_grace_period: Option<Duration>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetQuery {
+    /// Strip the given query parameters from the urls of imported bookmarks,
+    /// e.g. `utm_* fbclid`, or the entire query if no parameters are given.
+    #[arg(long, value_name = "PARAMS", num_args = 0.., value_delimiter = ' ')]
+    pub strip_query: Option<Vec<String>>,
+    /// Keep the given query parameters when stripping the query, e.g. `id`.
+    #[arg(long, value_name = "PARAMS", num_args = 1.., value_delimiter = ' ')]
+    pub include_query: Vec<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetCacheFileMode {
+    /// Set the permissions of the cached files on Unix as octal number, e.g.
+    /// `600` to make them readable by the owner only.
+    #[arg(long, value_name = "MODE", value_parser = utils::parse_file_mode)]
+    pub cache_file_mode: Option<u32>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false)]
+pub struct SetAcceptLanguage {
+    /// Set the `Accept-Language` header to request a language version of
+    /// websites, e.g. `de-DE,de;q=0.9`. An empty value unsets the header.
+    #[arg(long, value_name = "LANGUAGE")]
+    pub accept_language: Option<String>,
+}
+
+#[derive(ClapArgs, Debug)]
+#[group(required = false, multiple = true)]
+pub struct SetMaxContentSize {
+    /// The maximum size of the content of a website in bytes.
+    #[arg(long, value_name = "BYTES")]
+    pub max_content_size: Option<u64>,
+    /// Skip or truncate websites whose content exceeds the maximum size.
+    #[arg(long, value_enum)]
+    pub oversized_content: Option<OversizedContent>,
+}
+
 #[derive(ClapArgs, Debug)]
 #[group(required = false)]
 pub struct SetMaxIdleConnectionsPerHost {
-    #[arg(long)]
+    /// The maximum number of idle connections per host in the connection
+    /// pool. `0` disables keeping idle connections.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(0..=1024))]
     pub max_idle_connections_per_host: Option<usize>,
 }
 
 #[derive(ClapArgs, Debug)]
 #[group(required = false)]
 pub struct SetIdleConnectionsTimeout {
-    #[arg(long)]
+    /// The timeout in milliseconds for idle connections to be kept alive, up
+    /// to one hour.
+    #[arg(long, value_name = "MS", value_parser = RangedU64ValueParser::<u64>::new().range(1..=3_600_000))]
     pub idle_connections_timeout: Option<u64>,
 }
 
@@ -166,6 +394,31 @@ pub struct ImportArgs {
     /// Run command in dry mode.
     #[arg(short = 'n', long = "dry-run")]
     pub dry_run: bool,
+    /// The format of the configured bookmark files, overriding the detection
+    /// by file extension.
+    #[arg(long, value_enum)]
+    pub format: Option<SourceFormat>,
+    /// A custom label stored as source type of the imported bookmarks,
+    /// overriding the labels of the configured sources.
+    #[arg(long, value_name = "LABEL", value_parser = NonEmptyStringValueParser::new())]
+    pub source_type: Option<String>,
+    /// Replace a configured source with a new source, e.g. after moving the
+    /// browser profile.
+    ///
+    /// Bookmarks which are only present in the old source are removed.
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    pub replace_source: Option<Vec<String>>,
+    /// Only add new bookmarks, and keep the bookmarks which are missing in
+    /// the sources instead of removing them.
+    #[arg(long, visible_alias = "additive", conflicts_with = "replace_source")]
+    pub no_remove: bool,
+    /// List the bookmarks which were removed because they match the ignored
+    /// urls.
+    #[arg(long)]
+    pub ignored_report: bool,
+    /// Don't show a spinner while parsing the sources.
+    #[arg(short, long)]
+    pub quiet: bool,
 }
 
 /// Describes the arguments for the `fetch` subcommand.
@@ -186,15 +439,127 @@ pub struct FetchArgs {
     /// Multiple urls are separated by a whitespace.
     #[arg(short, long, value_name = "URLs", num_args = 0.., value_delimiter = ' ')]
     pub diff: Vec<String>,
+    /// Print the number of added and removed lines per bookmark instead of
+    /// the full diff.
+    ///
+    /// The full diff is still printed if the verbose flag is set.
+    #[arg(long, requires = "diff")]
+    pub summary: bool,
     /// Fetch and cache specified URLs.
     ///
     /// Multiple URLs are separated by a whitespace.
     /// If an URL is missing in the bookmarks, it will be imported.
     #[arg(long, num_args = 0.., value_delimiter = ' ')]
     pub urls: Vec<String>,
+    /// Fetch and cache the URLs listed in a file.
+    ///
+    /// URLs are separated by a newline, and lines starting with `#` are
+    /// ignored. URLs which are missing in the bookmarks are fetched without
+    /// adding them, unless `--add` is set; their cached files can be removed
+    /// with `bogrep clean --unreferenced`.
+    #[arg(long, value_name = "PATH", conflicts_with = "diff")]
+    pub input_file: Option<PathBuf>,
+    /// Add the URLs of `--input-file` which are missing in the bookmarks.
+    #[arg(long, requires = "input_file")]
+    pub add: bool,
+    /// Import bookmarks from the configured sources, and fetch only the
+    /// bookmarks added by this import.
+    #[arg(long, conflicts_with_all = ["replace", "diff", "urls", "input_file"])]
+    pub only_new: bool,
     /// Run command in dry mode.
     #[arg(short = 'n', long = "dry-run")]
     pub dry_run: bool,
+    /// Fetch only bookmarks whose url or host matches the glob pattern.
+    ///
+    /// Multiple patterns are separated by a whitespace.
+    #[arg(long, value_name = "GLOB", num_args = 0.., value_delimiter = ' ')]
+    pub include: Vec<String>,
+    /// Skip bookmarks whose url or host matches the glob pattern.
+    ///
+    /// Multiple patterns are separated by a whitespace.
+    #[arg(long, value_name = "GLOB", num_args = 0.., value_delimiter = ' ')]
+    pub exclude: Vec<String>,
+    /// Write a manifest with the filename, size, and hash of the cached files
+    /// to `manifest.json`.
+    #[arg(long)]
+    pub write_manifest: bool,
+    /// The strategy to extract the text of websites if the cache mode is
+    /// `text`.
+    #[arg(long, value_enum)]
+    pub extractor: Option<TextExtractor>,
+    /// Normalize the whitespace of the cached text, i.e. replace non-breaking
+    /// spaces, collapse runs of whitespace, and trim lines.
+    #[arg(long)]
+    pub normalize_whitespace: bool,
+    /// Override the maximum number of concurrent requests for this run.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: Option<usize>,
+    /// Override the request timeout in milliseconds for this run.
+    #[arg(long, value_name = "MS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+    pub timeout: Option<u64>,
+    /// Skip the first N bookmarks, sorted by url, e.g. to fetch a large
+    /// number of bookmarks in batches.
+    #[arg(long, value_name = "N")]
+    pub offset: Option<usize>,
+    /// Fetch at most N bookmarks, sorted by url, e.g. to fetch a large number
+    /// of bookmarks in batches.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub limit: Option<usize>,
+    /// Write the bookmarks to `bookmarks.json` after every N processed
+    /// bookmarks.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub checkpoint_every: Option<usize>,
+    /// Process the bookmarks concurrently on a single task, or in parallel on
+    /// a task per bookmark.
+    ///
+    /// The parallel strategy can be faster on multi-core machines if the
+    /// conversion of large websites dominates over the requests.
+    #[arg(long, value_enum, default_value_t = FetchStrategy::Concurrent)]
+    pub strategy: FetchStrategy,
+    /// Print the plain progress instead of a progress bar.
+    #[arg(short, long)]
+    pub quiet: bool,
+    /// Cache websites with an empty response as empty files instead of
+    /// skipping them.
+    ///
+    /// Websites which can't be fetched are counted as failed in any case.
+    #[arg(long)]
+    pub continue_on_empty: bool,
+    /// Don't resolve the underlying bookmarks of aggregator pages like
+    /// Hacker News or Reddit.
+    #[arg(long)]
+    pub no_underlying: bool,
+    /// Cache only the underlying bookmarks of aggregator pages like Hacker
+    /// News or Reddit, and skip caching the aggregator pages themselves.
+    #[arg(long, conflicts_with = "no_underlying")]
+    pub only_underlying: bool,
+    /// Cache the extracted text of binary documents like PDF instead of
+    /// skipping them (requires feature `pdf-extract`).
+    #[arg(long)]
+    pub include_binary: bool,
+    /// Don't follow redirects, and treat redirected websites as failed
+    /// instead.
+    #[arg(long)]
+    pub no_follow_redirects: bool,
+    /// Name the cached files of new bookmarks by their url, e.g.
+    /// `example-com-blog-post-1a2b3c4d.txt`, instead of their id.
+    #[arg(long)]
+    pub human_readable: bool,
+    /// Skip urls which are disallowed by the `robots.txt` of their host,
+    /// overriding the configured setting.
+    #[arg(long)]
+    pub respect_robots: bool,
+    /// Ignore `robots.txt`, overriding the configured setting.
+    #[arg(long, conflicts_with = "respect_robots")]
+    pub ignore_robots: bool,
+    /// Skip websites whose extracted text is shorter than the given number
+    /// of characters, overriding the configured setting.
+    #[arg(long, value_name = "CHARS")]
+    pub min_text_length: Option<usize>,
+    /// List the bookmarks which were removed because they match the ignored
+    /// urls.
+    #[arg(long)]
+    pub ignored_report: bool,
 }
 
 /// Describes the arguments for the `sync` subcommand.
@@ -206,6 +571,55 @@ pub struct SyncArgs {
     /// Run command in dry mode.
     #[arg(short = 'n', long = "dry-run")]
     pub dry_run: bool,
+    /// The strategy to extract the text of websites if the cache mode is
+    /// `text`.
+    #[arg(long, value_enum)]
+    pub extractor: Option<TextExtractor>,
+    /// Normalize the whitespace of the cached text, i.e. replace non-breaking
+    /// spaces, collapse runs of whitespace, and trim lines.
+    #[arg(long)]
+    pub normalize_whitespace: bool,
+    /// Override the maximum number of concurrent requests for this run.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub concurrency: Option<usize>,
+    /// Override the request timeout in milliseconds for this run.
+    #[arg(long, value_name = "MS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+    pub timeout: Option<u64>,
+    /// Don't resolve the underlying bookmarks of aggregator pages like
+    /// Hacker News or Reddit.
+    #[arg(long)]
+    pub no_underlying: bool,
+    /// Cache only the underlying bookmarks of aggregator pages like Hacker
+    /// News or Reddit, and skip caching the aggregator pages themselves.
+    #[arg(long, conflicts_with = "no_underlying")]
+    pub only_underlying: bool,
+    /// Cache the extracted text of binary documents like PDF instead of
+    /// skipping them (requires feature `pdf-extract`).
+    #[arg(long)]
+    pub include_binary: bool,
+    /// Don't follow redirects, and treat redirected websites as failed
+    /// instead.
+    #[arg(long)]
+    pub no_follow_redirects: bool,
+    /// Name the cached files of new bookmarks by their url, e.g.
+    /// `example-com-blog-post-1a2b3c4d.txt`, instead of their id.
+    #[arg(long)]
+    pub human_readable: bool,
+    /// Skip urls which are disallowed by the `robots.txt` of their host,
+    /// overriding the configured setting.
+    #[arg(long)]
+    pub respect_robots: bool,
+    /// Ignore `robots.txt`, overriding the configured setting.
+    #[arg(long, conflicts_with = "respect_robots")]
+    pub ignore_robots: bool,
+    /// Skip websites whose extracted text is shorter than the given number
+    /// of characters, overriding the configured setting.
+    #[arg(long, value_name = "CHARS")]
+    pub min_text_length: Option<usize>,
+    /// List the bookmarks which were removed because they match the ignored
+    /// urls.
+    #[arg(long)]
+    pub ignored_report: bool,
 }
 
 /// Describes the arguments for the `clean` subcommand.
@@ -214,6 +628,18 @@ pub struct CleanArgs {
     /// Clean cache for all file extensions (.txt, .md, .html).
     #[arg(short, long)]
     pub all: bool,
+    /// Remove cached files which don't belong to any bookmark in
+    /// `bookmarks.json`.
+    #[arg(long, conflicts_with = "all")]
+    pub unreferenced: bool,
+    /// Remove bookmarks which were removed from the sources longer ago than
+    /// the configured removal grace period, together with their cache.
+    #[arg(long, conflicts_with_all = ["all", "unreferenced"])]
+    pub expired: bool,
+    /// Remove the cached files of the given format for all bookmarks, e.g.
+    /// after switching the configured cache mode.
+    #[arg(long, value_enum, conflicts_with_all = ["all", "unreferenced", "expired", "mode"])]
+    pub cache_mode: Option<CacheMode>,
     /// Cache the fetched bookmarks as text, HTML or markdown file.
     #[arg(short, long, value_enum)]
     pub mode: Option<CacheMode>,
@@ -227,6 +653,17 @@ pub struct AddArgs {
     /// Multiple URLs are separated by a whitespace.
     #[arg(num_args = 0.., value_name = "URLs", value_delimiter = ' ')]
     pub urls: Vec<String>,
+    /// The title of the added bookmark.
+    ///
+    /// Can only be used if a single URL is added.
+    #[arg(long)]
+    pub title: Option<String>,
+    /// The folder of the added bookmarks.
+    #[arg(long)]
+    pub folder: Option<String>,
+    /// Fetch and cache the added bookmarks right away.
+    #[arg(long)]
+    pub fetch: bool,
     /// Run command in dry mode.
     #[arg(short = 'n', long = "dry-run")]
     pub dry_run: bool,
@@ -241,3 +678,134 @@ pub struct RemoveArgs {
     #[arg(num_args = 0.., value_name = "URLs", value_delimiter = ' ')]
     pub urls: Vec<String>,
 }
+
+/// Describes the arguments for the `verify` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct VerifyArgs {
+    /// Fix inconsistencies between cache and bookmarks.
+    ///
+    /// Cache modes for missing files are removed from the bookmarks, and
+    /// orphaned files are deleted from the cache.
+    #[arg(long)]
+    pub fix: bool,
+}
+
+/// Describes the arguments for the `reindex` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct ReindexArgs {
+    /// Run command in dry mode.
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+    /// Rebuild the cached bookmarks as text, HTML or markdown file.
+    #[arg(short, long, value_enum)]
+    pub mode: Option<CacheMode>,
+    /// The strategy to extract the text of websites if the cache mode is
+    /// `text`.
+    #[arg(long, value_enum)]
+    pub extractor: Option<TextExtractor>,
+    /// Normalize the whitespace of the cached text, i.e. replace non-breaking
+    /// spaces, collapse runs of whitespace, and trim lines.
+    #[arg(long)]
+    pub normalize_whitespace: bool,
+}
+
+/// Describes the arguments for the `cat` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct CatArgs {
+    /// The URL or id of the bookmark.
+    #[arg(value_name = "URL")]
+    pub bookmark: String,
+    /// Print the cached bookmark as text, HTML, or markdown.
+    ///
+    /// Defaults to the configured cache mode, or any other cache mode the
+    /// bookmark is cached in.
+    #[arg(short, long, value_enum)]
+    pub mode: Option<CatMode>,
+    /// Convert the cached bookmark to text, HTML, or markdown for display
+    /// without rewriting the cache.
+    #[arg(
+        long = "as",
+        visible_alias = "format",
+        value_name = "FORMAT",
+        value_enum
+    )]
+    pub as_format: Option<CatMode>,
+}
+
+/// Describes the arguments for the `list` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct ListArgs {
+    /// List only bookmarks which failed to cache, together with the reason.
+    #[arg(long)]
+    pub failed: bool,
+    /// List only bookmarks imported from the given source, e.g. `firefox`.
+    #[arg(long, value_name = "SOURCE")]
+    pub source: Option<String>,
+    /// List only bookmarks imported since the given date (`YYYY-MM-DD`).
+    #[arg(long, value_name = "DATE", value_parser = utils::parse_date)]
+    pub since: Option<NaiveDate>,
+}
+
+/// Describes the arguments for the `merge` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct MergeArgs {
+    /// The path to the other `bookmarks.json`.
+    pub path: PathBuf,
+}
+
+/// Describes the arguments for the `backup` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct BackupArgs {
+    /// The path of the archive, e.g. `bogrep.tar.gz`.
+    #[arg(short, long, value_name = "FILE")]
+    pub output: PathBuf,
+}
+
+/// Describes the arguments for the `restore` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct RestoreArgs {
+    /// The path of the archive created by `bogrep backup`.
+    #[arg(short, long, value_name = "FILE")]
+    pub input: PathBuf,
+    /// Overwrite existing bookmarks.
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Describes the arguments for the `prune` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct PruneArgs {
+    /// Run command in dry mode.
+    #[arg(short = 'n', long = "dry-run")]
+    pub dry_run: bool,
+    /// Remove bookmarks which weren't imported for the given duration, e.g.
+    /// `90d`.
+    #[arg(long, value_name = "DURATION", value_parser = utils::parse_duration)]
+    pub older_than: Duration,
+    /// Remove only bookmarks which weren't cached for the given duration, or
+    /// were never cached.
+    #[arg(long, value_name = "DURATION", value_parser = utils::parse_duration)]
+    pub cached_older_than: Option<Duration>,
+}
+
+/// Describes the arguments for the `watch` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct WatchArgs {
+    /// The interval between two cycles, e.g. `30m`, `12h`, or `1d`.
+    #[arg(long, value_name = "DURATION", value_parser = utils::parse_duration)]
+    pub interval: Duration,
+    /// Cache the fetched bookmarks as text, HTML or markdown file.
+    #[arg(short, long, value_enum)]
+    pub mode: Option<CacheMode>,
+    /// Stop watching after the given number of cycles.
+    #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub cycles: Option<usize>,
+}
+
+/// Describes the arguments for the `index` subcommand.
+#[derive(ClapArgs, Debug)]
+pub struct IndexArgs {
+    /// Index the cached bookmarks in HTML or plaintext format.
+    #[arg(short, long, value_enum)]
+    pub mode: Option<CacheMode>,
+}
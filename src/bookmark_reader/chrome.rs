@@ -36,18 +36,7 @@ impl SelectSource for ChromeSelector {
             SourceOs::Macos => vec![home_dir.join("Library/Application Support/Google/Chrome")],
         };
         let bookmark_dirs = ChromiumSelector::find_profile_dirs(&browser_dirs);
-        let bookmark_files = bookmark_dirs
-            .into_iter()
-            .filter_map(|bookmark_dir| {
-                let bookmark_file = bookmark_dir.join("Bookmarks");
-
-                if bookmark_file.is_file() {
-                    Some(bookmark_file)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let bookmark_files = ChromiumSelector::find_bookmark_files(bookmark_dirs);
 
         Ok(bookmark_files)
     }
@@ -7,7 +7,10 @@ use crate::{
 use anyhow::anyhow;
 use log::{debug, trace};
 use serde_json::{Map, Value};
-use std::path::{Path, PathBuf};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 pub type JsonBookmarkReader<'a> = Box<dyn ReadBookmark<'a, ParsedValue = serde_json::Value>>;
 
@@ -18,10 +21,28 @@ impl ChromiumSelector {
         Box::new(ChromiumSelector)
     }
 
+    /// Find the profile directories in the browser directories.
+    ///
+    /// The profiles are read from the `Local State` file of the browser
+    /// directory, which also lists renamed profiles. Falls back to scanning
+    /// `Default` and `Profile N` if `Local State` is missing or can't be
+    /// parsed.
     pub fn find_profile_dirs(browser_dirs: &[PathBuf]) -> Vec<PathBuf> {
         let mut bookmark_dirs = vec![];
 
         for browser_dir in browser_dirs {
+            if let Some(profile_names) = Self::read_profile_names(browser_dir) {
+                for profile_name in profile_names {
+                    let bookmark_dir = browser_dir.join(profile_name);
+
+                    if bookmark_dir.is_dir() {
+                        bookmark_dirs.push(bookmark_dir);
+                    }
+                }
+
+                continue;
+            }
+
             let bookmark_dir = browser_dir.join("Default");
 
             if bookmark_dir.is_dir() {
@@ -40,6 +61,55 @@ impl ChromiumSelector {
 
         bookmark_dirs
     }
+
+    /// Read the names of the profile directories from `profile.info_cache`
+    /// in the `Local State` file, with the `Default` profile first.
+    fn read_profile_names(browser_dir: &Path) -> Option<Vec<String>> {
+        let local_state_path = browser_dir.join("Local State");
+        let local_state_file = File::open(&local_state_path).ok()?;
+        let local_state: Value = match serde_json::from_reader(local_state_file) {
+            Ok(local_state) => local_state,
+            Err(err) => {
+                debug!("Can't parse {}: {err}", local_state_path.display());
+                return None;
+            }
+        };
+        let info_cache = local_state.get("profile")?.get("info_cache")?.as_object()?;
+
+        let mut profile_names = info_cache
+            .keys()
+            // Guard against profile names escaping the browser directory.
+            .filter(|profile_name| {
+                Path::new(profile_name).file_name() == Some(profile_name.as_ref())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        profile_names.sort_by_key(|profile_name| profile_name != "Default");
+
+        if profile_names.is_empty() {
+            None
+        } else {
+            Some(profile_names)
+        }
+    }
+
+    /// Find the bookmark files in the profile directories.
+    ///
+    /// Falls back to `Bookmarks.bak` if `Bookmarks` is missing or can't be
+    /// read, e.g. while the browser is running.
+    pub fn find_bookmark_files(bookmark_dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+        bookmark_dirs
+            .into_iter()
+            .filter_map(|bookmark_dir| {
+                ["Bookmarks", "Bookmarks.bak"]
+                    .into_iter()
+                    .map(|file_name| bookmark_dir.join(file_name))
+                    .find(|bookmark_file| {
+                        bookmark_file.is_file() && File::open(bookmark_file).is_ok()
+                    })
+            })
+            .collect()
+    }
 }
 
 impl SelectSource for ChromiumSelector {
@@ -67,18 +137,7 @@ impl SelectSource for ChromiumSelector {
             SourceOs::Macos => vec![],
         };
         let bookmark_dirs = ChromiumSelector::find_profile_dirs(&browser_dirs);
-        let bookmark_files = bookmark_dirs
-            .into_iter()
-            .filter_map(|bookmark_dir| {
-                let bookmark_file = bookmark_dir.join("Bookmarks");
-
-                if bookmark_file.is_file() {
-                    Some(bookmark_file)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let bookmark_files = ChromiumSelector::find_bookmark_files(bookmark_dirs);
 
         Ok(bookmark_files)
     }
@@ -204,6 +263,7 @@ mod tests {
     use assert_matches::assert_matches;
     use std::{
         collections::HashMap,
+        fs,
         path::{Path, PathBuf},
     };
     use tempfile::tempdir;
@@ -296,6 +356,84 @@ mod tests {
         assert!(bookmark_dirs.is_empty());
     }
 
+    #[test]
+    fn test_find_sources_backup() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+        let browser_dir = temp_path.join("snap/chromium/common/chromium");
+        let default_profile_dir = browser_dir.join("Default");
+        let profile_dir = browser_dir.join("Profile 1");
+        fs::create_dir_all(&default_profile_dir).unwrap();
+        fs::create_dir_all(&profile_dir).unwrap();
+        // Only the backup is available for the default profile.
+        File::create(default_profile_dir.join("Bookmarks.bak")).unwrap();
+        File::create(profile_dir.join("Bookmarks")).unwrap();
+        File::create(profile_dir.join("Bookmarks.bak")).unwrap();
+
+        let selector = ChromiumSelector;
+        let res = selector.find_sources(temp_path, &SourceOs::Linux);
+        assert!(res.is_ok(), "Can't find dir: {}", res.unwrap_err());
+
+        let bookmark_files = res.unwrap();
+        assert_eq!(
+            bookmark_files,
+            vec![
+                default_profile_dir.join("Bookmarks.bak"),
+                profile_dir.join("Bookmarks")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_profile_dirs_local_state() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+        let browser_dir = temp_path.join("chromium");
+        fs::create_dir_all(&browser_dir).unwrap();
+        fs::copy(
+            "test_data/local_state_chromium.json",
+            browser_dir.join("Local State"),
+        )
+        .unwrap();
+
+        // `Profile 1` isn't listed in `Local State`, and `Profile 3` is
+        // missing on disk.
+        for profile_name in ["Default", "Profile 1", "Work"] {
+            fs::create_dir_all(browser_dir.join(profile_name)).unwrap();
+        }
+
+        let bookmark_dirs = ChromiumSelector::find_profile_dirs(&[browser_dir.clone()]);
+        assert_eq!(
+            bookmark_dirs,
+            vec![browser_dir.join("Default"), browser_dir.join("Work")]
+        );
+    }
+
+    #[test]
+    fn test_find_profile_dirs_fallback() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+        let browser_dir = temp_path.join("chromium");
+        fs::create_dir_all(&browser_dir).unwrap();
+        fs::write(browser_dir.join("Local State"), "invalid json").unwrap();
+
+        for profile_name in ["Default", "Profile 1", "Work"] {
+            fs::create_dir_all(browser_dir.join(profile_name)).unwrap();
+        }
+
+        let bookmark_dirs = ChromiumSelector::find_profile_dirs(&[browser_dir.clone()]);
+        assert_eq!(
+            bookmark_dirs,
+            vec![browser_dir.join("Default"), browser_dir.join("Profile 1")]
+        );
+    }
+
     #[test]
     fn test_read_and_parse() {
         let source_path = Path::new("test_data/bookmarks_chromium.json");
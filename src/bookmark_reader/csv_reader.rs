@@ -0,0 +1,335 @@
+use super::{ParsedBookmarks, ReadBookmark, ReadSource, SeekRead};
+use crate::{
+    bookmarks::{Source, SourceBookmarkBuilder},
+    SourceBookmarks, SourceType,
+};
+use csv::{ReaderBuilder, StringRecord, Trim};
+use log::debug;
+use std::path::Path;
+
+pub type CsvRecords<'a> = csv::Reader<&'a mut dyn SeekRead>;
+
+/// Reader for csv files.
+#[derive(Debug)]
+pub struct CsvReader;
+
+impl ReadSource for CsvReader {
+    fn extension(&self) -> Option<&str> {
+        Some("csv")
+    }
+
+    fn read_and_parse<'a>(
+        &self,
+        reader: &'a mut dyn SeekRead,
+    ) -> Result<ParsedBookmarks<'a>, anyhow::Error> {
+        debug!("Read file with extension: {:?}", self.extension());
+
+        // The header row is optional and detected when importing the bookmarks.
+        let records = ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(Trim::All)
+            .from_reader(reader);
+        Ok(ParsedBookmarks::Csv(records))
+    }
+}
+
+/// The column indices of a csv file with bookmarks.
+#[derive(Debug, PartialEq)]
+struct CsvColumns {
+    url: usize,
+    folder: Option<usize>,
+}
+
+impl CsvColumns {
+    /// Get the columns from the header row, or `None` if the record doesn't
+    /// contain a `url` column.
+    fn from_header(record: &StringRecord) -> Option<Self> {
+        let position = |name: &str| {
+            record
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name))
+        };
+
+        position("url").map(|url| Self {
+            url,
+            folder: position("folder"),
+        })
+    }
+}
+
+impl Default for CsvColumns {
+    /// Without header row, the columns are expected in the order `url`,
+    /// `title`, `folder`.
+    fn default() -> Self {
+        Self {
+            url: 0,
+            folder: Some(2),
+        }
+    }
+}
+
+/// A bookmark reader to read bookmarks from a csv file with a `url` column,
+/// and optional `title` and `folder` columns.
+#[derive(Debug)]
+pub struct CsvBookmarkReader;
+
+impl CsvBookmarkReader {
+    pub fn new() -> Box<Self> {
+        Box::new(Self)
+    }
+
+    fn select_bookmark(
+        record: &StringRecord,
+        columns: &CsvColumns,
+        source: &Source,
+        source_bookmarks: &mut SourceBookmarks,
+    ) {
+        let folder = columns
+            .folder
+            .and_then(|folder| record.get(folder))
+            .filter(|folder| !folder.is_empty());
+
+        if let Some(folder) = folder {
+            source.match_folder(folder);
+        }
+
+        if !source.folders.is_empty()
+            && !folder.is_some_and(|folder| source.folders.iter().any(|f| f == folder))
+        {
+            return;
+        }
+
+        if let Some(url) = record.get(columns.url) {
+            if url.contains("http") {
+                let source_bookmark = SourceBookmarkBuilder::new(url)
+                    .add_source(source.source_type.to_owned())
+                    .add_folder_opt(source.source_type.to_owned(), folder)
+                    .build();
+                source_bookmarks.insert(source_bookmark);
+            }
+        }
+    }
+}
+
+impl<'a> ReadBookmark<'a> for CsvBookmarkReader {
+    type ParsedValue = CsvRecords<'a>;
+
+    fn name(&self) -> SourceType {
+        SourceType::Csv
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some("csv")
+    }
+
+    fn select_source(
+        &self,
+        _source_path: &Path,
+        _parsed_bookmarks: &Self::ParsedValue,
+    ) -> Result<Option<SourceType>, anyhow::Error> {
+        Ok(Some(SourceType::Csv))
+    }
+
+    fn import(
+        &self,
+        source: &Source,
+        mut parsed_bookmarks: Self::ParsedValue,
+        source_bookmarks: &mut SourceBookmarks,
+    ) -> Result<(), anyhow::Error> {
+        debug!("Import bookmarks from {:#?}", self.name());
+
+        let mut records = parsed_bookmarks.records();
+        let mut columns = CsvColumns::default();
+
+        if let Some(first_record) = records.next() {
+            let first_record = first_record?;
+
+            if let Some(header_columns) = CsvColumns::from_header(&first_record) {
+                columns = header_columns;
+            } else {
+                Self::select_bookmark(&first_record, &columns, source, source_bookmarks);
+            }
+        }
+
+        for record in records {
+            let record = record?;
+            Self::select_bookmark(&record, &columns, source, source_bookmarks);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bookmark_reader::SourceReader, utils};
+    use assert_matches::assert_matches;
+    use std::{
+        collections::HashMap,
+        io::Cursor,
+        path::{Path, PathBuf},
+    };
+
+    fn import_csv(content: &str, folders: Vec<String>) -> SourceBookmarks {
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(SourceType::Unknown, &PathBuf::from("dummy_path"), folders);
+        let reader = Cursor::new(content.as_bytes().to_vec());
+        let mut source_reader = SourceReader::new(source, Box::new(reader), Box::new(CsvReader));
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        source_bookmarks
+    }
+
+    #[test]
+    fn test_read_and_parse() {
+        let source_path = Path::new("test_data/bookmarks.csv");
+        let mut reader = utils::open_file(source_path).unwrap();
+        let source_reader = CsvReader;
+
+        let res = source_reader.read_and_parse(&mut reader);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let parsed_bookmarks = res.unwrap();
+        assert_matches!(parsed_bookmarks, ParsedBookmarks::Csv(_));
+    }
+
+    #[test]
+    fn test_import_csv_with_header() {
+        let source_path = Path::new("test_data/bookmarks.csv");
+        assert!(source_path.exists());
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(SourceType::Unknown, &PathBuf::from("dummy_path"), vec![]);
+        let bookmark_file = utils::open_file(source_path).unwrap();
+        let source_reader = Box::new(CsvReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://www.deepl.com/translator";
+        let url2 =
+            "https://www.quantamagazine.org/how-mathematical-curves-power-cryptography-20220919/";
+        let url3 = "https://en.wikipedia.org/wiki/Design_Patterns";
+        let url4 = "https://doc.rust-lang.org/book/title-page.html";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Csv)
+                        .build()
+                ),
+                (
+                    url2.to_owned(),
+                    SourceBookmarkBuilder::new(url2)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "science")
+                        .build()
+                ),
+                (
+                    url3.to_owned(),
+                    SourceBookmarkBuilder::new(url3)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "dev")
+                        .build()
+                ),
+                (
+                    url4.to_owned(),
+                    SourceBookmarkBuilder::new(url4)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "dev")
+                        .build()
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_csv_without_header() {
+        let content = "https://www.deepl.com/translator\n\
+            https://en.wikipedia.org/wiki/Design_Patterns,Design Patterns,dev\n";
+
+        let source_bookmarks = import_csv(content, vec![]);
+
+        let url1 = "https://www.deepl.com/translator";
+        let url2 = "https://en.wikipedia.org/wiki/Design_Patterns";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Csv)
+                        .build()
+                ),
+                (
+                    url2.to_owned(),
+                    SourceBookmarkBuilder::new(url2)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "dev")
+                        .build()
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_csv_reordered_header() {
+        let content = "folder,url\n\
+            dev,https://en.wikipedia.org/wiki/Design_Patterns\n";
+
+        let source_bookmarks = import_csv(content, vec![]);
+
+        let url = "https://en.wikipedia.org/wiki/Design_Patterns";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([(
+                url.to_owned(),
+                SourceBookmarkBuilder::new(url)
+                    .add_source(SourceType::Csv)
+                    .add_folder(SourceType::Csv, "dev")
+                    .build()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_import_csv_folder() {
+        let source_path = Path::new("test_data/bookmarks.csv");
+        let content = String::from_utf8(utils::read_file(source_path).unwrap()).unwrap();
+
+        let source_bookmarks = import_csv(&content, vec!["dev".to_owned()]);
+
+        let url1 = "https://en.wikipedia.org/wiki/Design_Patterns";
+        let url2 = "https://doc.rust-lang.org/book/title-page.html";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "dev")
+                        .build()
+                ),
+                (
+                    url2.to_owned(),
+                    SourceBookmarkBuilder::new(url2)
+                        .add_source(SourceType::Csv)
+                        .add_folder(SourceType::Csv, "dev")
+                        .build()
+                )
+            ])
+        );
+    }
+}
@@ -33,21 +33,10 @@ impl SelectSource for EdgeSelector {
                 home_dir.join(".config/microsoft-edge"),
             ],
             SourceOs::Windows => vec![home_dir.join("AppData\\Local\\Microsoft\\Edge\\User Data")],
-            SourceOs::Macos => vec![],
+            SourceOs::Macos => vec![home_dir.join("Library/Application Support/Microsoft Edge")],
         };
         let bookmark_dirs = ChromiumSelector::find_profile_dirs(&browser_dirs);
-        let bookmark_files = bookmark_dirs
-            .into_iter()
-            .filter_map(|bookmark_dir| {
-                let bookmark_file = bookmark_dir.join("Bookmarks");
-
-                if bookmark_file.is_file() {
-                    Some(bookmark_file)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let bookmark_files = ChromiumSelector::find_bookmark_files(bookmark_dirs);
 
         Ok(bookmark_files)
     }
@@ -56,7 +45,8 @@ impl SelectSource for EdgeSelector {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::tests;
+    use crate::{bookmark_reader::ChromiumReader, test_utils::tests, ReadBookmark};
+    use serde_json::json;
     use tempfile::tempdir;
 
     #[test]
@@ -127,7 +117,33 @@ mod tests {
         assert!(res.is_ok(), "Can't find dir: {}", res.unwrap_err());
 
         let bookmark_dirs = res.unwrap();
-        assert!(bookmark_dirs.is_empty());
+        assert_eq!(bookmark_dirs.len(), 2);
+        assert!(bookmark_dirs.contains(
+            &temp_path.join("Library/Application Support/Microsoft Edge/Default/Bookmarks")
+        ));
+        assert!(bookmark_dirs.contains(
+            &temp_path.join("Library/Application Support/Microsoft Edge/Profile 1/Bookmarks")
+        ));
+    }
+
+    #[test]
+    fn test_select_source() {
+        let source_reader = ChromiumReader;
+        let parsed_bookmarks = json!({
+            "checksum": "",
+            "roots": {},
+            "version": 1
+        });
+
+        for source_path in [
+            ".config/microsoft-edge/Default/Bookmarks",
+            "Library/Application Support/Microsoft Edge/Default/Bookmarks",
+            "AppData\\Local\\Microsoft\\Edge\\User Data\\Default\\Bookmarks",
+        ] {
+            let res = source_reader.select_source(Path::new(source_path), &parsed_bookmarks);
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+            assert_eq!(res.unwrap(), Some(SourceType::Edge));
+        }
     }
 
     #[cfg(target_os = "windows")]
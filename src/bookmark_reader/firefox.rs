@@ -249,10 +249,11 @@ mod tests {
         bookmark_reader::{
             CompressedJsonReader, JsonReader, ParsedBookmarks, ReadSource, SourceReader,
         },
+        errors::BogrepError,
         test_utils, utils,
     };
     use assert_matches::assert_matches;
-    use std::collections::HashMap;
+    use std::{collections::HashMap, io::Cursor};
     use tempfile::tempdir;
 
     #[test]
@@ -428,6 +429,27 @@ mod tests {
         assert_matches!(parsed_bookmarks, ParsedBookmarks::Json(_));
     }
 
+    #[test]
+    fn test_import_unmatched_folders() {
+        let source_path = Path::new("test_data/bookmarks_firefox.json");
+        let mut reader = utils::open_file(source_path).unwrap();
+        let ParsedBookmarks::Json(parsed_bookmarks) =
+            JsonReader.read_and_parse(&mut reader).unwrap()
+        else {
+            panic!("Unexpected parsed bookmarks");
+        };
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(
+            SourceType::Firefox,
+            source_path,
+            vec!["dev".to_owned(), "unknown".to_owned()],
+        );
+
+        let res = FirefoxReader::new().import(&source, parsed_bookmarks, &mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(source.unmatched_folders(), vec!["unknown"]);
+    }
+
     #[test]
     fn test_read_and_parse_compressed() {
         let source_path = Path::new("test_data/bookmarks_firefox.jsonlz4");
@@ -442,6 +464,28 @@ mod tests {
         assert_matches!(parsed_bookmarks, ParsedBookmarks::Json(_));
     }
 
+    #[test]
+    fn test_read_and_parse_compressed_missing_header() {
+        let compressed_bookmarks = test_utils::compress_bookmarks(b"{}");
+        let source_reader = CompressedJsonReader;
+
+        // Compressed data without the Firefox header
+        let mut reader = Cursor::new(compressed_bookmarks[8..].to_vec());
+        let res = source_reader.read_and_parse(&mut reader);
+        assert_matches!(
+            res.unwrap_err().downcast_ref::<BogrepError>(),
+            Some(BogrepError::InvalidFirefoxBackup)
+        );
+
+        // Truncated file
+        let mut reader = Cursor::new(b"mozLz".to_vec());
+        let res = source_reader.read_and_parse(&mut reader);
+        assert_matches!(
+            res.unwrap_err().downcast_ref::<BogrepError>(),
+            Some(BogrepError::InvalidFirefoxBackup)
+        );
+    }
+
     #[test]
     fn test_import_all() {
         let decompressed_bookmark_path = Path::new("test_data/bookmarks_firefox.json");
@@ -0,0 +1,76 @@
+use super::{ParsedBookmarks, ReadSource, SeekRead};
+use log::debug;
+use scraper::Html;
+use std::io::Read;
+
+/// The doctype of bookmark files exported from browsers in the Netscape
+/// bookmark file format.
+const NETSCAPE_DOCTYPE: &str = "<!DOCTYPE NETSCAPE-Bookmark-file-1>";
+
+/// The number of bytes to be read to detect the Netscape bookmark file format.
+const SNIFF_LEN: u64 = 1024;
+
+/// Reader for html files.
+#[derive(Debug)]
+pub struct HtmlReader;
+
+impl HtmlReader {
+    /// Check if the content starts with the doctype of the Netscape bookmark
+    /// file format, regardless of the file extension.
+    pub fn is_netscape_bookmarks(reader: &mut dyn SeekRead) -> Result<bool, anyhow::Error> {
+        let mut buf = Vec::new();
+        (&mut *reader).take(SNIFF_LEN).read_to_end(&mut buf)?;
+        reader.rewind()?;
+
+        let content = String::from_utf8_lossy(&buf);
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+
+        Ok(content
+            .get(..NETSCAPE_DOCTYPE.len())
+            .is_some_and(|doctype| doctype.eq_ignore_ascii_case(NETSCAPE_DOCTYPE)))
+    }
+}
+
+impl ReadSource for HtmlReader {
+    fn extension(&self) -> Option<&str> {
+        Some("html")
+    }
+
+    fn read_and_parse<'a>(
+        &self,
+        reader: &'a mut dyn SeekRead,
+    ) -> Result<ParsedBookmarks<'a>, anyhow::Error> {
+        debug!("Read file with extension: {:?}", self.extension());
+
+        let mut raw_bookmarks = Vec::new();
+        reader.read_to_end(&mut raw_bookmarks)?;
+
+        let parsed_bookmarks = Html::parse_document(&String::from_utf8_lossy(&raw_bookmarks));
+        Ok(ParsedBookmarks::Html(parsed_bookmarks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_is_netscape_bookmarks() {
+        let mut reader =
+            Cursor::new("\n  <!DOCTYPE NETSCAPE-Bookmark-file-1>\n<TITLE>Bookmarks</TITLE>");
+        let res = HtmlReader::is_netscape_bookmarks(&mut reader);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(res.unwrap());
+        assert_eq!(reader.position(), 0);
+
+        let mut reader = Cursor::new("\u{feff}<!doctype netscape-bookmark-file-1>");
+        assert!(HtmlReader::is_netscape_bookmarks(&mut reader).unwrap());
+
+        let mut reader = Cursor::new("<!DOCTYPE html>");
+        assert!(!HtmlReader::is_netscape_bookmarks(&mut reader).unwrap());
+
+        let mut reader = Cursor::new("https://url1.com");
+        assert!(!HtmlReader::is_netscape_bookmarks(&mut reader).unwrap());
+    }
+}
@@ -1,10 +1,13 @@
 use super::{ParsedBookmarks, ReadSource, SeekRead};
-use crate::{Source, SourceBookmarks};
+use crate::{errors::BogrepError, Source, SourceBookmarks};
 use anyhow::anyhow;
 use log::debug;
 use lz4::block;
 use serde_json::{Map, Value};
 
+/// The non-standard header of compressed json files in Firefox.
+const MOZ_LZ4_HEADER: &[u8] = b"mozLz40\0";
+
 /// Reader for json files.
 #[derive(Debug)]
 pub struct JsonReader;
@@ -85,8 +88,11 @@ impl ReadSource for CompressedJsonReader {
         let mut compressed_data = Vec::new();
         reader.read_to_end(&mut compressed_data)?;
 
-        // Skip the first 8 bytes: "mozLz40\0" (non-standard header)
-        let decompressed_data = block::decompress(&compressed_data[8..], None)?;
+        // Skip the non-standard header.
+        let compressed_data = compressed_data
+            .strip_prefix(MOZ_LZ4_HEADER)
+            .ok_or(BogrepError::InvalidFirefoxBackup)?;
+        let decompressed_data = block::decompress(compressed_data, None)?;
 
         let parsed_bookmarks = serde_json::from_slice(&decompressed_data)?;
         Ok(ParsedBookmarks::Json(parsed_bookmarks))
@@ -116,6 +122,8 @@ pub fn traverse_json(
                     parent_folder = Some(selected_folder.to_owned());
 
                     if source.folders.contains(selected_folder) {
+                        source.match_folder(selected_folder);
+
                         for (_, val) in obj {
                             traverse_children(
                                 val,
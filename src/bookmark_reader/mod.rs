@@ -1,9 +1,13 @@
 mod chrome;
 mod chromium;
+mod csv_reader;
 mod edge;
 mod firefox;
+mod html_reader;
 mod json_reader;
+mod netscape;
 mod plist_reader;
+mod pocket;
 mod safari;
 mod simple;
 mod source_reader;
@@ -14,9 +18,13 @@ mod text_reader;
 
 use crate::{Source, SourceBookmarks, SourceType};
 pub use chromium::ChromiumReader;
+pub use csv_reader::{CsvBookmarkReader, CsvReader};
 pub use firefox::FirefoxReader;
+pub use html_reader::HtmlReader;
 pub use json_reader::{CompressedJsonReader, JsonReader, JsonReaderNoExtension};
+pub use netscape::NetscapeReader;
 pub use plist_reader::PlistReader;
+pub use pocket::PocketReader;
 pub use safari::SafariReader;
 pub use simple::SimpleReader;
 pub use source_reader::SourceReader;
@@ -45,10 +53,10 @@ pub enum SourceOs {
 #[derive(Debug)]
 pub enum ParsedBookmarks<'a> {
     Json(serde_json::Value),
-    #[allow(dead_code)]
     Html(scraper::Html),
     Plist(plist::Value),
     Text(Lines<BufReader<&'a mut dyn SeekRead>>),
+    Csv(csv::Reader<&'a mut dyn SeekRead>),
 }
 
 /// A trait to find the bookmarks directory in the system's directories, and/or
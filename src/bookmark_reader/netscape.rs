@@ -0,0 +1,225 @@
+use super::ReadBookmark;
+use crate::{
+    bookmarks::{Source, SourceBookmarkBuilder},
+    SourceBookmarks, SourceType,
+};
+use anyhow::anyhow;
+use log::debug;
+use scraper::{ElementRef, Html, Selector};
+use std::path::Path;
+
+/// A bookmark reader to read bookmarks in the Netscape bookmark file format,
+/// which is used by browsers to export bookmarks as html file.
+#[derive(Debug)]
+pub struct NetscapeReader;
+
+impl NetscapeReader {
+    pub fn new() -> Box<Self> {
+        Box::new(Self)
+    }
+
+    /// The folders of a bookmark, starting with the innermost folder.
+    ///
+    /// A folder is a `<DT>` element with a `<H3>` heading followed by the
+    /// `<DL>` list of its children.
+    fn select_folders(element: &ElementRef) -> Vec<String> {
+        element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .filter(|ancestor| ancestor.value().name() == "dt")
+            .filter_map(|ancestor| {
+                ancestor
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .find(|child| child.value().name() == "h3")
+            })
+            .map(|heading| heading.text().collect::<String>().trim().to_owned())
+            .collect()
+    }
+}
+
+impl ReadBookmark<'_> for NetscapeReader {
+    type ParsedValue = Html;
+
+    fn name(&self) -> SourceType {
+        SourceType::Netscape
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some("html")
+    }
+
+    fn select_source(
+        &self,
+        _source_path: &Path,
+        _parsed_bookmarks: &Html,
+    ) -> Result<Option<SourceType>, anyhow::Error> {
+        Ok(Some(SourceType::Netscape))
+    }
+
+    fn import(
+        &self,
+        source: &Source,
+        parsed_bookmarks: Html,
+        source_bookmarks: &mut SourceBookmarks,
+    ) -> Result<(), anyhow::Error> {
+        debug!("Import bookmarks from {:#?}", self.name());
+
+        let selector =
+            Selector::parse("a[href]").map_err(|err| anyhow!("Invalid selector: {err}"))?;
+
+        if !source.folders.is_empty() {
+            let folder_selector =
+                Selector::parse("dt > h3").map_err(|err| anyhow!("Invalid selector: {err}"))?;
+
+            for heading in parsed_bookmarks.select(&folder_selector) {
+                source.match_folder(heading.text().collect::<String>().trim());
+            }
+        }
+
+        for element in parsed_bookmarks.select(&selector) {
+            let Some(url) = element.value().attr("href") else {
+                continue;
+            };
+
+            if !url.contains("http") {
+                continue;
+            }
+
+            let folders = Self::select_folders(&element);
+
+            if source.folders.is_empty() {
+                let source_bookmark = SourceBookmarkBuilder::new(url)
+                    .add_source(source.source_type.to_owned())
+                    .build();
+                source_bookmarks.insert(source_bookmark);
+            } else if let Some(folder) = folders
+                .iter()
+                .find(|folder| source.folders.contains(folder))
+            {
+                // The innermost folder which is selected in the source.
+                let source_bookmark = SourceBookmarkBuilder::new(url)
+                    .add_source(source.source_type.to_owned())
+                    .add_folder(source.source_type.to_owned(), folder)
+                    .build();
+                source_bookmarks.insert(source_bookmark);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bookmark_reader::{HtmlReader, ParsedBookmarks, ReadSource, SourceReader},
+        utils,
+    };
+    use assert_matches::assert_matches;
+    use std::{collections::HashMap, path::PathBuf};
+
+    #[test]
+    fn test_read_and_parse() {
+        let source_path = Path::new("test_data/bookmarks_netscape.html");
+        let mut reader = utils::open_file(source_path).unwrap();
+        let source_reader = HtmlReader;
+
+        let res = source_reader.read_and_parse(&mut reader);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let parsed_bookmarks = res.unwrap();
+        assert_matches!(parsed_bookmarks, ParsedBookmarks::Html(_));
+    }
+
+    #[test]
+    fn test_import_unmatched_folders() {
+        let source_path = Path::new("test_data/bookmarks_netscape.html");
+        let mut reader = utils::open_file(source_path).unwrap();
+        let ParsedBookmarks::Html(parsed_bookmarks) =
+            HtmlReader.read_and_parse(&mut reader).unwrap()
+        else {
+            panic!("Unexpected parsed bookmarks");
+        };
+        let mut source_bookmarks = SourceBookmarks::default();
+        // The "Bookmarks bar" folder contains subfolders only.
+        let source = Source::new(
+            SourceType::Netscape,
+            source_path,
+            vec!["Bookmarks bar".to_owned(), "unknown".to_owned()],
+        );
+
+        let res = NetscapeReader::new().import(&source, parsed_bookmarks, &mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(source.unmatched_folders(), vec!["unknown"]);
+        assert_eq!(source_bookmarks.inner().len(), 3);
+    }
+
+    #[test]
+    fn test_import_all() {
+        let source_path = Path::new("test_data/bookmarks_netscape.html");
+        assert!(source_path.exists());
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(SourceType::Unknown, &PathBuf::from("dummy_path"), vec![]);
+        let bookmark_file = utils::open_file(source_path).unwrap();
+        let source_reader = Box::new(HtmlReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://www.deepl.com/translator";
+        let url2 = "https://developer.mozilla.org/en-US/";
+        let url3 = "https://www.rust-lang.org/";
+        let url4 = "https://en.wikipedia.org/wiki/Design_Patterns";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([url1, url2, url3, url4].map(|url| {
+                (
+                    url.to_owned(),
+                    SourceBookmarkBuilder::new(url)
+                        .add_source(SourceType::Netscape)
+                        .build(),
+                )
+            }))
+        );
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let source_path = Path::new("test_data/bookmarks_netscape.html");
+        assert!(source_path.exists());
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(
+            SourceType::Unknown,
+            &PathBuf::from("dummy_path"),
+            vec!["dev".to_owned()],
+        );
+        let bookmark_file = utils::open_file(source_path).unwrap();
+        let source_reader = Box::new(HtmlReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://www.deepl.com/translator";
+        let url2 = "https://developer.mozilla.org/en-US/";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([url1, url2].map(|url| {
+                (
+                    url.to_owned(),
+                    SourceBookmarkBuilder::new(url)
+                        .add_source(SourceType::Netscape)
+                        .add_folder(SourceType::Netscape, "dev")
+                        .build(),
+                )
+            }))
+        );
+    }
+}
@@ -63,6 +63,8 @@ pub fn traverse_plist(
                     parent_folder = Some(selected_folder.to_owned());
 
                     if source.folders.contains(selected_folder) {
+                        source.match_folder(selected_folder);
+
                         for (_, val) in obj {
                             traverse_children(
                                 val,
@@ -0,0 +1,262 @@
+use super::ReadBookmark;
+use crate::{
+    bookmarks::{Source, SourceBookmarkBuilder},
+    SourceBookmarks, SourceType,
+};
+use log::{debug, trace};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// A bookmark reader to read bookmarks from a Pocket export in JSON format.
+///
+/// The tags of a Pocket item are imported as folders.
+#[derive(Debug)]
+pub struct PocketReader;
+
+impl PocketReader {
+    pub fn new() -> Box<Self> {
+        Box::new(Self)
+    }
+
+    fn select_tags(item: &Map<String, Value>) -> Vec<&String> {
+        match item.get("tags") {
+            Some(Value::Object(tags)) => tags
+                .iter()
+                .map(|(name, tag)| match tag.get("tag") {
+                    Some(Value::String(tag)) => tag,
+                    _ => name,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn select_bookmark(
+        item: &Map<String, Value>,
+        source: &Source,
+        source_bookmarks: &mut SourceBookmarks,
+    ) {
+        trace!("json object: {item:#?}");
+
+        if let Some(Value::String(url_value)) = item.get("given_url") {
+            if url_value.contains("http") {
+                let tags = Self::select_tags(item);
+
+                for tag in &tags {
+                    source.match_folder(tag);
+                }
+
+                if !source.folders.is_empty()
+                    && !tags.iter().any(|tag| source.folders.contains(tag))
+                {
+                    return;
+                }
+
+                let mut source_bookmark_builder =
+                    SourceBookmarkBuilder::new(url_value).add_source(source.source_type.to_owned());
+
+                for tag in tags {
+                    source_bookmark_builder =
+                        source_bookmark_builder.add_folder(source.source_type.to_owned(), tag);
+                }
+
+                source_bookmarks.insert(source_bookmark_builder.build());
+            }
+        }
+    }
+}
+
+impl ReadBookmark<'_> for PocketReader {
+    type ParsedValue = serde_json::Value;
+
+    fn name(&self) -> SourceType {
+        SourceType::Pocket
+    }
+
+    fn extension(&self) -> Option<&str> {
+        Some("json")
+    }
+
+    fn select_source(
+        &self,
+        _source_path: &Path,
+        parsed_bookmarks: &Value,
+    ) -> Result<Option<SourceType>, anyhow::Error> {
+        match parsed_bookmarks.get("list") {
+            // Pocket exports an empty list as array.
+            Some(Value::Array(items)) if items.is_empty() => Ok(Some(SourceType::Pocket)),
+            Some(Value::Object(items))
+                if items.is_empty()
+                    || items.values().any(|item| item.get("given_url").is_some()) =>
+            {
+                Ok(Some(SourceType::Pocket))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn import(
+        &self,
+        source: &Source,
+        parsed_bookmarks: Value,
+        source_bookmarks: &mut SourceBookmarks,
+    ) -> Result<(), anyhow::Error> {
+        debug!("Import bookmarks from {:#?}", self.name());
+
+        if let Some(Value::Object(items)) = parsed_bookmarks.get("list") {
+            for item in items.values() {
+                if let Value::Object(item) = item {
+                    Self::select_bookmark(item, source, source_bookmarks);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bookmark_reader::{JsonReader, SourceReader},
+        utils,
+    };
+    use serde_json::json;
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    };
+
+    #[test]
+    fn test_select_source() {
+        let source_path = Path::new("dummy_path");
+        let reader = PocketReader;
+
+        let parsed_bookmarks = json!({
+            "status": 1,
+            "list": {
+                "3914163181": {
+                    "item_id": "3914163181",
+                    "given_url": "https://www.deepl.com/translator"
+                }
+            }
+        });
+        let res = reader.select_source(source_path, &parsed_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), Some(SourceType::Pocket));
+
+        let parsed_bookmarks = json!({ "status": 2, "list": [] });
+        let res = reader.select_source(source_path, &parsed_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), Some(SourceType::Pocket));
+
+        let source_file = utils::read_file(Path::new("test_data/bookmarks_firefox.json")).unwrap();
+        let parsed_bookmarks = serde_json::from_slice::<Value>(&source_file).unwrap();
+        let res = reader.select_source(source_path, &parsed_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), None);
+
+        let source_file = utils::read_file(Path::new("test_data/bookmarks_chromium.json")).unwrap();
+        let parsed_bookmarks = serde_json::from_slice::<Value>(&source_file).unwrap();
+        let res = reader.select_source(source_path, &parsed_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), None);
+    }
+
+    #[test]
+    fn test_import_all() {
+        let source_path = Path::new("test_data/bookmarks_pocket.json");
+        assert!(source_path.exists());
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(SourceType::Unknown, &PathBuf::from("dummy_path"), vec![]);
+        let bookmark_file = utils::open_file(source_path).unwrap();
+        let source_reader = Box::new(JsonReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://www.deepl.com/translator";
+        let url2 =
+            "https://www.quantamagazine.org/how-mathematical-curves-power-cryptography-20220919/";
+        let url3 = "https://en.wikipedia.org/wiki/Design_Patterns";
+        let url4 = "https://doc.rust-lang.org/book/title-page.html";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Pocket)
+                        .add_folder(SourceType::Pocket, "tools")
+                        .build()
+                ),
+                (
+                    url2.to_owned(),
+                    SourceBookmarkBuilder::new(url2)
+                        .add_source(SourceType::Pocket)
+                        .build()
+                ),
+                (
+                    url3.to_owned(),
+                    SourceBookmarkBuilder::new(url3)
+                        .add_source(SourceType::Pocket)
+                        .add_folder(SourceType::Pocket, "dev")
+                        .build()
+                ),
+                (
+                    url4.to_owned(),
+                    SourceBookmarkBuilder::new(url4)
+                        .add_source(SourceType::Pocket)
+                        .add_folder(SourceType::Pocket, "dev")
+                        .build()
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let source_path = Path::new("test_data/bookmarks_pocket.json");
+        assert!(source_path.exists());
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(
+            SourceType::Unknown,
+            &PathBuf::from("dummy_path"),
+            vec!["dev".to_owned()],
+        );
+        let bookmark_file = utils::open_file(source_path).unwrap();
+        let source_reader = Box::new(JsonReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://en.wikipedia.org/wiki/Design_Patterns";
+        let url2 = "https://doc.rust-lang.org/book/title-page.html";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Pocket)
+                        .add_folder(SourceType::Pocket, "dev")
+                        .build()
+                ),
+                (
+                    url2.to_owned(),
+                    SourceBookmarkBuilder::new(url2)
+                        .add_source(SourceType::Pocket)
+                        .add_folder(SourceType::Pocket, "dev")
+                        .build()
+                )
+            ])
+        );
+    }
+}
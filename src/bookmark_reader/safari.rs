@@ -248,6 +248,27 @@ mod test {
         assert_matches!(parsed_bookmarks, ParsedBookmarks::Plist(_));
     }
 
+    #[test]
+    fn test_import_unmatched_folders() {
+        let source_path = Path::new("test_data/bookmarks_safari_xml.plist");
+        let mut reader = utils::open_file(source_path).unwrap();
+        let ParsedBookmarks::Plist(parsed_bookmarks) =
+            PlistReader.read_and_parse(&mut reader).unwrap()
+        else {
+            panic!("Unexpected parsed bookmarks");
+        };
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(
+            SourceType::Safari,
+            source_path,
+            vec!["Others".to_owned(), "unknown".to_owned()],
+        );
+
+        let res = SafariReader::new().import(&source, parsed_bookmarks, &mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(source.unmatched_folders(), vec!["unknown"]);
+    }
+
     #[test]
     fn test_read_and_parse_binary() {
         let source_path = Path::new("test_data/bookmarks_safari_binary.plist");
@@ -3,17 +3,22 @@ use crate::{
     bookmarks::{Source, SourceBookmarkBuilder},
     SourceBookmarks, SourceType,
 };
-use log::debug;
+use log::{debug, warn};
 use std::{
     io::{BufReader, Lines},
     path::Path,
 };
+use url::Url;
 
 pub type LinesReader<'a> = Lines<BufReader<&'a mut dyn SeekRead>>;
 pub type TextBookmarkReader<'a> = Box<dyn ReadBookmark<'a, ParsedValue = LinesReader<'a>>>;
 
 /// A bookmark reader to read bookmarks from a simple text file with one url per
 /// line.
+///
+/// Lines are trimmed, so that CRLF line endings and trailing whitespace are
+/// ignored. Blank lines and comment lines starting with `#` are skipped, and
+/// lines which are not a valid url are skipped with a warning.
 #[derive(Debug)]
 pub struct SimpleReader;
 
@@ -50,15 +55,28 @@ impl<'a> ReadBookmark<'a> for SimpleReader {
     ) -> Result<(), anyhow::Error> {
         debug!("Import bookmarks from {:#?}", self.name());
 
-        for line in parsed_bookmarks {
-            let url = line?;
+        for (line_number, line) in parsed_bookmarks.enumerate() {
+            let line = line?;
+            let url = line.trim();
 
-            if !url.is_empty() {
-                let source_bookmark = SourceBookmarkBuilder::new(&url)
-                    .add_source(source.source_type.to_owned())
-                    .build();
-                source_bookmarks.insert(source_bookmark);
+            if url.is_empty() || url.starts_with('#') {
+                continue;
             }
+
+            if let Err(err) = Url::parse(url) {
+                warn!(
+                    "Skip invalid url '{url}' in line {} of {}: {err}",
+                    line_number + 1,
+                    source.path.display()
+                );
+                continue;
+            }
+
+            let source_bookmark = SourceBookmarkBuilder::new(url)
+                .add_source(source.source_type.to_owned())
+                .add_folder_opt(source.source_type.to_owned(), source.folder.as_ref())
+                .build();
+            source_bookmarks.insert(source_bookmark);
         }
 
         Ok(())
@@ -76,6 +94,7 @@ mod tests {
     use assert_matches::assert_matches;
     use std::{
         collections::HashMap,
+        io::Cursor,
         path::{Path, PathBuf},
     };
 
@@ -142,4 +161,44 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn test_import_txt_comments_and_blank_lines() {
+        let content = "# Reading list\r\n\
+                       https://url1.com/page \r\n\
+                       \r\n\
+                       \t# https://url2.com/page\r\n\
+                       not a url\r\n\
+                       \thttps://url3.com/page\r\n\
+                       \n";
+        let mut source_bookmarks = SourceBookmarks::default();
+        let source = Source::new(SourceType::Unknown, &PathBuf::from("dummy_path"), vec![]);
+        let bookmark_file = Cursor::new(content.as_bytes().to_vec());
+        let source_reader = Box::new(TextReader);
+        let mut source_reader = SourceReader::new(source, Box::new(bookmark_file), source_reader);
+
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let url1 = "https://url1.com/page";
+        let url3 = "https://url3.com/page";
+
+        assert_eq!(
+            source_bookmarks.inner(),
+            HashMap::from_iter([
+                (
+                    url1.to_owned(),
+                    SourceBookmarkBuilder::new(url1)
+                        .add_source(SourceType::Simple)
+                        .build()
+                ),
+                (
+                    url3.to_owned(),
+                    SourceBookmarkBuilder::new(url3)
+                        .add_source(SourceType::Simple)
+                        .build()
+                ),
+            ])
+        );
+    }
 }
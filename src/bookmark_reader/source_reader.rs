@@ -1,18 +1,26 @@
 use super::{
     chrome::ChromeSelector,
     chromium::{ChromiumSelector, JsonBookmarkReader},
+    csv_reader::CsvRecords,
     edge::EdgeSelector,
     firefox::FirefoxSelector,
     safari::{PlistBookmarkReader, SafariSelector},
     simple::TextBookmarkReader,
-    BookmarkReader, ChromiumReader, CompressedJsonReader, FirefoxReader, JsonReader,
-    JsonReaderNoExtension, ParsedBookmarks, PlistReader, ReadSource, SafariReader, SeekRead,
-    SimpleReader, SourceOs, SourceSelector, TextReader,
+    BookmarkReader, ChromiumReader, CompressedJsonReader, CsvBookmarkReader, CsvReader,
+    FirefoxReader, HtmlReader, JsonReader, JsonReaderNoExtension, NetscapeReader, ParsedBookmarks,
+    PlistReader, PocketReader, ReadSource, SafariReader, SeekRead, SimpleReader, SourceOs,
+    SourceSelector, TextReader,
 };
-use crate::{bookmarks::RawSource, utils, Source, SourceBookmarks, SourceType};
+use crate::{bookmarks::RawSource, utils, Source, SourceBookmarks, SourceFormat, SourceType};
 use anyhow::anyhow;
-use log::debug;
-use std::path::Path;
+use log::{debug, warn};
+use scraper::Html;
+use std::{
+    env, fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
 
 pub struct SourceSelectors([SourceSelector; 5]);
 
@@ -54,20 +62,33 @@ impl SourceReader {
         home_dir: &Path,
         source_os: &SourceOs,
     ) -> Result<Vec<RawSource>, anyhow::Error> {
-        let mut source_dirs = vec![];
+        let raw_sources = Self::discover_sources(home_dir, source_os)?
+            .into_iter()
+            .map(|(_, source_dir)| RawSource::new(source_dir, vec![]))
+            .collect();
+
+        Ok(raw_sources)
+    }
+
+    /// Find the sources of the supported browsers in the home directory,
+    /// together with the detected source type.
+    pub fn discover_sources(
+        home_dir: &Path,
+        source_os: &SourceOs,
+    ) -> Result<Vec<(SourceType, PathBuf)>, anyhow::Error> {
+        let mut sources = vec![];
         let source_selectors = SourceSelectors::new();
 
         for source_selector in source_selectors.0 {
             let source_dirs_by_selector = source_selector.find_sources(home_dir, source_os)?;
-            source_dirs.extend(source_dirs_by_selector);
+            sources.extend(
+                source_dirs_by_selector
+                    .into_iter()
+                    .map(|source_dir| (source_selector.name(), source_dir)),
+            );
         }
 
-        let raw_sources = source_dirs
-            .into_iter()
-            .map(|source_dir| RawSource::new(source_dir, vec![]))
-            .collect();
-
-        Ok(raw_sources)
+        Ok(sources)
     }
 
     /// Select the source file if a source directory is given.
@@ -86,9 +107,9 @@ impl SourceReader {
 
                     if bookmarks_path.is_file() && source_selector.extension() == source_extension {
                         let source =
-                            Source::new(source_selector.name(), &bookmarks_path, folders.clone());
-                        let bookmark_file = utils::open_file(&bookmarks_path)?;
-                        let reader = Box::new(bookmark_file);
+                            Source::new(source_selector.name(), &bookmarks_path, folders.clone())
+                                .with_label(raw_source.label.clone());
+                        let reader = Self::open_source(&bookmarks_path)?;
                         let source_reader = Self::select(source_extension)?;
                         return Ok(Self::new(source, reader, source_reader));
                     }
@@ -96,10 +117,13 @@ impl SourceReader {
             }
         } else if source_path.is_file() {
             let source_extension = source_path.extension().and_then(|path| path.to_str());
-            let source = Source::new(SourceType::Unknown, source_path, folders.clone());
-            let bookmark_file = utils::open_file(&raw_source.path)?;
-            let reader = Box::new(bookmark_file);
-            let source_reader = Self::select(source_extension)?;
+            let source = Source::new(SourceType::Unknown, source_path, folders.clone())
+                .with_label(raw_source.label.clone());
+            let reader = Self::open_source(&raw_source.path)?;
+            let source_reader = match &raw_source.format {
+                Some(format) => Self::select_format(format)?,
+                None => Self::select(source_extension)?,
+            };
             return Ok(Self::new(source.clone(), reader, source_reader));
         }
 
@@ -109,33 +133,105 @@ impl SourceReader {
         ))
     }
 
+    /// Select the source files of a source.
+    ///
+    /// A directory without bookmarks of a supported browser is imported as a
+    /// directory of text files with one url per line, and the bookmarks of
+    /// each text file are assigned to a folder named after the file.
+    pub fn init_all(raw_source: &RawSource) -> Result<Vec<Self>, anyhow::Error> {
+        match Self::init(raw_source) {
+            Ok(source_reader) => Ok(vec![source_reader]),
+            Err(err) if raw_source.path.is_dir() => {
+                let text_paths = Self::find_text_files(&raw_source.path)?;
+
+                if text_paths.is_empty() {
+                    return Err(err);
+                }
+
+                text_paths
+                    .iter()
+                    .map(|text_path| {
+                        let folder = text_path
+                            .file_stem()
+                            .map(|file_stem| file_stem.to_string_lossy().into_owned());
+                        let source =
+                            Source::new(SourceType::Simple, text_path, raw_source.folders.clone())
+                                .with_label(raw_source.label.clone())
+                                .with_folder(folder);
+                        let reader = Self::open_source(text_path)?;
+                        Ok(Self::new(source, reader, Box::new(TextReader)))
+                    })
+                    .collect()
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Find the text files in a directory, sorted by path.
+    fn find_text_files(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+        let mut text_paths = vec![];
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_file() && path.extension().is_some_and(|extension| extension == "txt") {
+                text_paths.push(path);
+            }
+        }
+
+        text_paths.sort();
+        Ok(text_paths)
+    }
+
+    /// Open the source file.
+    ///
+    /// If the source file can't be opened, e.g. because it is locked by a
+    /// running browser on Windows, the file is copied to a temporary path and
+    /// read from there.
+    fn open_source(source_path: &Path) -> Result<Box<dyn SeekRead>, anyhow::Error> {
+        match utils::open_file(source_path) {
+            Ok(bookmark_file) => Ok(Box::new(bookmark_file)),
+            Err(err) => {
+                warn!("{err}; reading a copy of the bookmark file");
+                let copy_path = env::temp_dir().join(format!("bogrep-{}", Uuid::new_v4()));
+
+                if fs::copy(source_path, &copy_path).is_err() {
+                    return Err(err.into());
+                }
+
+                let content = utils::read_file(&copy_path);
+                utils::remove_file(&copy_path)?;
+                Ok(Box::new(Cursor::new(content?)))
+            }
+        }
+    }
+
     pub fn source(&self) -> &Source {
         &self.source
     }
 
     pub fn import(&mut self, source_bookmarks: &mut SourceBookmarks) -> Result<(), anyhow::Error> {
         let raw_source = self.source().clone();
-        let source_path = &raw_source.path;
-        let folders = &raw_source.folders;
         let parsed_bookmarks = self.read_and_parse()?;
 
         match parsed_bookmarks {
             ParsedBookmarks::Text(parsed_bookmarks) => {
                 let bookmark_readers: Vec<TextBookmarkReader> = vec![SimpleReader::new()];
                 Self::import_by_source(
-                    source_path,
-                    folders,
+                    &raw_source,
                     source_bookmarks,
                     parsed_bookmarks,
                     bookmark_readers,
                 )?;
             }
             ParsedBookmarks::Json(parsed_bookmarks) => {
-                let bookmark_readers: Vec<JsonBookmarkReader> =
-                    vec![FirefoxReader::new(), ChromiumReader::new()];
+                let bookmark_readers: Vec<JsonBookmarkReader> = vec![
+                    FirefoxReader::new(),
+                    ChromiumReader::new(),
+                    PocketReader::new(),
+                ];
                 Self::import_by_source(
-                    source_path,
-                    folders,
+                    &raw_source,
                     source_bookmarks,
                     parsed_bookmarks,
                     bookmark_readers,
@@ -144,15 +240,30 @@ impl SourceReader {
             ParsedBookmarks::Plist(parsed_bookmarks) => {
                 let bookmark_readers: Vec<PlistBookmarkReader> = vec![SafariReader::new()];
                 Self::import_by_source(
-                    source_path,
-                    folders,
+                    &raw_source,
                     source_bookmarks,
                     parsed_bookmarks,
                     bookmark_readers,
                 )?;
             }
-            ParsedBookmarks::Html(_parsed_bookmarks) => {
-                return Err(anyhow!("Bookmarks in HTML format not supported"));
+            ParsedBookmarks::Csv(parsed_bookmarks) => {
+                let bookmark_readers: Vec<BookmarkReader<CsvRecords>> =
+                    vec![CsvBookmarkReader::new()];
+                Self::import_by_source(
+                    &raw_source,
+                    source_bookmarks,
+                    parsed_bookmarks,
+                    bookmark_readers,
+                )?;
+            }
+            ParsedBookmarks::Html(parsed_bookmarks) => {
+                let bookmark_readers: Vec<BookmarkReader<Html>> = vec![NetscapeReader::new()];
+                Self::import_by_source(
+                    &raw_source,
+                    source_bookmarks,
+                    parsed_bookmarks,
+                    bookmark_readers,
+                )?;
             }
         }
 
@@ -160,23 +271,47 @@ impl SourceReader {
     }
 
     fn read_and_parse(&mut self) -> Result<ParsedBookmarks, anyhow::Error> {
+        // Bookmarks exported from browsers are parsed as html regardless of
+        // the file extension.
+        if HtmlReader::is_netscape_bookmarks(&mut self.reader)? {
+            debug!("Detected bookmark file in Netscape format");
+            return HtmlReader.read_and_parse(&mut self.reader);
+        }
+
         let parsed_bookmarks = self.source_reader.read_and_parse(&mut self.reader)?;
         Ok(parsed_bookmarks)
     }
 
     fn import_by_source<P>(
-        source_path: &Path,
-        folders: &[String],
+        raw_source: &Source,
         source_bookmarks: &mut SourceBookmarks,
         parsed_bookmarks: P,
         bookmark_readers: Vec<BookmarkReader<P>>,
     ) -> Result<(), anyhow::Error> {
+        let source_path = &raw_source.path;
+
         for bookmark_reader in bookmark_readers {
             if let Some(source_type) =
                 bookmark_reader.select_source(source_path, &parsed_bookmarks)?
             {
-                let source = Source::new(source_type, source_path, folders.to_vec());
+                let source_type = match &raw_source.label {
+                    Some(label) => SourceType::Custom(label.to_owned()),
+                    None => source_type,
+                };
+                let source = Source::new(source_type, source_path, raw_source.folders.clone())
+                    .with_folder(raw_source.folder.clone());
                 bookmark_reader.import(&source, parsed_bookmarks, source_bookmarks)?;
+
+                let unmatched_folders = source.unmatched_folders();
+
+                if !unmatched_folders.is_empty() {
+                    warn!(
+                        "Folders not found in source {}: {}",
+                        source_path.display(),
+                        unmatched_folders.join(", ")
+                    );
+                }
+
                 break;
             }
         }
@@ -187,22 +322,41 @@ impl SourceReader {
     fn select(source_extension: Option<&str>) -> Result<Box<dyn ReadSource>, anyhow::Error> {
         match source_extension {
             Some("txt") => Ok(Box::new(TextReader)),
+            Some("csv") => Ok(Box::new(CsvReader)),
             Some("json") => Ok(Box::new(JsonReader)),
             Some("jsonlz4") => Ok(Box::new(CompressedJsonReader)),
             Some("plist") => Ok(Box::new(PlistReader)),
+            Some("html") | Some("htm") => Ok(Box::new(HtmlReader)),
+            // Chromium's backup of the bookmarks file.
+            Some("bak") => Ok(Box::new(JsonReaderNoExtension)),
             Some(others) => Err(anyhow!(format!("File type {others} not supported"))),
             // Chrome's bookmarks in json format are provided without file
             // extension.
             None => Ok(Box::new(JsonReaderNoExtension)),
         }
     }
+
+    /// Select the reader for the given format, regardless of the file
+    /// extension.
+    fn select_format(format: &SourceFormat) -> Result<Box<dyn ReadSource>, anyhow::Error> {
+        debug!("Select reader for format {format}");
+
+        match format {
+            SourceFormat::Json => Ok(Box::new(JsonReader)),
+            SourceFormat::Jsonlz4 => Ok(Box::new(CompressedJsonReader)),
+            SourceFormat::Plist => Ok(Box::new(PlistReader)),
+            SourceFormat::Txt => Ok(Box::new(TextReader)),
+            SourceFormat::Csv => Ok(Box::new(CsvReader)),
+            SourceFormat::Html => Ok(Box::new(HtmlReader)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils;
-    use std::path::Path;
+    use crate::{test_utils, SourceFolder};
+    use std::{collections::HashSet, path::Path};
     use tempfile::tempdir;
 
     #[cfg(not(any(target_os = "windows")))]
@@ -230,7 +384,7 @@ mod tests {
         test_utils::tests::create_test_files(temp_path, &source_os);
 
         let sources = SourceReader::select_sources(temp_path, &source_os).unwrap();
-        assert_eq!(sources.len(), 5);
+        assert_eq!(sources.len(), 7);
     }
 
     #[cfg(target_os = "windows")]
@@ -327,6 +481,19 @@ mod tests {
         assert_eq!(source_reader.source_reader.extension(), None);
     }
 
+    #[test]
+    fn test_init_chrome_backup() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().join("Bookmarks.bak");
+        std::fs::copy("test_data/bookmarks_chromium_no_extension", &source_path).unwrap();
+        let folders = vec![];
+        let raw_source = RawSource::new(&source_path, folders);
+        let source_reader = SourceReader::init(&raw_source).unwrap();
+        let source = source_reader.source();
+        assert_eq!(source.path, source_path);
+        assert_eq!(source_reader.source_reader.extension(), None);
+    }
+
     #[test]
     fn test_init_simple() {
         let source_path = Path::new("test_data/bookmarks_simple.txt");
@@ -339,4 +506,117 @@ mod tests {
         assert_eq!(source.path, source_path);
         assert_eq!(source_reader.source_reader.extension(), Some("txt"));
     }
+
+    #[test]
+    fn test_init_format() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().join("bookmarks.export");
+        std::fs::copy("test_data/bookmarks_firefox.json", &source_path).unwrap();
+        let raw_source = RawSource::new(&source_path, vec![]);
+        let res = SourceReader::init(&raw_source);
+        assert!(res.is_err());
+
+        let raw_source = raw_source.with_format(Some(SourceFormat::Json));
+        let mut source_reader = SourceReader::init(&raw_source).unwrap();
+        assert_eq!(source_reader.source_reader.extension(), Some("json"));
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(!source_bookmarks.inner().is_empty());
+    }
+
+    #[test]
+    fn test_import_label() {
+        let source_path = Path::new("test_data/bookmarks_simple.txt");
+        let raw_source = RawSource::new(source_path, vec![]).with_label(Some("work".to_owned()));
+        let mut source_reader = SourceReader::init(&raw_source).unwrap();
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let source_bookmarks = source_bookmarks.inner();
+        assert!(!source_bookmarks.is_empty());
+        assert!(source_bookmarks.values().all(|source_bookmark| {
+            source_bookmark.sources() == &HashSet::from([SourceType::Custom("work".to_owned())])
+        }));
+    }
+
+    #[test]
+    fn test_import_netscape_misleading_extension() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path().join("bookmarks.txt");
+        std::fs::copy("test_data/bookmarks_netscape.html", &source_path).unwrap();
+        let raw_source = RawSource::new(&source_path, vec![]);
+        let mut source_reader = SourceReader::init(&raw_source).unwrap();
+        assert_eq!(source_reader.source_reader.extension(), Some("txt"));
+
+        let mut source_bookmarks = SourceBookmarks::default();
+        let res = source_reader.import(&mut source_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let source_bookmarks = source_bookmarks.inner();
+        assert_eq!(source_bookmarks.len(), 4);
+        assert!(source_bookmarks
+            .values()
+            .all(|source_bookmark| source_bookmark.sources().contains(&SourceType::Netscape)));
+    }
+    #[test]
+    fn test_import_text_directory() {
+        let temp_dir = tempdir().unwrap();
+        let source_path = temp_dir.path();
+        fs::write(
+            source_path.join("rust.txt"),
+            "https://doc.rust-lang.org/book/title-page.html\nhttps://www.rust-lang.org/\n",
+        )
+        .unwrap();
+        fs::write(
+            source_path.join("science.txt"),
+            "https://www.quantamagazine.org/\nhttps://www.rust-lang.org/\n",
+        )
+        .unwrap();
+        // Files which aren't text files are skipped.
+        fs::write(source_path.join("notes.md"), "https://url1.com\n").unwrap();
+        let raw_source = RawSource::new(source_path, vec![]);
+
+        let source_readers = SourceReader::init_all(&raw_source).unwrap();
+        assert_eq!(source_readers.len(), 2);
+
+        let mut source_bookmarks = SourceBookmarks::default();
+
+        for mut source_reader in source_readers {
+            let res = source_reader.import(&mut source_bookmarks);
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+        }
+
+        let folder = |name: &str| SourceFolder::new(SourceType::Simple, name.to_owned());
+        let source_bookmarks = source_bookmarks.inner();
+        assert_eq!(source_bookmarks.len(), 3);
+        assert_eq!(
+            source_bookmarks["https://doc.rust-lang.org/book/title-page.html"].folders(),
+            &HashSet::from([folder("rust")])
+        );
+        assert_eq!(
+            source_bookmarks["https://www.quantamagazine.org/"].folders(),
+            &HashSet::from([folder("science")])
+        );
+        // Bookmarks in multiple files are assigned to multiple folders.
+        assert_eq!(
+            source_bookmarks["https://www.rust-lang.org/"].folders(),
+            &HashSet::from([folder("rust"), folder("science")])
+        );
+        assert!(source_bookmarks.values().all(
+            |source_bookmark| source_bookmark.sources() == &HashSet::from([SourceType::Simple])
+        ));
+    }
+
+    #[test]
+    fn test_init_all_empty_directory() {
+        let temp_dir = tempdir().unwrap();
+        let raw_source = RawSource::new(temp_dir.path(), vec![]);
+
+        let res = SourceReader::init_all(&raw_source);
+        assert!(res.is_err());
+    }
 }
@@ -141,13 +141,21 @@ mod tests {
                     TargetBookmark {
                         id: String::from("a87f7024-a7f5-4f9c-8a71-f64880b2f275"),
                         url: Url::parse("https://url1.com").unwrap(),
+                        resolved_url: None,
+                        title: None,
                         underlying_url: None,
                         underlying_type: UnderlyingType::None,
                         last_imported: 1694989714351,
                         last_cached: None,
+                        removed_at: None,
                         sources: HashSet::new(),
                         source_folders: HashSet::new(),
                         cache_modes: HashSet::new(),
+                        content_hash: None,
+                        last_status: None,
+                        etag: None,
+                        last_modified: None,
+                        filename: None,
                         status: Status::None,
                         action: Action::None,
                     }
@@ -157,13 +165,21 @@ mod tests {
                     TargetBookmark {
                         id: String::from("511b1590-e6de-4989-bca4-96dc61730508"),
                         url: Url::parse("https://url2.com").unwrap(),
+                        resolved_url: None,
+                        title: None,
                         underlying_url: None,
                         underlying_type: UnderlyingType::None,
                         last_imported: 1694989714351,
                         last_cached: None,
+                        removed_at: None,
                         sources: HashSet::new(),
                         source_folders: HashSet::new(),
                         cache_modes: HashSet::new(),
+                        content_hash: None,
+                        last_status: None,
+                        etag: None,
+                        last_modified: None,
+                        filename: None,
                         status: Status::None,
                         action: Action::None,
                     }
@@ -191,13 +207,21 @@ mod tests {
                 TargetBookmark {
                     id: String::from("a87f7024-a7f5-4f9c-8a71-f64880b2f275"),
                     url: Url::parse("https://url1.com").unwrap(),
+                    resolved_url: None,
+                    title: None,
                     underlying_url: None,
                     underlying_type: UnderlyingType::None,
                     last_imported: 1694989714351,
                     last_cached: None,
+                    removed_at: None,
                     sources: HashSet::new(),
                     source_folders: HashSet::new(),
                     cache_modes: HashSet::new(),
+                    content_hash: None,
+                    last_status: None,
+                    etag: None,
+                    last_modified: None,
+                    filename: None,
                     status: Status::None,
                     action: Action::None,
                 },
@@ -207,13 +231,21 @@ mod tests {
                 TargetBookmark {
                     id: String::from("511b1590-e6de-4989-bca4-96dc61730508"),
                     url: Url::parse("https://url2.com").unwrap(),
+                    resolved_url: None,
+                    title: None,
                     underlying_url: None,
                     underlying_type: UnderlyingType::None,
                     last_imported: 1694989714351,
                     last_cached: None,
+                    removed_at: None,
                     sources: HashSet::new(),
                     source_folders: HashSet::new(),
                     cache_modes: HashSet::new(),
+                    content_hash: None,
+                    last_status: None,
+                    etag: None,
+                    last_modified: None,
+                    filename: None,
                     status: Status::None,
                     action: Action::None,
                 },
@@ -227,6 +259,40 @@ mod tests {
         assert_eq!(String::from_utf8(actual).unwrap(), EXPECTED_BOOKMARKS);
     }
 
+    #[test]
+    fn test_read_write_target_bookmarks_last_status() {
+        let bookmarks = r#"{
+    "bookmarks": [
+        {
+            "id": "a87f7024-a7f5-4f9c-8a71-f64880b2f275",
+            "url": "https://url1.com/",
+            "last_imported": 1694989714351,
+            "last_cached": null,
+            "sources": [],
+            "cache_modes": [],
+            "last_status": "status 404"
+        }
+    ]
+}"#;
+        let mut target_bookmarks = TargetBookmarks::default();
+        let mut target_reader_writer = Cursor::new(bookmarks.as_bytes().to_vec());
+
+        let res = target_reader_writer.read_target(&mut target_bookmarks);
+        assert!(res.is_ok());
+
+        let bookmark = target_bookmarks
+            .get(&Url::parse("https://url1.com").unwrap())
+            .unwrap();
+        assert_eq!(bookmark.last_status(), Some("status 404"));
+
+        let mut target_reader_writer = Cursor::new(Vec::new());
+        let res = target_reader_writer.write_target(&target_bookmarks);
+        assert!(res.is_ok());
+
+        let actual = target_reader_writer.into_inner();
+        assert_eq!(String::from_utf8(actual).unwrap(), bookmarks);
+    }
+
     #[test]
     fn test_write_target_bookmarks_empty() {
         let bookmarks = TargetBookmarks::default();
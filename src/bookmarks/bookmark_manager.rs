@@ -7,13 +7,35 @@ use crate::{
     TargetBookmarks,
 };
 use chrono::{DateTime, Utc};
-use log::{trace, warn};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use log::{debug, trace, warn};
+use std::{
+    io::{self, IsTerminal},
+    time::Duration,
+};
 use url::Url;
 
 #[derive(Debug)]
 pub struct BookmarkManager {
     target_bookmarks: TargetBookmarks,
     source_readers: Vec<SourceReader>,
+    /// The number of sources which were skipped because they can't be read.
+    skipped_sources: usize,
+    /// Keep the bookmarks which are missing in the sources instead of
+    /// removing them.
+    no_remove: bool,
+    /// Mark the bookmarks which are missing in the sources as removed, and
+    /// keep them together with their cache until they are cleaned up.
+    soft_delete: bool,
+    /// The number of bookmarks which were marked as removed.
+    soft_deleted: usize,
+    /// The query parameters which are stripped from the urls of the source
+    /// bookmarks.
+    strip_query: Vec<String>,
+    /// The query parameters which are kept when stripping the query.
+    include_query: Vec<String>,
+    /// Show a spinner while importing the sources.
+    show_progress_bar: bool,
     target_reader_writer: Box<dyn ReadWriteTarget>,
 }
 
@@ -22,10 +44,48 @@ impl BookmarkManager {
         Self {
             target_bookmarks: TargetBookmarks::default(),
             source_readers: vec![],
+            skipped_sources: 0,
+            no_remove: false,
+            soft_delete: false,
+            soft_deleted: 0,
+            strip_query: vec![],
+            include_query: vec![],
+            show_progress_bar: false,
             target_reader_writer: reader_writer,
         }
     }
 
+    /// Keep the bookmarks which are missing in the sources instead of
+    /// removing them, i.e. only add new bookmarks when importing.
+    pub fn with_no_remove(mut self, no_remove: bool) -> Self {
+        self.no_remove = no_remove;
+        self
+    }
+
+    /// Mark the bookmarks which are missing in the sources as removed, and
+    /// keep them together with their cache until they are cleaned up via
+    /// `bogrep clean --expired`.
+    pub fn with_soft_delete(mut self, soft_delete: bool) -> Self {
+        self.soft_delete = soft_delete;
+        self
+    }
+
+    /// Strip the given query parameters from the urls of the source
+    /// bookmarks, unless they are included, e.g. to remove tracking
+    /// parameters like `utm_*`.
+    pub fn with_strip_query(mut self, strip_query: &[String], include_query: &[String]) -> Self {
+        self.strip_query = strip_query.to_vec();
+        self.include_query = include_query.to_vec();
+        self
+    }
+
+    /// Show a spinner with the number of parsed bookmarks while importing
+    /// the sources if stdout is a terminal.
+    pub fn with_progress_bar(mut self, show_progress_bar: bool) -> Self {
+        self.show_progress_bar = show_progress_bar && io::stdout().is_terminal();
+        self
+    }
+
     pub fn target_bookmarks(&self) -> &TargetBookmarks {
         &self.target_bookmarks
     }
@@ -38,12 +98,20 @@ impl BookmarkManager {
         self.target_reader_writer
     }
 
+    /// Add the source files to import the bookmarks from.
+    ///
+    /// Sources which can't be read are skipped.
     pub fn add_sources(&mut self, sources: &[RawSource]) -> Result<(), anyhow::Error> {
-        let source_readers = sources
-            .iter()
-            .map(SourceReader::init)
-            .collect::<Result<Vec<_>, anyhow::Error>>()?;
-        self.source_readers.extend(source_readers);
+        for source in sources {
+            match SourceReader::init_all(source) {
+                Ok(source_readers) => self.source_readers.extend(source_readers),
+                Err(err) => {
+                    warn!("Skip source {}: {err}", source.path.display());
+                    self.skipped_sources += 1;
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -55,12 +123,54 @@ impl BookmarkManager {
         if !self.source_readers.is_empty() {
             let mut source_bookmarks = SourceBookmarks::default();
 
+            let progress_bar = self.show_progress_bar.then(|| {
+                let progress_bar =
+                    ProgressBar::with_draw_target(None, ProgressDrawTarget::stdout());
+                progress_bar.set_style(
+                    ProgressStyle::with_template("{spinner} {msg}")
+                        .expect("Invalid progress bar template"),
+                );
+                progress_bar.enable_steady_tick(Duration::from_millis(100));
+                progress_bar
+            });
+
             for source_reader in self.source_readers.iter_mut() {
+                if let Some(progress_bar) = &progress_bar {
+                    progress_bar.set_message(format!(
+                        "Importing bookmarks from {} ({} parsed)",
+                        source_reader.source().path.display(),
+                        source_bookmarks.len()
+                    ));
+                }
+
                 source_reader.import(&mut source_bookmarks)?;
             }
 
+            if let Some(progress_bar) = progress_bar {
+                progress_bar.finish_and_clear();
+            }
+
+            debug!("Parsed {} source bookmarks", source_bookmarks.len());
+
+            if !self.strip_query.is_empty() {
+                source_bookmarks.strip_query(&self.strip_query, &self.include_query);
+            }
+
             self.add_bookmarks(&source_bookmarks, now)?;
-            self.remove_bookmarks(&source_bookmarks);
+            self.restore_bookmarks(&source_bookmarks);
+
+            // The bookmarks of skipped sources are missing in the source
+            // bookmarks, so we can't tell which bookmarks were removed.
+            if self.no_remove {
+                trace!("Keep bookmarks which are missing in sources");
+            } else if self.skipped_sources == 0 {
+                self.remove_bookmarks(&source_bookmarks, now);
+            } else {
+                warn!(
+                    "Skipped {} sources, keeping bookmarks which are missing in sources",
+                    self.skipped_sources
+                );
+            }
         }
 
         Ok(())
@@ -162,7 +272,14 @@ impl BookmarkManager {
             println!("Removed {removed_count} bookmarks{dry_run_str}");
         }
 
-        if added_bookmarks.is_empty() && removed_bookmarks.is_empty() {
+        if self.soft_deleted > 0 {
+            println!(
+                "Marked {} bookmarks as removed{dry_run_str}",
+                self.soft_deleted
+            );
+        }
+
+        if added_bookmarks.is_empty() && removed_bookmarks.is_empty() && self.soft_deleted == 0 {
             println!("Bookmarks are already up to date");
         }
 
@@ -205,7 +322,9 @@ impl BookmarkManager {
     }
 
     /// Remove the difference between source and target bookmarks.
-    fn remove_bookmarks(&mut self, source_bookmarks: &SourceBookmarks) {
+    ///
+    /// In soft delete mode, the bookmarks are marked as removed instead.
+    fn remove_bookmarks(&mut self, source_bookmarks: &SourceBookmarks, now: DateTime<Utc>) {
         let bookmarks_to_remove =
             Self::filter_to_remove(source_bookmarks, &mut self.target_bookmarks);
         trace!(
@@ -217,10 +336,45 @@ impl BookmarkManager {
         );
 
         for bookmark in bookmarks_to_remove {
-            bookmark.set_status(Status::Removed);
+            if !self.soft_delete {
+                bookmark.set_status(Status::Removed);
+            } else if bookmark.removed_at().is_none() {
+                bookmark.set_removed_at(now);
+                self.soft_deleted += 1;
+            }
+        }
+    }
+
+    /// Restore the bookmarks which were marked as removed but are present in
+    /// the sources again.
+    fn restore_bookmarks(&mut self, source_bookmarks: &SourceBookmarks) {
+        for (url, bookmark) in self.target_bookmarks.iter_mut() {
+            if bookmark.removed_at().is_some() && source_bookmarks.contains_key(url.as_str()) {
+                trace!("Restore bookmark: {url}");
+                bookmark.unset_removed_at();
+            }
         }
     }
 
+    /// Mark the bookmarks as [`Status::Removed`] which were marked as removed
+    /// before the given time, and return the number of expired bookmarks.
+    pub fn expire_bookmarks(&mut self, removed_before: DateTime<Utc>) -> usize {
+        let removed_before = removed_before.timestamp_millis();
+        let mut expired = 0;
+
+        for bookmark in self.target_bookmarks.values_mut() {
+            if bookmark
+                .removed_at()
+                .is_some_and(|removed_at| removed_at <= removed_before)
+            {
+                bookmark.set_status(Status::Removed);
+                expired += 1;
+            }
+        }
+
+        expired
+    }
+
     /// Filter the `SourceBookmarks` which should be added to the `TargetBookmarks`.
     fn filter_to_add<'a>(
         source_bookmarks: &'a SourceBookmarks,
@@ -264,6 +418,7 @@ pub mod tests {
         bookmarks::{RawSource, SourceBookmarkBuilder},
         json, JsonBookmarks, Settings, UnderlyingType,
     };
+    use chrono::TimeDelta;
     use std::{
         collections::{HashMap, HashSet},
         io::{Cursor, Write},
@@ -308,6 +463,158 @@ pub mod tests {
         ));
     }
 
+    #[test]
+    fn test_import_source_unreadable() {
+        let now = Utc::now();
+        let url = Url::parse("https://url.com").unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(
+            TargetBookmarkBuilder::new(url.clone(), now)
+                .add_source(SourceType::Firefox)
+                .with_status(Status::None)
+                .build(),
+        );
+        let sources = vec![
+            RawSource::new(Path::new("test_data/missing_bookmarks.json"), vec![]),
+            RawSource::new(Path::new("test_data/bookmarks_simple.txt"), vec![]),
+        ];
+        let target_reader_writer = create_target_reader_writer(&target_bookmarks);
+        let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+
+        let res = bookmark_manager.add_sources(&sources);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(bookmark_manager.source_readers.len(), 1);
+        assert_eq!(bookmark_manager.skipped_sources, 1);
+
+        let res = bookmark_manager.import(now);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        assert!(bookmark_manager.target_bookmarks.contains_key(
+            &Url::from_str("https://en.wikipedia.org/wiki/Design_Patterns").unwrap()
+        ));
+        // The bookmark could belong to the skipped source.
+        assert_eq!(
+            bookmark_manager
+                .target_bookmarks
+                .get(&url)
+                .unwrap()
+                .status(),
+            &Status::None
+        );
+    }
+
+    #[test]
+    fn test_import_source_no_remove() {
+        let now = Utc::now();
+        let url = Url::parse("https://url.com").unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(
+            TargetBookmarkBuilder::new(url.clone(), now)
+                .add_source(SourceType::Simple)
+                .with_status(Status::None)
+                .build(),
+        );
+        let sources = vec![RawSource::new(
+            Path::new("test_data/bookmarks_simple.txt"),
+            vec![],
+        )];
+
+        for (no_remove, expected_status) in [(false, Status::Removed), (true, Status::None)] {
+            let target_reader_writer = create_target_reader_writer(&target_bookmarks);
+            let mut bookmark_manager =
+                BookmarkManager::new(Box::new(target_reader_writer)).with_no_remove(no_remove);
+            bookmark_manager.add_sources(&sources).unwrap();
+
+            let res = bookmark_manager.import(now);
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+
+            assert!(bookmark_manager.target_bookmarks.contains_key(
+                &Url::from_str("https://en.wikipedia.org/wiki/Design_Patterns").unwrap()
+            ));
+            assert_eq!(
+                bookmark_manager
+                    .target_bookmarks
+                    .get(&url)
+                    .unwrap()
+                    .status(),
+                &expected_status
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_source_soft_delete() {
+        let now = Utc::now();
+        let removed_url = Url::parse("https://url.com").unwrap();
+        let restored_url = Url::parse("https://en.wikipedia.org/wiki/Design_Patterns").unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(
+            TargetBookmarkBuilder::new(removed_url.clone(), now)
+                .add_source(SourceType::Simple)
+                .with_status(Status::None)
+                .build(),
+        );
+        let mut restored_bookmark = TargetBookmarkBuilder::new(restored_url.clone(), now)
+            .add_source(SourceType::Simple)
+            .with_status(Status::None)
+            .build();
+        restored_bookmark.set_removed_at(now);
+        target_bookmarks.insert(restored_bookmark);
+        let sources = vec![RawSource::new(
+            Path::new("test_data/bookmarks_simple.txt"),
+            vec![],
+        )];
+        let target_reader_writer = create_target_reader_writer(&target_bookmarks);
+        let mut bookmark_manager =
+            BookmarkManager::new(Box::new(target_reader_writer)).with_soft_delete(true);
+        bookmark_manager.add_sources(&sources).unwrap();
+
+        let res = bookmark_manager.import(now);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(bookmark_manager.soft_deleted, 1);
+
+        let removed_bookmark = bookmark_manager.target_bookmarks.get(&removed_url).unwrap();
+        assert_eq!(removed_bookmark.status(), &Status::None);
+        assert_eq!(removed_bookmark.removed_at(), Some(now.timestamp_millis()));
+
+        let restored_bookmark = bookmark_manager
+            .target_bookmarks
+            .get(&restored_url)
+            .unwrap();
+        assert_eq!(restored_bookmark.status(), &Status::None);
+        assert_eq!(restored_bookmark.removed_at(), None);
+
+        let expired = bookmark_manager.expire_bookmarks(now - TimeDelta::milliseconds(1));
+        assert_eq!(expired, 0);
+        assert_eq!(
+            bookmark_manager
+                .target_bookmarks
+                .get(&removed_url)
+                .unwrap()
+                .status(),
+            &Status::None
+        );
+
+        let expired = bookmark_manager.expire_bookmarks(now);
+        assert_eq!(expired, 1);
+        assert_eq!(
+            bookmark_manager
+                .target_bookmarks
+                .get(&removed_url)
+                .unwrap()
+                .status(),
+            &Status::Removed
+        );
+        assert_eq!(
+            bookmark_manager
+                .target_bookmarks
+                .get(&restored_url)
+                .unwrap()
+                .status(),
+            &Status::None
+        );
+    }
+
     #[test]
     fn test_import() {
         let now = Utc::now();
@@ -463,7 +770,7 @@ pub mod tests {
             ]))
         );
 
-        bookmark_manager.remove_bookmarks(&source_bookmarks);
+        bookmark_manager.remove_bookmarks(&source_bookmarks, now);
         let actual_bookmarks = bookmark_manager.target_bookmarks();
         assert_eq!(
             actual_bookmarks,
@@ -1,23 +1,83 @@
-use super::{BookmarkManager, RunMode};
+use super::{BookmarkManager, RunMode, SourceFolder, UrlFilter};
 use crate::{
-    errors::BogrepError, html, utils, Action, Caching, Fetch, ServiceReport, SourceType, Status,
-    TargetBookmark, TargetBookmarkBuilder,
+    errors::BogrepError,
+    html::{self, TextExtractor},
+    json,
+    robots::Robots,
+    settings::UNDERLYING_DEPTH_DEFAULT,
+    utils, Action, CacheValidators, Caching, Fetch, JsonBookmarks, Manifest, OversizedContent,
+    ProcessReport, ServiceReport, Settings, SourceType, Status, TargetBookmark,
+    TargetBookmarkBuilder, TargetBookmarks, UnderlyingType,
 };
 use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use colored::Colorize;
 use futures::{stream, StreamExt};
-use log::{debug, trace, warn};
+use log::{debug, log_enabled, trace, warn, Level};
 use parking_lot::Mutex;
 use similar::{ChangeTag, TextDiff};
-use std::{error::Error, io::Write, rc::Rc};
-use tokio::signal;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{signal, task::JoinHandle};
 use url::Url;
 
+/// The strategy to execute the actions of the bookmarks.
+///
+/// The concurrent strategy polls all requests on the current task, which
+/// avoids copying the bookmarks. The parallel strategy spawns a task per
+/// bookmark, which spreads the processing of the fetched websites, like the
+/// conversion to text, over multiple threads at the cost of copying each
+/// bookmark.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FetchStrategy {
+    /// Process the bookmarks concurrently on the current task.
+    #[default]
+    Concurrent,
+    /// Process the bookmarks in parallel on spawned tasks.
+    Parallel,
+}
+
 #[derive(Debug, Default)]
 pub struct ServiceConfig {
     run_mode: RunMode,
     ignored_urls: Vec<Url>,
     max_concurrent_requests: usize,
+    show_progress_bar: bool,
+    url_filter: UrlFilter,
+    /// The number of bookmarks to skip before fetching.
+    offset: usize,
+    /// The maximum number of bookmarks to fetch.
+    limit: Option<usize>,
+    title: Option<String>,
+    folder: Option<String>,
+    manifest_path: Option<PathBuf>,
+    checkpoint: Option<Checkpoint>,
+    max_content_size: Option<usize>,
+    oversized_content: OversizedContent,
+    underlying_depth: usize,
+    resolve_underlying: bool,
+    only_underlying: bool,
+    diff_summary: bool,
+    cache_empty: bool,
+    human_readable: bool,
+    respect_robots: bool,
+    min_text_length: Option<usize>,
+    ignored_report: bool,
+    untracked_urls: Vec<Url>,
+}
+
+/// Write the target bookmarks to disk periodically while processing.
+#[derive(Debug)]
+struct Checkpoint {
+    /// The number of processed bookmarks after which a checkpoint is written.
+    every: usize,
+    /// The path of the target bookmark file.
+    target_bookmark_path: PathBuf,
 }
 
 impl ServiceConfig {
@@ -32,9 +92,172 @@ impl ServiceConfig {
             run_mode,
             ignored_urls,
             max_concurrent_requests,
+            show_progress_bar: false,
+            url_filter: UrlFilter::default(),
+            offset: 0,
+            limit: None,
+            title: None,
+            folder: None,
+            manifest_path: None,
+            checkpoint: None,
+            max_content_size: None,
+            oversized_content: OversizedContent::default(),
+            underlying_depth: UNDERLYING_DEPTH_DEFAULT,
+            resolve_underlying: true,
+            only_underlying: false,
+            diff_summary: false,
+            cache_empty: false,
+            human_readable: false,
+            respect_robots: false,
+            min_text_length: None,
+            ignored_report: false,
+            untracked_urls: vec![],
         })
     }
 
+    /// Show a progress bar with throughput and ETA if stdout is a terminal.
+    pub fn with_progress_bar(mut self, show_progress_bar: bool) -> Self {
+        self.show_progress_bar = show_progress_bar;
+        self
+    }
+
+    /// Fetch only bookmarks whose url matches the filter.
+    pub fn with_url_filter(mut self, url_filter: UrlFilter) -> Self {
+        self.url_filter = url_filter;
+        self
+    }
+
+    /// Fetch only a slice of the bookmarks sorted by url, which skips the
+    /// first `offset` bookmarks and fetches at most `limit` bookmarks.
+    pub fn with_slice(mut self, offset: Option<usize>, limit: Option<usize>) -> Self {
+        self.offset = offset.unwrap_or_default();
+        self.limit = limit;
+        self
+    }
+
+    /// Set the title for added bookmarks.
+    pub fn with_title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Set the folder for added bookmarks.
+    pub fn with_folder(mut self, folder: Option<String>) -> Self {
+        self.folder = folder;
+        self
+    }
+
+    /// Write a manifest of the cached files to the given path.
+    pub fn with_manifest(mut self, manifest_path: Option<PathBuf>) -> Self {
+        self.manifest_path = manifest_path;
+        self
+    }
+
+    /// Write the target bookmarks to the given path after every `every`
+    /// processed bookmarks.
+    pub fn with_checkpoint(mut self, every: Option<usize>, target_bookmark_path: &Path) -> Self {
+        self.checkpoint = every.map(|every| Checkpoint {
+            every,
+            target_bookmark_path: target_bookmark_path.to_owned(),
+        });
+        self
+    }
+
+    /// Apply the configured settings for fetching websites, i.e. the maximum
+    /// content size, the underlying depth, `robots.txt`, and the minimum text
+    /// length.
+    pub fn with_settings(self, settings: &Settings) -> Self {
+        self.with_max_content_size(
+            settings.max_content_size_bytes,
+            settings.oversized_content.clone(),
+        )
+        .with_underlying_depth(settings.underlying_depth)
+        .with_respect_robots(settings.respect_robots)
+        .with_min_text_length(settings.min_text_length)
+    }
+
+    /// Skip or truncate websites whose content exceeds the maximum size in
+    /// bytes.
+    pub fn with_max_content_size(
+        mut self,
+        max_content_size: Option<u64>,
+        oversized_content: OversizedContent,
+    ) -> Self {
+        self.max_content_size = max_content_size.map(|size| size as usize);
+        self.oversized_content = oversized_content;
+        self
+    }
+
+    /// Resolve underlyings of underlyings up to the given number of levels.
+    pub fn with_underlying_depth(mut self, underlying_depth: usize) -> Self {
+        self.underlying_depth = underlying_depth;
+        self
+    }
+
+    /// Resolve the underlying bookmarks of aggregator pages like Hacker News
+    /// or Reddit.
+    pub fn with_resolve_underlying(mut self, resolve_underlying: bool) -> Self {
+        self.resolve_underlying = resolve_underlying;
+        self
+    }
+
+    /// Cache only the underlying bookmarks, and skip caching the aggregator
+    /// pages themselves.
+    pub fn with_only_underlying(mut self, only_underlying: bool) -> Self {
+        self.only_underlying = only_underlying;
+        self
+    }
+
+    /// Print the number of added and removed lines per bookmark instead of
+    /// the full diff, unless the log level is verbose.
+    pub fn with_diff_summary(mut self, diff_summary: bool) -> Self {
+        self.diff_summary = diff_summary;
+        self
+    }
+
+    /// Cache websites with an empty response as empty files instead of
+    /// skipping them.
+    pub fn with_cache_empty(mut self, cache_empty: bool) -> Self {
+        self.cache_empty = cache_empty;
+        self
+    }
+
+    /// Name the cached files of new bookmarks by a slug of their url instead
+    /// of their id.
+    pub fn with_human_readable(mut self, human_readable: bool) -> Self {
+        self.human_readable = human_readable;
+        self
+    }
+
+    /// Skip urls which are disallowed by the `robots.txt` of their host.
+    pub fn with_respect_robots(mut self, respect_robots: bool) -> Self {
+        self.respect_robots = respect_robots;
+        self
+    }
+
+    /// Skip websites whose extracted text is shorter than the given number
+    /// of characters, e.g. cookie banners or consent pages.
+    pub fn with_min_text_length(mut self, min_text_length: Option<usize>) -> Self {
+        self.min_text_length = min_text_length;
+        self
+    }
+
+    /// List the bookmarks which were removed because they match the ignored
+    /// urls. The list is also printed if the log level is verbose.
+    pub fn with_ignored_report(mut self, ignored_report: bool) -> Self {
+        self.ignored_report = ignored_report;
+        self
+    }
+
+    /// Fetch the given urls of `RunMode::FetchUrls` or
+    /// `RunMode::FetchAllUrls` without adding them if they are missing in the
+    /// bookmarks. The cached files of these urls, and of their underlyings,
+    /// are unreferenced.
+    pub fn with_untracked_urls(mut self, untracked_urls: Vec<Url>) -> Self {
+        self.untracked_urls = untracked_urls;
+        self
+    }
+
     pub fn run_mode(&self) -> &RunMode {
         &self.run_mode
     }
@@ -44,35 +267,65 @@ impl ServiceConfig {
     }
 }
 
+/// Execute the action of a bookmark on a spawned task.
+type SpawnAction<C, F> = fn(
+    &BookmarkService<C, F>,
+    TargetBookmark,
+) -> JoinHandle<(TargetBookmark, Result<(), BogrepError>)>;
+
+#[derive(Clone)]
 pub struct BookmarkService<C: Caching, F: Fetch> {
-    config: ServiceConfig,
+    config: Arc<ServiceConfig>,
     client: F,
     cache: C,
-    underlying_bookmarks: Rc<Mutex<Vec<TargetBookmark>>>,
-    report: Rc<Mutex<ServiceReport>>,
+    /// Spawn a task per bookmark for `FetchStrategy::Parallel`.
+    spawn_action: Option<SpawnAction<C, F>>,
+    underlying_bookmarks: Arc<Mutex<Vec<TargetBookmark>>>,
+    /// The urls of bookmarks and their underlyings which would be added in a
+    /// dry run.
+    dry_run_underlyings: Arc<Mutex<Vec<(Url, Url)>>>,
+    /// The urls of bookmarks which were removed because they match an ignored
+    /// url, and the matched ignored url.
+    ignored_bookmarks: Arc<Mutex<Vec<(Url, Url)>>>,
+    report: Arc<Mutex<ServiceReport>>,
 }
 
 impl<C, F> BookmarkService<C, F>
 where
     C: Caching,
-    F: Fetch,
+    F: Fetch + Sync,
 {
     pub fn new(config: ServiceConfig, client: F, cache: C) -> Self {
         let underlying_bookmarks = vec![];
-        let report = ServiceReport {
+        let mut report = ServiceReport {
             dry_run: config.run_mode == RunMode::DryRun,
             ..Default::default()
         };
 
+        if config.show_progress_bar {
+            report.enable_progress_bar();
+        }
+
+        report.set_oversized_content(config.oversized_content.clone());
+        report.set_cache_empty(config.cache_empty);
+
         Self {
-            config,
+            config: Arc::new(config),
             client,
             cache,
-            underlying_bookmarks: Rc::new(Mutex::new(underlying_bookmarks)),
-            report: Rc::new(Mutex::new(report)),
+            spawn_action: None,
+            underlying_bookmarks: Arc::new(Mutex::new(underlying_bookmarks)),
+            dry_run_underlyings: Arc::new(Mutex::new(vec![])),
+            ignored_bookmarks: Arc::new(Mutex::new(vec![])),
+            report: Arc::new(Mutex::new(report)),
         }
     }
 
+    /// The counts of the bookmarks processed in the last run.
+    pub fn report(&self) -> ProcessReport {
+        self.report.lock().process_report()
+    }
+
     pub async fn run(
         &self,
         bookmark_manager: &mut BookmarkManager,
@@ -89,23 +342,63 @@ where
             }
         }
 
-        bookmark_manager.export()?;
+        if let Some(checkpoint) = &self.config.checkpoint {
+            // The target bookmark file was replaced by a checkpoint, so we are
+            // writing to the path instead of the opened file.
+            Self::write_checkpoint(
+                bookmark_manager.target_bookmarks(),
+                &checkpoint.target_bookmark_path,
+            )?;
+        } else {
+            bookmark_manager.export()?;
+        }
 
         Ok(())
     }
 
+    /// Write the target bookmarks atomically to avoid a corrupted target
+    /// bookmark file.
+    fn write_checkpoint(
+        target_bookmarks: &TargetBookmarks,
+        target_bookmark_path: &Path,
+    ) -> Result<(), BogrepError> {
+        let bookmarks = JsonBookmarks::from(target_bookmarks);
+        let json = json::serialize(&bookmarks)?;
+        utils::write_file_atomically(target_bookmark_path, &json)
+    }
+
     /// Process all imported bookmarks.
     pub async fn process(
         &self,
         bookmark_manager: &mut BookmarkManager,
         now: DateTime<Utc>,
     ) -> Result<(), BogrepError> {
+        let untracked_urls = self
+            .config
+            .untracked_urls
+            .iter()
+            .filter(|url| !bookmark_manager.target_bookmarks().contains_key(url))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Bookmarks which are tracked before processing are kept, even if they
+        // are an underlying of an untracked url.
+        let tracked_urls = if untracked_urls.is_empty() {
+            HashSet::new()
+        } else {
+            bookmark_manager
+                .target_bookmarks()
+                .keys()
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+
         self.set_actions(bookmark_manager, now)?;
 
         match self.config.run_mode {
             RunMode::Import
             | RunMode::RemoveUrls(_)
             | RunMode::Fetch
+            | RunMode::FetchNew
             | RunMode::FetchAll
             | RunMode::FetchUrls(_)
             | RunMode::FetchAllUrls(_)
@@ -115,22 +408,134 @@ where
             | RunMode::RemoveAll
             | RunMode::DryRun => {
                 self.execute_actions(bookmark_manager).await?;
-                self.add_underlyings(bookmark_manager);
 
-                if !self.underlying_bookmarks.lock().is_empty() {
-                    println!("Processing underlying bookmarks");
+                for depth in 1..=self.config.underlying_depth {
+                    if !self.add_underlyings(bookmark_manager) {
+                        break;
+                    }
+
+                    if depth == 1 {
+                        println!("Processing underlying bookmarks");
+                    } else {
+                        println!("Processing underlying bookmarks (level {depth})");
+                    }
+
                     self.execute_actions(bookmark_manager).await?;
                 }
             }
             _ => (),
         }
 
+        if self.config.run_mode == RunMode::DryRun {
+            self.print_dry_run_underlyings();
+        }
+
+        if self.config.ignored_report || log_enabled!(Level::Debug) {
+            self.print_ignored_bookmarks();
+        }
+
+        if !untracked_urls.is_empty() {
+            Self::remove_untracked(bookmark_manager, untracked_urls, &tracked_urls);
+        }
+
+        if let Some(manifest_path) = &self.config.manifest_path {
+            if self.config.run_mode != RunMode::DryRun {
+                self.write_manifest(bookmark_manager, manifest_path)?;
+            }
+        }
+
         bookmark_manager.print_report(self.config.run_mode());
         bookmark_manager.finish();
 
         Ok(())
     }
 
+    /// Set the configured title and folder for the added bookmarks.
+    fn set_title_and_folder(&self, bookmark_manager: &mut BookmarkManager, urls: &[Url]) {
+        for url in urls {
+            if let Some(bookmark) = bookmark_manager.target_bookmarks_mut().get_mut(url) {
+                if let Some(title) = &self.config.title {
+                    bookmark.set_title(title.to_owned());
+                }
+
+                if let Some(folder) = &self.config.folder {
+                    bookmark.add_folder(SourceFolder::new(SourceType::Internal, folder.to_owned()));
+                }
+            }
+        }
+    }
+
+    /// Remove the untracked bookmarks after they were fetched, together with
+    /// the underlying bookmarks which were added for them.
+    fn remove_untracked(
+        bookmark_manager: &mut BookmarkManager,
+        untracked_urls: Vec<Url>,
+        tracked_urls: &HashSet<Url>,
+    ) {
+        let target_bookmarks = bookmark_manager.target_bookmarks_mut();
+        let mut removed_urls = HashSet::new();
+        let mut urls = untracked_urls;
+
+        while !urls.is_empty() {
+            for url in &urls {
+                debug!("Remove untracked bookmark: {url}");
+                target_bookmarks.remove(url);
+                removed_urls.insert(url.to_string());
+            }
+
+            // Underlyings of underlyings are removed in the next iteration.
+            urls = target_bookmarks
+                .values()
+                .filter(|bookmark| {
+                    !tracked_urls.contains(bookmark.url())
+                        && bookmark.sources().iter().all(|source| {
+                            matches!(source, SourceType::Underlying(url) if removed_urls.contains(url))
+                        })
+                })
+                .map(|bookmark| bookmark.url().clone())
+                .collect();
+        }
+    }
+
+    /// Aggregate the cached files of all bookmarks in a manifest.
+    fn write_manifest(
+        &self,
+        bookmark_manager: &BookmarkManager,
+        manifest_path: &Path,
+    ) -> Result<(), BogrepError> {
+        let mut manifest = Manifest::default();
+
+        for bookmark in bookmark_manager.target_bookmarks().values() {
+            if bookmark.status() != &Status::Removed {
+                manifest.insert(bookmark.id(), self.cache.manifest_entries(bookmark)?);
+            }
+        }
+
+        debug!(
+            "Write manifest for {} bookmarks to {}",
+            manifest.len(),
+            manifest_path.display()
+        );
+        manifest.write(manifest_path)
+    }
+
+    /// Warn if the cache directory and the cache modes of the bookmarks have
+    /// drifted apart, and return the number of inconsistent bookmarks.
+    fn check_cache_drift(&self, target_bookmarks: &TargetBookmarks) -> usize {
+        let drifted_bookmarks = target_bookmarks
+            .values()
+            .filter(|bookmark| !self.cache.is_consistent(bookmark))
+            .count();
+
+        if drifted_bookmarks > 0 {
+            warn!(
+                "Cache is inconsistent with {drifted_bookmarks} bookmarks, run `bogrep verify --fix` to fix them"
+            );
+        }
+
+        drifted_bookmarks
+    }
+
     fn set_actions(
         &self,
         bookmark_manager: &mut BookmarkManager,
@@ -138,7 +543,31 @@ where
     ) -> Result<(), BogrepError> {
         if self.cache.is_empty() {
             debug!("Cache is empty");
+            let cached_bookmarks = bookmark_manager
+                .target_bookmarks()
+                .values()
+                .filter(|bookmark| !bookmark.cache_modes().is_empty())
+                .count();
+
+            if cached_bookmarks > 0 {
+                warn!(
+                    "Cache is empty, but {cached_bookmarks} bookmarks are marked as cached; \
+                    resetting cache status"
+                );
+            }
+
             bookmark_manager.target_bookmarks_mut().reset_cache_status();
+        } else if matches!(
+            self.config.run_mode(),
+            RunMode::Fetch
+                | RunMode::FetchNew
+                | RunMode::FetchAll
+                | RunMode::FetchUrls(_)
+                | RunMode::FetchAllUrls(_)
+                | RunMode::FetchDiff(_)
+                | RunMode::Sync
+        ) {
+            self.check_cache_drift(bookmark_manager.target_bookmarks());
         }
 
         match self.config.run_mode() {
@@ -149,6 +578,7 @@ where
             }
             RunMode::AddUrls(urls) => {
                 bookmark_manager.add_urls(urls, self.cache.mode(), &Action::None, now);
+                self.set_title_and_folder(bookmark_manager, urls);
             }
             RunMode::RemoveUrls(urls) => {
                 bookmark_manager.remove_urls(urls);
@@ -165,6 +595,7 @@ where
             }
             RunMode::FetchUrls(urls) => {
                 bookmark_manager.add_urls(urls, self.cache.mode(), &Action::FetchAndAdd, now);
+                self.set_title_and_folder(bookmark_manager, urls);
             }
             RunMode::FetchAllUrls(urls) => {
                 bookmark_manager.add_urls(urls, self.cache.mode(), &Action::FetchAndReplace, now);
@@ -174,6 +605,15 @@ where
                     .target_bookmarks_mut()
                     .set_action(&Action::FetchAndAdd);
             }
+            RunMode::FetchNew => {
+                for bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+                    if bookmark.status() == &Status::Added {
+                        bookmark.set_action(Action::FetchAndAdd);
+                    } else {
+                        bookmark.set_action(Action::None);
+                    }
+                }
+            }
             RunMode::FetchAll => {
                 bookmark_manager
                     .target_bookmarks_mut()
@@ -199,6 +639,21 @@ where
             }
         }
 
+        for target_bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+            let is_fetched = matches!(
+                target_bookmark.action(),
+                Action::FetchAndAdd | Action::FetchAndReplace
+            );
+
+            if is_fetched && !self.config.url_filter.is_match(target_bookmark.url()) {
+                target_bookmark.set_action(Action::None);
+            }
+        }
+
+        if self.config.offset > 0 || self.config.limit.is_some() {
+            self.select_slice(bookmark_manager);
+        }
+
         for target_bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
             if self.config.run_mode != RunMode::DryRun {
                 match target_bookmark.status() {
@@ -215,6 +670,9 @@ where
                 .filter(|bookmark| bookmark.url.host() == ignored_url.host());
 
             for bookmark in ignored_bookmarks {
+                self.ignored_bookmarks
+                    .lock()
+                    .push((bookmark.url().to_owned(), ignored_url.to_owned()));
                 bookmark.set_status(Status::Removed);
 
                 if self.config.run_mode != RunMode::DryRun {
@@ -226,19 +684,114 @@ where
         Ok(())
     }
 
+    /// Skip the fetched bookmarks outside of the configured slice of bookmarks
+    /// sorted by url.
+    fn select_slice(&self, bookmark_manager: &mut BookmarkManager) {
+        let mut fetched_bookmarks = bookmark_manager
+            .target_bookmarks_mut()
+            .values_mut()
+            .filter(|bookmark| {
+                matches!(
+                    bookmark.action(),
+                    Action::FetchAndAdd | Action::FetchAndReplace
+                )
+            })
+            .collect::<Vec<_>>();
+        fetched_bookmarks.sort_by(|a, b| a.url().cmp(b.url()));
+
+        let start = self.config.offset;
+        let end = self
+            .config
+            .limit
+            .map_or(usize::MAX, |limit| start.saturating_add(limit));
+
+        for (i, bookmark) in fetched_bookmarks.into_iter().enumerate() {
+            if i < start || i >= end {
+                bookmark.set_action(Action::None);
+            }
+        }
+    }
+
+    /// Skip bookmarks disallowed by `robots.txt`.
+    ///
+    /// The `robots.txt` is fetched once per host, and the action of the
+    /// disallowed bookmarks is reset. Returns the number of disallowed
+    /// bookmarks.
+    async fn skip_disallowed(&self, bookmark_manager: &mut BookmarkManager) -> usize {
+        let is_fetched = |bookmark: &TargetBookmark| {
+            matches!(
+                bookmark.action(),
+                Action::FetchAndAdd | Action::FetchAndReplace | Action::FetchAndDiff
+            ) && matches!(bookmark.url().scheme(), "http" | "https")
+        };
+        let origins = bookmark_manager
+            .target_bookmarks()
+            .values()
+            .filter(|bookmark| is_fetched(bookmark))
+            .map(|bookmark| (bookmark.url().origin(), bookmark.url().clone()))
+            .collect::<HashMap<_, _>>();
+        let robots = stream::iter(origins)
+            .map(|(origin, url)| async move {
+                let robots = self.client.fetch_robots(&url).await;
+                (origin, robots.map(|robots| Robots::parse(&robots)))
+            })
+            .buffer_unordered(self.config.max_concurrent_requests)
+            .collect::<HashMap<_, _>>()
+            .await;
+        let mut disallowed = 0;
+
+        for bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+            let is_disallowed = is_fetched(bookmark)
+                && robots
+                    .get(&bookmark.url().origin())
+                    .and_then(|robots| robots.as_ref())
+                    .is_some_and(|robots| !robots.is_allowed(bookmark.url()));
+
+            if is_disallowed {
+                debug!("Skip bookmark disallowed by robots.txt: {}", bookmark.url());
+                bookmark.set_action(Action::None);
+                bookmark.set_last_status("disallowed by robots.txt");
+                disallowed += 1;
+            }
+        }
+
+        disallowed
+    }
+
     /// Execute `Action`s for provided bookmarks.
     async fn execute_actions(
         &self,
         bookmark_manager: &mut BookmarkManager,
     ) -> Result<(), BogrepError> {
         let max_concurrent_requests = self.config.max_concurrent_requests;
-        let bookmarks = bookmark_manager
+        let checkpoint = self
+            .config
+            .checkpoint
+            .as_ref()
+            .filter(|_| self.config.run_mode != RunMode::DryRun);
+
+        if self.config.human_readable {
+            bookmark_manager.target_bookmarks_mut().assign_filenames();
+        }
+
+        let disallowed = if self.config.respect_robots {
+            self.skip_disallowed(bookmark_manager).await
+        } else {
+            0
+        };
+
+        let mut checkpoint_bookmarks =
+            checkpoint.map(|_| bookmark_manager.target_bookmarks().clone());
+        let mut bookmarks = bookmark_manager
             .target_bookmarks_mut()
             .values_mut()
             .filter(|bookmark| bookmark.action() != &Action::None)
             .collect::<Vec<_>>();
+        // Process bookmarks in a deterministic order.
+        bookmarks.sort_by(|a, b| a.url().cmp(b.url()));
 
-        if bookmarks.is_empty() {
+        // The summary is printed if bookmarks were disallowed.
+        if bookmarks.is_empty() && disallowed == 0 {
             return Ok(());
         }
 
@@ -246,13 +799,56 @@ where
             let mut report = self.report.lock();
             report.reset();
             report.set_total(bookmarks.len());
+            report.set_disallowed(disallowed);
         }
 
-        let mut stream = stream::iter(bookmarks)
-            .map(|bookmark| self.execute_action(bookmark))
-            .buffer_unordered(max_concurrent_requests);
+        let mut processed = 0;
+        let mut stream = match self.spawn_action {
+            None => stream::iter(bookmarks)
+                .map(|bookmark| async move {
+                    let res = self.execute_action(bookmark).await;
+                    (bookmark, res)
+                })
+                .buffer_unordered(max_concurrent_requests)
+                .boxed_local(),
+            Some(spawn_action) => stream::iter(bookmarks)
+                .map(move |bookmark| {
+                    // The spawned task processes a copy of the bookmark,
+                    // which replaces the bookmark after the task finished.
+                    let task = spawn_action(self, bookmark.clone());
+
+                    async move {
+                        match task.await {
+                            Ok((spawned_bookmark, res)) => {
+                                *bookmark = spawned_bookmark;
+                                (bookmark, res)
+                            }
+                            Err(err) => (bookmark, Err(BogrepError::Other(err.into()))),
+                        }
+                    }
+                })
+                .buffer_unordered(max_concurrent_requests)
+                .boxed_local(),
+        };
+
+        while let Some((bookmark, item)) = stream.next().await {
+            processed += 1;
+
+            if let Some(last_status) = item.as_ref().err().and_then(Self::fetch_status) {
+                bookmark.set_last_status(last_status);
+            }
+
+            if let (Some(checkpoint), Some(checkpoint_bookmarks)) =
+                (checkpoint, checkpoint_bookmarks.as_mut())
+            {
+                checkpoint_bookmarks.insert(bookmark.clone());
+
+                if processed % checkpoint.every == 0 {
+                    debug!("Write checkpoint after {processed} bookmarks");
+                    Self::write_checkpoint(checkpoint_bookmarks, &checkpoint.target_bookmark_path)?;
+                }
+            }
 
-        while let Some(item) = stream.next().await {
             let mut report = self.report.lock();
             report.increment_processed();
             report.print();
@@ -291,10 +887,23 @@ where
                         debug!("{err}");
                         report.increment_empty_response();
                     }
+                    BogrepError::OversizedResponse { .. } => {
+                        debug!("{err}");
+                        report.increment_oversized_response();
+                    }
+                    BogrepError::ThinResponse { .. } => {
+                        debug!("{err}");
+                        report.increment_thin_response();
+                    }
                     BogrepError::ConvertHost(_) => {
                         warn!("{err}");
                         report.increment_failed_response();
                     }
+                    BogrepError::OpenFile { .. } => {
+                        // Local files of `file://` bookmarks may be missing.
+                        warn!("{err}");
+                        report.increment_failed_response();
+                    }
                     BogrepError::CreateFile { .. } => {
                         // Write errors are expected if there are "Too many open
                         // files", so we are issuing a warning instead of returning
@@ -324,35 +933,92 @@ where
         &self,
         bookmark: &'a mut TargetBookmark,
     ) -> Result<(), BogrepError> {
-        let client = &self.client;
         let cache = &self.cache;
 
+        if self.config.only_underlying
+            && bookmark.underlying_type() != &UnderlyingType::None
+            && matches!(
+                bookmark.action(),
+                Action::FetchAndReplace | Action::FetchAndAdd
+            )
+        {
+            // The aggregator page is only fetched to resolve the underlying,
+            // but not cached.
+            if bookmark.underlying_url().is_none() {
+                let website = self.fetch(bookmark).await?;
+                trace!("Fetched website: {website}");
+                self.add_underlying(bookmark, &website)?;
+            }
+
+            bookmark.set_action(Action::None);
+            return Ok(());
+        }
+
         match bookmark.action() {
             Action::FetchAndReplace => {
-                let website = client.fetch(bookmark).await?;
+                // Fetch the website conditionally to skip unmodified websites.
+                let validators = if cache.exists(bookmark) {
+                    bookmark.cache_validators()
+                } else {
+                    CacheValidators::default()
+                };
+                let website = match self.fetch_modified(bookmark, &validators).await {
+                    Err(BogrepError::NotModified(url)) => {
+                        debug!("Skip unmodified bookmark ({url})");
+                        bookmark.set_last_status("ok");
+                        bookmark.set_action(Action::None);
+                        return Ok(());
+                    }
+                    res => res?,
+                };
                 trace!("Fetched website: {website}");
                 self.add_underlying(bookmark, &website)?;
                 let html = html::filter_html(&website)?;
-                cache.replace(html, bookmark).await?;
+                let html = self.limit_content_size(html, bookmark)?;
+                self.check_text_length(&html, bookmark)?;
+                let content_hash = utils::hash_content(html.as_bytes());
+
+                // Skip writing the cache if the content hasn't changed.
+                if bookmark.content_hash() == Some(content_hash.as_str()) && cache.exists(bookmark)
+                {
+                    debug!("Skip unchanged bookmark ({})", bookmark.url());
+                } else {
+                    cache.replace(html, bookmark).await?;
+                    bookmark.set_content_hash(content_hash);
+                }
             }
             Action::FetchAndAdd => {
                 if !cache.exists(bookmark) {
-                    let website = client.fetch(bookmark).await?;
+                    let website = self.fetch(bookmark).await?;
                     trace!("Fetched website: {website}");
                     self.add_underlying(bookmark, &website)?;
                     let html = html::filter_html(&website)?;
+                    let html = self.limit_content_size(html, bookmark)?;
+                    self.check_text_length(&html, bookmark)?;
+                    let content_hash = utils::hash_content(html.as_bytes());
                     cache.add(html, bookmark).await?;
+                    bookmark.set_content_hash(content_hash);
                 }
             }
             //  Fetch difference between cached and fetched website, and display
             //  changes.
             Action::FetchAndDiff => {
                 if let Some(website_before) = cache.get(bookmark)? {
-                    let fetched_website = client.fetch(bookmark).await?;
+                    let fetched_website = self.fetch(bookmark).await?;
                     trace!("Fetched website: {fetched_website}");
                     let html = html::filter_html(&fetched_website)?;
+                    let html = self.limit_content_size(html, bookmark)?;
+                    self.check_text_length(&html, bookmark)?;
+                    let content_hash = utils::hash_content(html.as_bytes());
                     let website_after = cache.replace(html, bookmark).await?;
-                    Self::diff_websites(&website_before, &website_after);
+                    bookmark.set_content_hash(content_hash);
+                    let print_diff = !self.config.diff_summary || log_enabled!(Level::Debug);
+                    let (added, removed) =
+                        Self::diff_websites(&website_before, &website_after, print_diff);
+
+                    if self.config.diff_summary {
+                        println!("{}: +{added} -{removed}", bookmark.url());
+                    }
                 }
             }
             Action::Remove => {
@@ -362,7 +1028,10 @@ where
                 cache.remove_by_modes(bookmark).await?;
             }
             // We don't reset the action to `Action::None` in a dry run.
-            Action::DryRun => return Ok(()),
+            Action::DryRun => {
+                self.preview_underlying(bookmark)?;
+                return Ok(());
+            }
             Action::None => (),
         }
 
@@ -371,24 +1040,216 @@ where
         Ok(())
     }
 
-    fn diff_websites(before: &str, after: &str) {
+    /// Describe why a bookmark couldn't be fetched.
+    fn fetch_status(err: &BogrepError) -> Option<String> {
+        let last_status = match err {
+            BogrepError::HttpResponse(err) if err.is_timeout() => "timeout",
+            BogrepError::HttpResponse(err) if err.is_connect() => "connection error",
+            BogrepError::HttpResponse(_) => "request error",
+            BogrepError::HttpStatus { status, .. } => {
+                let code = status.split_whitespace().next().unwrap_or(status);
+                return Some(format!("status {code}"));
+            }
+            BogrepError::ParseHttpResponse(_) => "invalid response",
+            BogrepError::BinaryResponse(_) => "binary response",
+            BogrepError::EmptyResponse(_) => "empty response",
+            BogrepError::OversizedResponse { .. } => "oversized response",
+            BogrepError::ThinResponse { .. } => "thin response",
+            BogrepError::ConvertHost(_) => "invalid host",
+            BogrepError::OpenFile { .. } => "missing file",
+            _ => return None,
+        };
+
+        Some(last_status.to_owned())
+    }
+
+    /// Fetch a website unconditionally.
+    async fn fetch(&self, bookmark: &mut TargetBookmark) -> Result<String, BogrepError> {
+        self.fetch_modified(bookmark, &CacheValidators::default())
+            .await
+    }
+
+    /// Fetch a website if it was modified since the given cache validators,
+    /// and store the cache validators of the response.
+    ///
+    /// An empty response is treated as empty website if empty websites are
+    /// cached.
+    async fn fetch_modified(
+        &self,
+        bookmark: &mut TargetBookmark,
+        validators: &CacheValidators,
+    ) -> Result<String, BogrepError> {
+        let website = match self.client.fetch_modified(bookmark, validators).await {
+            Ok(website) => {
+                bookmark.set_cache_validators(website.validators);
+                bookmark.set_resolved_url(website.resolved_url);
+                website.html
+            }
+            Err(BogrepError::EmptyResponse(url)) if self.config.cache_empty => {
+                debug!("Cache empty response for {url}");
+                self.report.lock().increment_empty_response();
+                String::new()
+            }
+            Err(err) => return Err(err),
+        };
+
+        bookmark.set_last_status("ok");
+
+        Ok(website)
+    }
+
+    /// Skip or truncate the content if it exceeds the maximum content size.
+    fn limit_content_size(
+        &self,
+        mut html: String,
+        bookmark: &TargetBookmark,
+    ) -> Result<String, BogrepError> {
+        let Some(max_content_size) = self.config.max_content_size else {
+            return Ok(html);
+        };
+
+        if html.len() <= max_content_size {
+            return Ok(html);
+        }
+
+        match self.config.oversized_content {
+            OversizedContent::Skip => Err(BogrepError::OversizedResponse {
+                url: bookmark.url().to_string(),
+                size: html.len(),
+            }),
+            OversizedContent::Truncate => {
+                debug!(
+                    "Truncate oversized bookmark ({}): {} bytes",
+                    bookmark.url(),
+                    html.len()
+                );
+                self.report.lock().increment_oversized_response();
+
+                // Truncate at a char boundary to keep valid UTF-8.
+                let mut index = max_content_size;
+
+                while !html.is_char_boundary(index) {
+                    index -= 1;
+                }
+
+                html.truncate(index);
+                Ok(html)
+            }
+        }
+    }
+
+    /// Skip the content if its extracted text is shorter than the minimum
+    /// text length.
+    ///
+    /// Empty websites are only cached if `cache_empty` is set, and are not
+    /// checked.
+    fn check_text_length(&self, html: &str, bookmark: &TargetBookmark) -> Result<(), BogrepError> {
+        let Some(min_text_length) = self.config.min_text_length else {
+            return Ok(());
+        };
+
+        if html.is_empty() {
+            return Ok(());
+        }
+
+        let text = html::convert_to_text(html, bookmark.url(), &TextExtractor::DomText)?;
+        let length = text.trim().chars().count();
+
+        if length < min_text_length {
+            Err(BogrepError::ThinResponse {
+                url: bookmark.url().to_string(),
+                length,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Count the added and removed lines between two websites, and print
+    /// the changes if `print_diff` is set.
+    fn diff_websites(before: &str, after: &str, print_diff: bool) -> (usize, usize) {
         let diff = TextDiff::from_lines(before, after);
+        let mut added = 0;
+        let mut removed = 0;
 
         for change in diff.iter_all_changes() {
             match change.tag() {
                 ChangeTag::Delete => {
-                    if let Some(change) = change.as_str() {
+                    removed += 1;
+
+                    if let Some(change) = change.as_str().filter(|_| print_diff) {
                         print!("{}{}", "-".red(), change.red());
                     }
                 }
                 ChangeTag::Insert => {
-                    if let Some(change) = change.as_str() {
+                    added += 1;
+
+                    if let Some(change) = change.as_str().filter(|_| print_diff) {
                         print!("{}{}", "+".green(), change.green());
                     }
                 }
                 ChangeTag::Equal => continue,
             }
         }
+
+        (added, removed)
+    }
+
+    /// Select the underlying of a cached website without fetching, and
+    /// remember it to be reported in a dry run.
+    fn preview_underlying(&self, bookmark: &TargetBookmark) -> Result<(), BogrepError> {
+        if !self.config.resolve_underlying
+            || bookmark.underlying_type() == &UnderlyingType::None
+            || bookmark.underlying_url().is_some()
+        {
+            return Ok(());
+        }
+
+        if let Ok(Some(website)) = self.cache.get(bookmark) {
+            if let Some(underlying_url) =
+                html::select_underlying(&website, bookmark.underlying_type())?
+            {
+                self.dry_run_underlyings
+                    .lock()
+                    .push((bookmark.url().to_owned(), underlying_url));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_dry_run_underlyings(&self) {
+        let mut dry_run_underlyings = self.dry_run_underlyings.lock();
+
+        if dry_run_underlyings.is_empty() {
+            return;
+        }
+
+        dry_run_underlyings.sort();
+
+        println!("Underlying bookmarks which would be added:");
+
+        for (url, underlying_url) in dry_run_underlyings.iter() {
+            println!("  {underlying_url} (from {url})");
+        }
+    }
+
+    fn print_ignored_bookmarks(&self) {
+        let mut ignored_bookmarks = self.ignored_bookmarks.lock();
+
+        if ignored_bookmarks.is_empty() {
+            return;
+        }
+
+        // A bookmark can match multiple ignored urls.
+        ignored_bookmarks.sort();
+        ignored_bookmarks.dedup_by(|a, b| a.0 == b.0);
+
+        println!("Ignored bookmarks:");
+
+        for (url, ignored_url) in ignored_bookmarks.iter() {
+            println!("  {url} (matches {ignored_url})");
+        }
     }
 
     fn add_underlying(
@@ -396,6 +1257,10 @@ where
         bookmark: &mut TargetBookmark,
         website: &str,
     ) -> Result<(), BogrepError> {
+        if !self.config.resolve_underlying {
+            return Ok(());
+        }
+
         debug!("Add underlying");
 
         if bookmark.underlying_url().is_none() {
@@ -404,6 +1269,19 @@ where
             if let Some(underlying_url) = underlying_url {
                 bookmark.set_underlying_url(underlying_url.clone());
 
+                let mut underlying_bookmarks = self.underlying_bookmarks.lock();
+
+                // Multiple bookmarks can link to the same underlying.
+                if let Some(underlying_bookmark) = underlying_bookmarks
+                    .iter_mut()
+                    .find(|underlying_bookmark| underlying_bookmark.url() == &underlying_url)
+                {
+                    debug!("Skip duplicate underlying bookmark with url {underlying_url}");
+                    underlying_bookmark
+                        .add_source(SourceType::Underlying(bookmark.url().to_string()));
+                    return Ok(());
+                }
+
                 let underlying_bookmark =
                     TargetBookmarkBuilder::new(underlying_url.to_owned(), Utc::now())
                         .add_source(SourceType::Underlying(bookmark.url().to_string()))
@@ -416,7 +1294,6 @@ where
                 );
                 trace!("Added underlying bookmark: {underlying_bookmark:#?}");
 
-                let mut underlying_bookmarks = self.underlying_bookmarks.lock();
                 underlying_bookmarks.push(underlying_bookmark);
             }
         }
@@ -424,13 +1301,63 @@ where
         Ok(())
     }
 
-    fn add_underlyings(&self, bookmark_manager: &mut BookmarkManager) {
+    /// Insert the collected underlying bookmarks into the target bookmarks,
+    /// and return `true` if there are any underlying bookmarks to process.
+    ///
+    /// Underlyings which are already in the target bookmarks are not inserted
+    /// again, which guards against cycles between underlyings.
+    fn add_underlyings(&self, bookmark_manager: &mut BookmarkManager) -> bool {
         let target_bookmarks = bookmark_manager.target_bookmarks_mut();
-        let underlying_bookmarks = self.underlying_bookmarks.lock();
+        let underlying_bookmarks = std::mem::take(&mut *self.underlying_bookmarks.lock());
+        let mut is_added = false;
 
-        for underlying_bookmark in underlying_bookmarks.iter() {
-            target_bookmarks.insert(underlying_bookmark.clone());
+        for underlying_bookmark in underlying_bookmarks {
+            if let Some(target_bookmark) = target_bookmarks.get_mut(underlying_bookmark.url()) {
+                debug!(
+                    "Skip visited underlying bookmark with url {}",
+                    underlying_bookmark.url()
+                );
+
+                for source in underlying_bookmark.sources() {
+                    target_bookmark.add_source(source.clone());
+                }
+            } else {
+                target_bookmarks.insert(underlying_bookmark);
+                is_added = true;
+            }
         }
+
+        is_added
+    }
+}
+
+impl<C, F> BookmarkService<C, F>
+where
+    C: Caching + Send + Sync + 'static,
+    F: Fetch + Send + Sync + 'static,
+{
+    /// Execute the actions of the bookmarks with the given strategy.
+    ///
+    /// The parallel strategy requires a client and cache which can be moved to
+    /// spawned tasks.
+    pub fn with_strategy(mut self, strategy: FetchStrategy) -> Self {
+        self.spawn_action = match strategy {
+            FetchStrategy::Concurrent => None,
+            FetchStrategy::Parallel => Some(Self::spawn_action),
+        };
+        self
+    }
+
+    fn spawn_action(
+        &self,
+        mut bookmark: TargetBookmark,
+    ) -> JoinHandle<(TargetBookmark, Result<(), BogrepError>)> {
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let res = service.execute_action(&mut bookmark).await;
+            (bookmark, res)
+        })
     }
 }
 
@@ -438,10 +1365,13 @@ where
 mod tests {
     use super::*;
     use crate::{
-        bookmarks::bookmark_manager::tests::create_target_reader_writer, CacheMode, MockCache,
-        MockClient, Settings, TargetBookmarks,
+        bookmarks::bookmark_manager::tests::create_target_reader_writer, client::ClientConfig,
+        json, Cache, CacheMode, Client, ManifestEntry, ManifestMismatch, MockCache, MockClient,
+        Settings, TargetBookmarks,
     };
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::fs;
+    use tempfile::tempdir;
 
     fn create_mock_client(urls: &[Url], content: &str) -> MockClient {
         let client = MockClient::new();
@@ -526,6 +1456,27 @@ mod tests {
         bookmark_manager
     }
 
+    #[test]
+    fn test_service_config_with_settings() {
+        let mut settings = Settings {
+            max_content_size_bytes: Some(1024),
+            oversized_content: OversizedContent::Truncate,
+            min_text_length: Some(100),
+            ..Default::default()
+        };
+        settings.set_underlying_depth(3);
+        settings.set_respect_robots(true);
+
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 4)
+            .unwrap()
+            .with_settings(&settings);
+        assert_eq!(service_config.max_content_size, Some(1024));
+        assert_eq!(service_config.oversized_content, OversizedContent::Truncate);
+        assert_eq!(service_config.underlying_depth, 3);
+        assert!(service_config.respect_robots);
+        assert_eq!(service_config.min_text_length, Some(100));
+    }
+
     #[tokio::test]
     async fn test_set_actions_import() {
         let now = Utc::now();
@@ -584,6 +1535,149 @@ mod tests {
         assert_eq!(bookmarks.get(&url3).unwrap().action, Action::FetchAndAdd);
     }
 
+    #[tokio::test]
+    async fn test_set_actions_fetch_new() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone(), url3.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::FetchNew,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap();
+        let mut bookmark_manager =
+            create_mock_manager(&urls, &[Status::Added, Status::Removed, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.set_actions(&mut bookmark_manager, now);
+        assert!(res.is_ok());
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.get(&url1).unwrap().action, Action::FetchAndAdd);
+        assert_eq!(bookmarks.get(&url2).unwrap().action, Action::Remove);
+        assert_eq!(bookmarks.get(&url3).unwrap().action, Action::None);
+    }
+
+    #[tokio::test]
+    async fn test_check_cache_drift() {
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone(), url3.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap();
+        let mut bookmark_manager =
+            create_mock_manager(&urls, &[Status::None, Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache =
+            create_mock_cache(CacheMode::Html, Some("Test content"), &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.check_cache_drift(bookmark_manager.target_bookmarks());
+        assert_eq!(res, 0);
+
+        // The cached file exists, but the cache mode is not tracked.
+        bookmark_manager
+            .target_bookmarks_mut()
+            .get_mut(&url1)
+            .unwrap()
+            .remove_cache_mode(&CacheMode::Html);
+        // The cache mode is tracked, but the cached file is missing.
+        bookmark_manager
+            .target_bookmarks_mut()
+            .get_mut(&url2)
+            .unwrap()
+            .add_cache_mode(CacheMode::Html);
+
+        let res = service.check_cache_drift(bookmark_manager.target_bookmarks());
+        assert_eq!(res, 2);
+    }
+
+    #[tokio::test]
+    async fn test_check_cache_drift_human_readable() {
+        let temp_dir = tempdir().unwrap();
+        let url1 = Url::parse("https://url1.com/blog/post-1").unwrap();
+        let url2 = Url::parse("https://url2.com/blog/post-2").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_human_readable(true);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = Cache::new(temp_dir.path(), CacheMode::Html);
+
+        bookmark_manager
+            .target_bookmarks_mut()
+            .set_action(&Action::FetchAndAdd);
+        bookmark_manager.target_bookmarks_mut().assign_filenames();
+
+        for bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+            assert_ne!(bookmark.cache_name(), bookmark.id());
+            cache
+                .add(
+                    "<html><body>Test content</body></html>".to_owned(),
+                    bookmark,
+                )
+                .await
+                .unwrap();
+        }
+
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.check_cache_drift(bookmark_manager.target_bookmarks());
+        assert_eq!(res, 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_actions_add_urls() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::AddUrls(vec![url1.clone()]),
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_title(Some("Title".to_owned()))
+        .with_folder(Some("dev".to_owned()));
+        let target_reader_writer = create_target_reader_writer(&TargetBookmarks::default());
+        let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.set_actions(&mut bookmark_manager, now);
+        assert!(res.is_ok());
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 1);
+        let bookmark = bookmarks.get(&url1).unwrap();
+        assert_eq!(bookmark.title(), Some("Title"));
+        assert_eq!(
+            bookmark.source_folders(),
+            &HashSet::from_iter([SourceFolder::new(SourceType::Internal, "dev".to_owned())])
+        );
+    }
+
     #[tokio::test]
     async fn test_set_actions_dry_run() {
         let now = Utc::now();
@@ -636,21 +1730,117 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_process_fetch_html() {
+    async fn test_process_import_ignored_report() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com/endpoint").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let service_config =
+            ServiceConfig::new(RunMode::Import, &["https://url2.com".to_owned()], 100)
+                .unwrap()
+                .with_ignored_report(true);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            *service.ignored_bookmarks.lock(),
+            vec![(url2, Url::parse("https://url2.com").unwrap())]
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 1);
+        assert!(bookmarks.get(&url1).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_resolved_url() {
+        let now = Utc::now();
         let url1 = Url::parse("https://url1.com").unwrap();
         let url2 = Url::parse("https://url2.com").unwrap();
-        let urls = vec![url1, url2];
+        let resolved_url = Url::parse("https://url1.com/new").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 100).unwrap();
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        client.add_resolved_url(resolved_url.clone(), &url1);
+        client.add_resolved_url(url2.clone(), &url2);
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(
+            bookmarks.get(&url1).unwrap().resolved_url(),
+            Some(&resolved_url)
+        );
+        // The resolved url is only stored if the bookmark was redirected.
+        assert_eq!(bookmarks.get(&url2).unwrap().resolved_url(), None);
+
+        let bookmarks = JsonBookmarks::from(bookmarks);
+        let bookmark = bookmarks
+            .iter()
+            .find(|bookmark| bookmark.url == url1.as_str())
+            .unwrap();
+        assert_eq!(
+            bookmark.resolved_url.as_deref(),
+            Some(resolved_url.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_slice() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let urls = vec![url3.clone(), url1.clone(), url2.clone()];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 100)
+            .unwrap()
+            .with_slice(Some(1), Some(1));
+        let mut bookmark_manager =
+            create_mock_manager(&urls, &[Status::None, Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(service.cache.cache_map().len(), 1);
+        assert_eq!(service.report().total(), 1);
+        assert_eq!(service.client.fetched_urls(), vec![url2.clone()]);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert!(bookmarks.get(&url1).unwrap().last_cached.is_none());
+        assert!(bookmarks.get(&url2).unwrap().last_cached.is_some());
+        assert!(bookmarks.get(&url3).unwrap().last_cached.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_url_filter() {
         let now = Utc::now();
+        let url1 = Url::parse("https://www.rust-lang.org").unwrap();
+        let url2 = Url::parse("https://crates.io").unwrap();
+        let url3 = Url::parse("https://doc.rust-lang.org/book").unwrap();
+        let urls = vec![url1.clone(), url2.clone(), url3.clone()];
         let settings = Settings::default();
+        let url_filter = UrlFilter::new(&["*.rust-lang.org".to_owned()], &[]).unwrap();
         let service_config = ServiceConfig::new(
             RunMode::Fetch,
             &settings.ignored_urls,
             settings.max_concurrent_requests,
         )
-        .unwrap();
-        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        .unwrap()
+        .with_url_filter(url_filter);
+        let mut bookmark_manager =
+            create_mock_manager(&urls, &[Status::None, Status::None, Status::None]);
         let client = create_mock_client(&urls, "Test content");
-        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
         let service = BookmarkService::new(service_config, client, cache);
 
         let res = service.process(&mut bookmark_manager, now).await;
@@ -660,18 +1850,951 @@ mod tests {
             HashMap::from_iter(vec![
                 (
                     "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
-                    "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+                    "Test content".to_owned()
+                ),
+                (
+                    "a4d8f19b-92c1-4e68-a6e9-7d60b54024bc".to_owned(),
+                    "Test content".to_owned()
+                )
+            ])
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 3);
+        assert!(bookmarks.get(&url1).unwrap().last_cached.is_some());
+        assert!(bookmarks.get(&url2).unwrap().last_cached.is_none());
+        assert!(bookmarks.get(&url3).unwrap().last_cached.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_max_concurrent_requests() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let urls = vec![url1, url2, url3];
+        let settings = Settings::default();
+
+        for max_concurrent_requests in [1, 2] {
+            let service_config = ServiceConfig::new(
+                RunMode::Fetch,
+                &settings.ignored_urls,
+                max_concurrent_requests,
+            )
+            .unwrap();
+            let mut bookmark_manager =
+                create_mock_manager(&urls, &[Status::None, Status::None, Status::None]);
+            let client = create_mock_client(&urls, "Test content");
+            let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+            let service = BookmarkService::new(service_config, client, cache);
+
+            let res = service.process(&mut bookmark_manager, now).await;
+            assert!(res.is_ok());
+            assert_eq!(service.client.max_in_flight(), max_concurrent_requests);
+            assert_eq!(service.cache.cache_map().len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_write_manifest() {
+        let now = Utc::now();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1, url2];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_manifest(Some(manifest_path.clone()));
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        let manifest = utils::read_file(&manifest_path).unwrap();
+        let manifest = json::deserialize::<Manifest>(&manifest).unwrap();
+        let expected_entry = |id: &str| {
+            vec![ManifestEntry::new(
+                format!("{id}.txt"),
+                CacheMode::Text,
+                b"Test content",
+            )]
+        };
+        assert_eq!(
+            manifest.bookmarks,
+            BTreeMap::from_iter([
+                (
+                    "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                    expected_entry("dd30381b-8e67-4e84-9379-0852f60a7cd7")
                 ),
                 (
                     "25b6357e-6eda-4367-8212-84376c6efe05".to_owned(),
-                    "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+                    expected_entry("25b6357e-6eda-4367-8212-84376c6efe05")
                 )
             ])
         );
-        assert!(bookmark_manager
-            .target_bookmarks()
-            .values()
-            .all(|bookmark| bookmark.last_cached.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_write_manifest_cache() {
+        let now = Utc::now();
+        let temp_dir = tempdir().unwrap();
+        let cache_path = temp_dir.path().join("cache");
+        fs::create_dir_all(&cache_path).unwrap();
+        let manifest_path = temp_dir.path().join("manifest.json");
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1, url2];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 4)
+            .unwrap()
+            .with_manifest(Some(manifest_path.clone()));
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "<html><body><p>Test content</p></body></html>");
+        let cache = Cache::new(&cache_path, CacheMode::Text);
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let manifest = Manifest::read(&manifest_path).unwrap();
+        assert_eq!(manifest.len(), 2);
+
+        // Every entry matches a cached file.
+        for (id, entries) in &manifest.bookmarks {
+            assert_eq!(entries.len(), 1);
+            let entry = &entries[0];
+            assert_eq!(entry.filename, format!("{id}.txt"));
+
+            let content = fs::read(cache_path.join(&entry.filename)).unwrap();
+            assert_eq!(entry.size, content.len() as u64);
+            assert_eq!(entry.sha256, utils::hash_content(&content));
+        }
+
+        assert!(manifest.verify(&cache_path).unwrap().is_empty());
+
+        // Missing and extra files are reported, but other files are ignored.
+        let missing_filename = format!("{}.txt", manifest.bookmarks.keys().next().unwrap());
+        fs::remove_file(cache_path.join(&missing_filename)).unwrap();
+        fs::write(cache_path.join("extra.txt"), "Extra content").unwrap();
+        fs::write(cache_path.join(".DS_Store"), "").unwrap();
+        assert_eq!(
+            manifest.verify(&cache_path).unwrap(),
+            ManifestMismatch {
+                missing: vec![missing_filename],
+                extra: vec!["extra.txt".to_owned()],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_checkpoint() {
+        let now = Utc::now();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let target_bookmark_path = temp_dir.path().join("bookmarks.json");
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let urls = vec![url1, url2, url3];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 1)
+            .unwrap()
+            .with_checkpoint(Some(2), &target_bookmark_path);
+        let mut bookmark_manager =
+            create_mock_manager(&urls, &[Status::None, Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        // The checkpoint is written after two of the three bookmarks.
+        let checkpoint = utils::read_file(&target_bookmark_path).unwrap();
+        let checkpoint = json::deserialize::<JsonBookmarks>(&checkpoint).unwrap();
+        assert_eq!(checkpoint.len(), 3);
+        assert_eq!(
+            checkpoint
+                .iter()
+                .filter(|bookmark| bookmark.last_cached.is_some())
+                .count(),
+            2
+        );
+        assert!(!temp_dir.path().join("bookmarks.json.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_file_urls() {
+        let now = Utc::now();
+        let temp_dir = tempdir().unwrap();
+        let mut urls = vec![];
+
+        for i in 0..10 {
+            let file_path = temp_dir.path().join(format!("page{i}.html"));
+            fs::write(
+                &file_path,
+                format!("<html><head></head><body><p>Test content {i}</p></body></html>"),
+            )
+            .unwrap();
+            urls.push(Url::from_file_path(&file_path).unwrap());
+        }
+
+        let target_reader_writer = create_target_reader_writer(&TargetBookmarks::default());
+        let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+        let client = Client::new(&ClientConfig::new(&Settings::default())).unwrap();
+        let cache = MockCache::new(CacheMode::Text);
+        let service_config = ServiceConfig::new(RunMode::FetchUrls(urls.clone()), &[], 4).unwrap();
+        let service = BookmarkService::new(service_config, client, cache.clone())
+            .with_strategy(FetchStrategy::Concurrent);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(service.report().cached(), 10);
+
+        let cache_map = cache.cache_map();
+
+        for (i, url) in urls.iter().enumerate() {
+            let bookmark = bookmark_manager.target_bookmarks().get(url).unwrap();
+            assert_eq!(
+                cache_map.get(bookmark.cache_name()),
+                Some(&format!("Test content {i}"))
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_fetch_strategy() {
+        let now = Utc::now();
+        let urls = (0..20)
+            .map(|i| Url::parse(&format!("https://url{i}.com")).unwrap())
+            .collect::<Vec<_>>();
+        let mut results = vec![];
+
+        for strategy in [FetchStrategy::Concurrent, FetchStrategy::Parallel] {
+            let target_reader_writer = create_target_reader_writer(&TargetBookmarks::default());
+            let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+            let client = MockClient::new();
+
+            for (i, url) in urls.iter().enumerate() {
+                client
+                    .add(
+                        format!("<html><head></head><body><p>Test content {i}</p></body></html>"),
+                        url,
+                    )
+                    .unwrap();
+                bookmark_manager.target_bookmarks_mut().insert(
+                    TargetBookmark::builder_with_id(format!("id{i}"), url.to_owned(), now).build(),
+                );
+            }
+
+            let cache = MockCache::new(CacheMode::Text);
+            let service_config = ServiceConfig::new(RunMode::FetchAll, &[], 4).unwrap();
+            let service =
+                BookmarkService::new(service_config, client, cache.clone()).with_strategy(strategy);
+
+            let res = service.process(&mut bookmark_manager, now).await;
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+            assert_eq!(service.report().cached(), 20);
+
+            let bookmarks = bookmark_manager
+                .target_bookmarks()
+                .values_sorted()
+                .into_iter()
+                .map(|bookmark| {
+                    (
+                        bookmark.url().clone(),
+                        bookmark.action().clone(),
+                        bookmark.content_hash().map(|hash| hash.to_owned()),
+                        bookmark.cache_modes().clone(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            results.push((cache.cache_map(), bookmarks));
+        }
+
+        // Both strategies produce the same cache and bookmarks.
+        assert_eq!(results[0].0.len(), 20);
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[tokio::test]
+    async fn test_process_sync_unchanged() {
+        let now = Utc::now();
+        let last_cached = now - chrono::Duration::days(1);
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service_config = ServiceConfig::new(RunMode::Sync, &[], 100).unwrap();
+        let service = BookmarkService::new(service_config, client.clone(), cache.clone());
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        for bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+            assert!(bookmark.content_hash().is_some());
+            bookmark.set_last_cached(last_cached);
+        }
+
+        // Only the content of the second bookmark has changed.
+        client
+            .add(
+                "<html><head></head><body><p>Changed content</p></body></html>".to_owned(),
+                &url2,
+            )
+            .unwrap();
+        let service_config = ServiceConfig::new(RunMode::Sync, &[], 100).unwrap();
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            service.cache.cache_map(),
+            HashMap::from_iter(vec![
+                (
+                    "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                    "Test content".to_owned()
+                ),
+                (
+                    "25b6357e-6eda-4367-8212-84376c6efe05".to_owned(),
+                    "Changed content".to_owned()
+                )
+            ])
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(
+            bookmarks.get(&url1).unwrap().last_cached(),
+            Some(last_cached.timestamp_millis())
+        );
+        assert!(
+            bookmarks.get(&url2).unwrap().last_cached().unwrap() > last_cached.timestamp_millis()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_sync_not_modified() {
+        let now = Utc::now();
+        let last_cached = now - chrono::Duration::days(1);
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let validators = CacheValidators {
+            etag: Some("\"etag1\"".to_owned()),
+            last_modified: None,
+        };
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        client.add_validators(validators.clone(), &url1);
+        let cache = create_mock_cache(CacheMode::Text, None, &mut bookmark_manager).await;
+        let service_config = ServiceConfig::new(RunMode::Sync, &[], 100).unwrap();
+        let service = BookmarkService::new(service_config, client.clone(), cache.clone());
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        let bookmarks = bookmark_manager.target_bookmarks_mut();
+        assert_eq!(bookmarks.get(&url1).unwrap().cache_validators(), validators);
+        assert!(bookmarks.get(&url2).unwrap().cache_validators().is_empty());
+
+        for bookmark in bookmarks.values_mut() {
+            bookmark.set_last_cached(last_cached);
+        }
+
+        // The content of both bookmarks has changed, but the first bookmark is
+        // reported as not modified.
+        for url in &urls {
+            client
+                .add(
+                    "<html><head></head><body><p>Changed content</p></body></html>".to_owned(),
+                    url,
+                )
+                .unwrap();
+        }
+        let service_config = ServiceConfig::new(RunMode::Sync, &[], 100).unwrap();
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(client.fetched_urls().len(), 4);
+        assert_eq!(
+            service.cache.cache_map(),
+            HashMap::from_iter(vec![
+                (
+                    "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                    "Test content".to_owned()
+                ),
+                (
+                    "25b6357e-6eda-4367-8212-84376c6efe05".to_owned(),
+                    "Changed content".to_owned()
+                )
+            ])
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        let bookmark1 = bookmarks.get(&url1).unwrap();
+        assert_eq!(
+            bookmark1.last_cached(),
+            Some(last_cached.timestamp_millis())
+        );
+        assert_eq!(bookmark1.last_status(), Some("ok"));
+        assert!(
+            bookmarks.get(&url2).unwrap().last_cached().unwrap() > last_cached.timestamp_millis()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_oversized_skip() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 100)
+            .unwrap()
+            .with_max_content_size(Some(64), OversizedContent::Skip);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        client
+            .add(
+                format!(
+                    "<html><head></head><body><p>{}</p></body></html>",
+                    "a".repeat(100)
+                ),
+                &url2,
+            )
+            .unwrap();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            service.cache.cache_map(),
+            HashMap::from_iter(vec![(
+                "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+            )])
+        );
+        assert_eq!(service.report.lock().oversized_response, 1);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert!(bookmarks.get(&url1).unwrap().last_cached.is_some());
+        assert!(bookmarks.get(&url2).unwrap().last_cached.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_min_text_length() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 100)
+            .unwrap()
+            .with_min_text_length(Some(20));
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content of a long article");
+        client
+            .add(
+                "<html><head></head><body><p>Accept all cookies</p></body></html>".to_owned(),
+                &url2,
+            )
+            .unwrap();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(service.cache.cache_map().len(), 1);
+        assert_eq!(service.report.lock().thin_response, 1);
+
+        let report = service.report();
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.processed(), 2);
+        assert_eq!(report.cached(), 1);
+        assert_eq!(report.ignored(), 1);
+        assert_eq!(report.thin_response(), 1);
+        assert_eq!(report.failed_response(), 0);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert!(bookmarks.get(&url1).unwrap().last_cached.is_some());
+        let bookmark2 = bookmarks.get(&url2).unwrap();
+        assert!(bookmark2.last_cached.is_none());
+        assert_eq!(bookmark2.last_status(), Some("thin response"));
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_oversized_truncate() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let service_config = ServiceConfig::new(RunMode::Fetch, &[], 100)
+            .unwrap()
+            .with_max_content_size(Some(64), OversizedContent::Truncate);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        client
+            .add(
+                format!(
+                    "<html><head></head><body><p>a{}</p></body></html>",
+                    "ä".repeat(100)
+                ),
+                &url2,
+            )
+            .unwrap();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+
+        // The multi-byte character at the limit is dropped.
+        let expected_html = format!("<html><head></head><body><p>a{}", "ä".repeat(17));
+        assert_eq!(expected_html.len(), 63);
+        assert_eq!(
+            service.cache.cache_map(),
+            HashMap::from_iter(vec![
+                (
+                    "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                    "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+                ),
+                (
+                    "25b6357e-6eda-4367-8212-84376c6efe05".to_owned(),
+                    expected_html
+                )
+            ])
+        );
+        assert_eq!(service.report.lock().oversized_response, 1);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert!(bookmarks.get(&url1).unwrap().last_cached.is_some());
+        assert!(bookmarks.get(&url2).unwrap().last_cached.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_html() {
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![url1, url2];
+        let now = Utc::now();
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap();
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_mock_client(&urls, "Test content");
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client, cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            service.cache.cache_map(),
+            HashMap::from_iter(vec![
+                (
+                    "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+                    "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+                ),
+                (
+                    "25b6357e-6eda-4367-8212-84376c6efe05".to_owned(),
+                    "<html><head></head><body><p>Test content</p></body></html>".to_owned()
+                )
+            ])
+        );
+        assert!(bookmark_manager
+            .target_bookmarks()
+            .values()
+            .all(|bookmark| bookmark.last_cached.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_duplicate_underlyings() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let url2 = Url::parse("https://news.ycombinator.com/item?id=2").unwrap();
+        let underlying_url = Url::parse("https://underlying.com").unwrap();
+        let urls = vec![url1.clone(), url2.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap();
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = MockClient::new();
+
+        for url in &urls {
+            client
+                .add(
+                    format!(
+                        r#"<html><body><span class="titleline"><a href="{underlying_url}">Title</a></span></body></html>"#
+                    ),
+                    url,
+                )
+                .unwrap();
+        }
+
+        client
+            .add(
+                "<html><body><p>Test content</p></body></html>".to_owned(),
+                &underlying_url,
+            )
+            .unwrap();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let fetched_urls = client.fetched_urls();
+        assert_eq!(fetched_urls.len(), 3);
+        assert_eq!(
+            fetched_urls
+                .iter()
+                .filter(|url| *url == &underlying_url)
+                .count(),
+            1
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 3);
+        let underlying_bookmark = bookmarks.get(&underlying_url).unwrap();
+        assert_eq!(
+            underlying_bookmark.sources(),
+            &HashSet::from_iter([
+                SourceType::Underlying(url1.to_string()),
+                SourceType::Underlying(url2.to_string())
+            ])
+        );
+        assert!(underlying_bookmark.last_cached.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_untracked_urls() {
+        let now = Utc::now();
+        let tracked_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let untracked_url = Url::parse("https://news.ycombinator.com/item?id=2").unwrap();
+        let underlying_url1 = Url::parse("https://underlying1.com/page").unwrap();
+        let underlying_url2 = Url::parse("https://underlying2.com/page").unwrap();
+        let urls = vec![tracked_url.clone(), untracked_url.clone()];
+        let service_config = ServiceConfig::new(RunMode::FetchUrls(urls.clone()), &[], 4)
+            .unwrap()
+            .with_untracked_urls(urls);
+        let target_reader_writer = create_target_reader_writer(&TargetBookmarks::default());
+        let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+        bookmark_manager
+            .target_bookmarks_mut()
+            .insert(TargetBookmark::builder(tracked_url.clone(), now).build());
+        let client = MockClient::new();
+
+        for (url, underlying_url) in [
+            (&tracked_url, &underlying_url1),
+            (&untracked_url, &underlying_url2),
+        ] {
+            client
+                .add(
+                    format!(
+                        r#"<html><body><span class="titleline"><a href="{underlying_url}">Title</a></span></body></html>"#
+                    ),
+                    url,
+                )
+                .unwrap();
+            client
+                .add(
+                    "<html><body><p>Test content</p></body></html>".to_owned(),
+                    underlying_url,
+                )
+                .unwrap();
+        }
+
+        let cache = MockCache::new(CacheMode::Text);
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(client.fetched_urls().len(), 4);
+
+        // The missing url and its underlying were fetched, but not added. The
+        // underlying of the tracked url is added.
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 2);
+        assert!(bookmarks.get(&tracked_url).is_some());
+        assert!(bookmarks.get(&underlying_url1).is_some());
+        assert!(bookmarks.get(&untracked_url).is_none());
+        assert!(bookmarks.get(&underlying_url2).is_none());
+    }
+
+    fn create_chained_client(
+        hackernews_url: &Url,
+        reddit_url: &Url,
+        underlying_url: &Url,
+    ) -> MockClient {
+        let client = MockClient::new();
+        client
+            .add(
+                format!(
+                    r#"<html><body><span class="titleline"><a href="{reddit_url}">Title</a></span></body></html>"#
+                ),
+                hackernews_url,
+            )
+            .unwrap();
+        client
+            .add(
+                format!(
+                    r#"<html><body><a href="{underlying_url}" class="styled-outbound-link">Title</a></body></html>"#
+                ),
+                reddit_url,
+            )
+            .unwrap();
+        client
+            .add(
+                "<html><body><p>Test content</p></body></html>".to_owned(),
+                &Url::parse("https://url2.com").unwrap(),
+            )
+            .unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_underlying_depth() {
+        let now = Utc::now();
+        let hackernews_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let reddit_url = Url::parse("https://www.reddit.com/r/rust/comments/1").unwrap();
+        let underlying_url = Url::parse("https://underlying.com").unwrap();
+        let urls = vec![
+            hackernews_url.clone(),
+            Url::parse("https://url2.com").unwrap(),
+        ];
+        let settings = Settings::default();
+
+        for (underlying_depth, expected_fetches) in [(1, 3), (2, 4)] {
+            let service_config = ServiceConfig::new(
+                RunMode::Fetch,
+                &settings.ignored_urls,
+                settings.max_concurrent_requests,
+            )
+            .unwrap()
+            .with_underlying_depth(underlying_depth);
+            let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+            let client = create_chained_client(&hackernews_url, &reddit_url, &underlying_url);
+            client
+                .add(
+                    "<html><body><p>Test content</p></body></html>".to_owned(),
+                    &underlying_url,
+                )
+                .unwrap();
+            let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+            let service = BookmarkService::new(service_config, client.clone(), cache);
+
+            let res = service.process(&mut bookmark_manager, now).await;
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+            assert_eq!(
+                client.fetched_urls().len(),
+                expected_fetches,
+                "Unexpected fetches for underlying depth {underlying_depth}"
+            );
+
+            let bookmarks = bookmark_manager.target_bookmarks();
+            assert!(bookmarks.get(&reddit_url).is_some());
+            assert_eq!(
+                bookmarks.get(&underlying_url).is_some(),
+                underlying_depth == 2
+            );
+        }
+    }
+
+    #[test]
+    fn test_diff_websites() {
+        let before = "line 1\nline 2\nline 3\n";
+
+        let (added, removed) = BookmarkService::<MockCache, MockClient>::diff_websites(
+            before,
+            "line 1\nline 2 changed\nline 3\nline 4\n",
+            false,
+        );
+        assert_eq!((added, removed), (2, 1));
+
+        let (added, removed) =
+            BookmarkService::<MockCache, MockClient>::diff_websites(before, before, false);
+        assert_eq!((added, removed), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_process_dry_run_underlying() {
+        let now = Utc::now();
+        let hackernews_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let underlying_url = Url::parse("https://underlying.com/").unwrap();
+        let urls = vec![
+            hackernews_url.clone(),
+            Url::parse("https://url2.com").unwrap(),
+        ];
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = MockClient::new();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        cache
+            .add(
+                format!(
+                    r#"<html><body><span class="titleline"><a href="{underlying_url}">Title</a></span></body></html>"#
+                ),
+                bookmark_manager
+                    .target_bookmarks_mut()
+                    .get_mut(&hackernews_url)
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let service_config = ServiceConfig::new(RunMode::DryRun, &[], 100).unwrap();
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(client.fetched_urls().is_empty());
+        assert_eq!(
+            *service.dry_run_underlyings.lock(),
+            vec![(hackernews_url, underlying_url.clone())]
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 2);
+        assert!(bookmarks.get(&underlying_url).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_no_underlying() {
+        let now = Utc::now();
+        let hackernews_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let reddit_url = Url::parse("https://www.reddit.com/r/rust/comments/1").unwrap();
+        let underlying_url = Url::parse("https://underlying.com").unwrap();
+        let urls = vec![
+            hackernews_url.clone(),
+            Url::parse("https://url2.com").unwrap(),
+        ];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_resolve_underlying(false);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_chained_client(&hackernews_url, &reddit_url, &underlying_url);
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(client.fetched_urls().len(), 2);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 2);
+        assert!(bookmarks.get(&reddit_url).is_none());
+
+        let hackernews_bookmark = bookmarks.get(&hackernews_url).unwrap();
+        assert!(hackernews_bookmark.underlying_url().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_only_underlying() {
+        let now = Utc::now();
+        let hackernews_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let reddit_url = Url::parse("https://www.reddit.com/r/rust/comments/1").unwrap();
+        let underlying_url = Url::parse("https://underlying.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let urls = vec![hackernews_url.clone(), url2.clone()];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_underlying_depth(2)
+        .with_only_underlying(true);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        let client = create_chained_client(&hackernews_url, &reddit_url, &underlying_url);
+        client
+            .add(
+                "<html><body><p>Underlying content</p></body></html>".to_owned(),
+                &underlying_url,
+            )
+            .unwrap();
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(client.fetched_urls().len(), 4);
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        let hackernews_bookmark = bookmarks.get(&hackernews_url).unwrap();
+        assert_eq!(hackernews_bookmark.underlying_url(), Some(&reddit_url));
+        assert!(!service.cache.exists(hackernews_bookmark));
+
+        let reddit_bookmark = bookmarks.get(&reddit_url).unwrap();
+        assert_eq!(reddit_bookmark.underlying_url(), Some(&underlying_url));
+        assert!(!service.cache.exists(reddit_bookmark));
+
+        let underlying_bookmark = bookmarks.get(&underlying_url).unwrap();
+        assert!(service.cache.exists(underlying_bookmark));
+        assert!(service.cache.exists(bookmarks.get(&url2).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_process_fetch_underlying_cycle() {
+        let now = Utc::now();
+        let hackernews_url = Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        let reddit_url = Url::parse("https://www.reddit.com/r/rust/comments/1").unwrap();
+        let urls = vec![
+            hackernews_url.clone(),
+            Url::parse("https://url2.com").unwrap(),
+        ];
+        let settings = Settings::default();
+        let service_config = ServiceConfig::new(
+            RunMode::Fetch,
+            &settings.ignored_urls,
+            settings.max_concurrent_requests,
+        )
+        .unwrap()
+        .with_underlying_depth(3);
+        let mut bookmark_manager = create_mock_manager(&urls, &[Status::None, Status::None]);
+        // The Reddit post links back to the Hacker News post.
+        let client = create_chained_client(&hackernews_url, &reddit_url, &hackernews_url);
+        let cache = create_mock_cache(CacheMode::Html, None, &mut bookmark_manager).await;
+        let service = BookmarkService::new(service_config, client.clone(), cache);
+
+        let res = service.process(&mut bookmark_manager, now).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let mut fetched_urls = client.fetched_urls();
+        fetched_urls.sort();
+        assert_eq!(
+            fetched_urls,
+            vec![hackernews_url.clone(), urls[1].clone(), reddit_url.clone()]
+        );
+
+        let bookmarks = bookmark_manager.target_bookmarks();
+        assert_eq!(bookmarks.len(), 3);
+        let hackernews_bookmark = bookmarks.get(&hackernews_url).unwrap();
+        assert_eq!(
+            hackernews_bookmark.id(),
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7"
+        );
+        assert!(hackernews_bookmark
+            .sources()
+            .contains(&SourceType::Underlying(reddit_url.to_string())));
     }
 
     #[tokio::test]
@@ -2,21 +2,27 @@ mod bookmark_manager;
 mod bookmark_service;
 mod source_bookmarks;
 mod target_bookmarks;
+mod url_filter;
 
-use crate::CacheMode;
+use crate::{CacheMode, OversizedContent};
 pub use bookmark_manager::BookmarkManager;
-pub use bookmark_service::{BookmarkService, ServiceConfig};
+pub use bookmark_service::{BookmarkService, FetchStrategy, ServiceConfig};
+use clap::ValueEnum;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use serde::{Deserialize, Serialize};
 pub use source_bookmarks::{SourceBookmark, SourceBookmarkBuilder, SourceBookmarks};
 use std::{
+    cell::RefCell,
     cmp::Ordering,
     collections::HashSet,
     fmt,
+    io::{self, IsTerminal},
     path::{Path, PathBuf},
     slice::Iter,
 };
 pub use target_bookmarks::{TargetBookmark, TargetBookmarkBuilder, TargetBookmarks};
 use url::Url;
+pub use url_filter::UrlFilter;
 use uuid::Uuid;
 
 pub const HACKER_NEWS_DOMAINS: &[&str] = &["news.ycombinator.com", "www.news.ycombinator.com"];
@@ -39,10 +45,16 @@ pub enum SourceType {
     Chrome,
     Edge,
     Safari,
+    Pocket,
     Simple,
+    Csv,
+    Netscape,
     Underlying(String),
     Internal,
     External,
+    /// A custom label of the source, e.g. to distinguish a work and a personal
+    /// list of bookmarks.
+    Custom(String),
     #[default]
     Unknown,
 }
@@ -56,10 +68,14 @@ impl fmt::Display for SourceType {
             SourceType::Chrome => "Chrome",
             SourceType::Edge => "Edge",
             SourceType::Safari => "Safari",
+            SourceType::Pocket => "Pocket",
             SourceType::Simple => "Simple",
+            SourceType::Csv => "CSV",
+            SourceType::Netscape => "Netscape",
             SourceType::Underlying(_) => "Underlying",
             SourceType::Internal => "Internal",
             SourceType::External => "External",
+            SourceType::Custom(label) => label,
             SourceType::Unknown => "Unknown",
         };
         write!(f, "{}", reader_name)
@@ -141,6 +157,8 @@ pub enum RunMode {
     FetchAllUrls(Vec<Url>),
     /// Fetch bookmarks if not fetched yet.
     Fetch,
+    /// Import bookmarks, and fetch only the bookmarks added by the import.
+    FetchNew,
     /// Fetch and replace bookmarks.
     FetchAll,
     /// Fetch diff for provided bookmark urls.
@@ -153,6 +171,32 @@ pub enum RunMode {
     None,
 }
 
+/// The format of a source file.
+#[derive(Debug, ValueEnum, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceFormat {
+    Json,
+    Jsonlz4,
+    Plist,
+    Txt,
+    Csv,
+    Html,
+}
+
+impl fmt::Display for SourceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match &self {
+            SourceFormat::Json => "json",
+            SourceFormat::Jsonlz4 => "jsonlz4",
+            SourceFormat::Plist => "plist",
+            SourceFormat::Txt => "txt",
+            SourceFormat::Csv => "csv",
+            SourceFormat::Html => "html",
+        };
+        write!(f, "{}", format)
+    }
+}
+
 /// The source of bookmarks.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct RawSource {
@@ -164,6 +208,15 @@ pub struct RawSource {
     /// If no folders are selected, all bookmarks in the source file will be
     /// imported.
     pub folders: Vec<String>,
+    /// The format of the source file.
+    ///
+    /// If no format is given, the format is detected by the file extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<SourceFormat>,
+    /// A custom label which is stored as source type of the imported
+    /// bookmarks instead of the detected source type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 impl RawSource {
@@ -171,8 +224,20 @@ impl RawSource {
         Self {
             path: path.into(),
             folders,
+            format: None,
+            label: None,
         }
     }
+
+    pub fn with_format(mut self, format: Option<SourceFormat>) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -186,6 +251,13 @@ pub struct Source {
     /// If no folders are selected, all bookmarks in the source file will be
     /// imported.
     pub folders: Vec<String>,
+    /// A custom label overriding the detected source type.
+    pub label: Option<String>,
+    /// The folder assigned to all bookmarks of the source, e.g. the filename
+    /// of a text file in a directory of text files.
+    pub folder: Option<String>,
+    /// The selected folders which were found in the source during import.
+    matched_folders: RefCell<HashSet<String>>,
 }
 
 impl Source {
@@ -194,12 +266,42 @@ impl Source {
             source_type,
             path: path.to_owned(),
             folders,
+            label: None,
+            folder: None,
+            matched_folders: RefCell::new(HashSet::new()),
+        }
+    }
+
+    pub fn with_label(mut self, label: Option<String>) -> Self {
+        self.label = label;
+        self
+    }
+
+    pub fn with_folder(mut self, folder: Option<String>) -> Self {
+        self.folder = folder;
+        self
+    }
+
+    /// Mark a folder as found in the source if it is selected.
+    pub fn match_folder(&self, folder: &str) {
+        if self.folders.iter().any(|selected| selected == folder) {
+            self.matched_folders.borrow_mut().insert(folder.to_owned());
         }
     }
+
+    /// The selected folders which were not found in the source.
+    pub fn unmatched_folders(&self) -> Vec<&str> {
+        let matched_folders = self.matched_folders.borrow();
+        self.folders
+            .iter()
+            .filter(|folder| !matched_folders.contains(*folder))
+            .map(|folder| folder.as_str())
+            .collect()
+    }
 }
 
 /// The source folder of a bookmark.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SourceFolder {
     source: SourceType,
     name: String,
@@ -227,6 +329,37 @@ pub struct JsonBookmark {
     pub last_cached: Option<i64>,
     pub sources: HashSet<SourceType>,
     pub cache_modes: HashSet<CacheMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The folders assigned via `bogrep add --folder`.
+    ///
+    /// The folders of imported bookmarks are not persisted, because they are
+    /// read from the sources on each import.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub folders: HashSet<SourceFolder>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removed_at: Option<i64>,
+}
+
+/// The folders of a bookmark which are persisted in `bookmarks.json`.
+fn persisted_folders(folders: &HashSet<SourceFolder>) -> HashSet<SourceFolder> {
+    folders
+        .iter()
+        .filter(|folder| folder.source() == &SourceType::Internal)
+        .cloned()
+        .collect()
 }
 
 impl JsonBookmark {
@@ -244,6 +377,15 @@ impl JsonBookmark {
             last_cached,
             sources,
             cache_modes,
+            title: None,
+            folders: HashSet::new(),
+            content_hash: None,
+            last_status: None,
+            etag: None,
+            last_modified: None,
+            filename: None,
+            resolved_url: None,
+            removed_at: None,
         }
     }
 }
@@ -257,6 +399,21 @@ impl From<TargetBookmark> for JsonBookmark {
             last_cached: value.last_cached(),
             sources: value.sources().to_owned(),
             cache_modes: value.cache_modes().to_owned(),
+            title: value.title().map(|title| title.to_owned()),
+            folders: persisted_folders(value.source_folders()),
+            content_hash: value
+                .content_hash()
+                .map(|content_hash| content_hash.to_owned()),
+            last_status: value
+                .last_status()
+                .map(|last_status| last_status.to_owned()),
+            etag: value.etag.clone(),
+            last_modified: value.last_modified.clone(),
+            filename: value.filename.clone(),
+            resolved_url: value
+                .resolved_url()
+                .map(|resolved_url| resolved_url.to_string()),
+            removed_at: value.removed_at(),
         }
     }
 }
@@ -270,6 +427,21 @@ impl From<&TargetBookmark> for JsonBookmark {
             last_cached: value.last_cached(),
             sources: value.sources().clone(),
             cache_modes: value.cache_modes().clone(),
+            title: value.title().map(|title| title.to_owned()),
+            folders: persisted_folders(value.source_folders()),
+            content_hash: value
+                .content_hash()
+                .map(|content_hash| content_hash.to_owned()),
+            last_status: value
+                .last_status()
+                .map(|last_status| last_status.to_owned()),
+            etag: value.etag.clone(),
+            last_modified: value.last_modified.clone(),
+            filename: value.filename.clone(),
+            resolved_url: value
+                .resolved_url()
+                .map(|resolved_url| resolved_url.to_string()),
+            removed_at: value.removed_at(),
         }
     }
 }
@@ -377,7 +549,19 @@ pub struct ServiceReport {
     failed_response: i32,
     binary_response: i32,
     empty_response: i32,
+    oversized_response: i32,
+    thin_response: i32,
+    /// The number of bookmarks disallowed by `robots.txt`.
+    disallowed: i32,
+    /// The behavior for oversized websites.
+    oversized_content: OversizedContent,
+    /// Cache empty websites instead of skipping them.
+    cache_empty: bool,
     dry_run: bool,
+    /// Show a progress bar instead of the plain progress if stdout is a
+    /// terminal.
+    show_progress_bar: bool,
+    progress_bar: Option<ProgressBar>,
 }
 
 impl ServiceReport {
@@ -397,7 +581,14 @@ impl ServiceReport {
             failed_response,
             binary_response,
             empty_response,
+            oversized_response: 0,
+            thin_response: 0,
+            disallowed: 0,
+            oversized_content: OversizedContent::default(),
+            cache_empty: false,
             dry_run,
+            show_progress_bar: false,
+            progress_bar: None,
         }
     }
 
@@ -405,12 +596,32 @@ impl ServiceReport {
         Self::new(0, 0, 0, 0, 0, 0, dry_run)
     }
 
+    pub fn set_oversized_content(&mut self, oversized_content: OversizedContent) {
+        self.oversized_content = oversized_content;
+    }
+
+    pub fn set_cache_empty(&mut self, cache_empty: bool) {
+        self.cache_empty = cache_empty;
+    }
+
+    /// Enable the progress bar if stdout is a terminal.
+    pub fn enable_progress_bar(&mut self) {
+        self.show_progress_bar = io::stdout().is_terminal();
+    }
+
     pub fn print(&self) {
-        print!("Processing bookmarks ({}/{})\r", self.processed, self.total);
+        if let Some(progress_bar) = &self.progress_bar {
+            progress_bar.set_position(self.processed as u64);
+        } else {
+            print!("Processing bookmarks ({}/{})\r", self.processed, self.total);
+        }
     }
 
-    pub fn print_summary(&self) {
-        if self.total == 0 {
+    pub fn print_summary(&mut self) {
+        if let Some(progress_bar) = self.progress_bar.take() {
+            progress_bar.finish_and_clear();
+            println!("Processing bookmarks ({}/{})", self.processed, self.total);
+        } else if self.total == 0 {
             println!("Processing bookmarks (0/0)");
         } else {
             println!();
@@ -426,23 +637,72 @@ impl ServiceReport {
                 "Processed {} bookmarks, {} cached, {} ignored, {} failed",
                 self.total,
                 self.cached,
-                self.binary_response + self.empty_response,
+                self.ignored(),
                 self.failed_response,
             );
+
+            if self.empty_response > 0 {
+                if self.cache_empty {
+                    println!("Cached {} empty bookmarks", self.empty_response)
+                } else {
+                    println!("Skipped {} empty bookmarks", self.empty_response)
+                }
+            }
+
+            if self.oversized_response > 0 {
+                match self.oversized_content {
+                    OversizedContent::Skip => {
+                        println!("Skipped {} oversized bookmarks", self.oversized_response)
+                    }
+                    OversizedContent::Truncate => {
+                        println!("Truncated {} oversized bookmarks", self.oversized_response)
+                    }
+                }
+            }
+
+            if self.thin_response > 0 {
+                println!("Skipped {} thin bookmarks", self.thin_response)
+            }
+
+            if self.disallowed > 0 {
+                println!(
+                    "Skipped {} bookmarks disallowed by robots.txt",
+                    self.disallowed
+                )
+            }
         }
     }
 
     pub fn reset(&mut self) {
+        if let Some(progress_bar) = self.progress_bar.take() {
+            progress_bar.finish_and_clear();
+        }
+
         self.total = 0;
         self.processed = 0;
         self.cached = 0;
         self.failed_response = 0;
         self.binary_response = 0;
         self.empty_response = 0;
+        self.oversized_response = 0;
+        self.thin_response = 0;
+        self.disallowed = 0;
     }
 
     pub fn set_total(&mut self, total: usize) {
         self.total = total;
+
+        if self.show_progress_bar && total > 0 {
+            let progress_bar =
+                ProgressBar::with_draw_target(Some(total as u64), ProgressDrawTarget::stdout());
+            progress_bar.set_style(
+                ProgressStyle::with_template(
+                    "Processing bookmarks {bar:40} {pos}/{len} ({per_sec}, ETA {eta})",
+                )
+                .expect("Invalid progress bar template"),
+            );
+            self.progress_bar = Some(progress_bar);
+        }
     }
 
     pub fn increment_processed(&mut self) {
@@ -464,4 +724,244 @@ impl ServiceReport {
     pub fn increment_empty_response(&mut self) {
         self.empty_response += 1;
     }
+
+    pub fn increment_oversized_response(&mut self) {
+        self.oversized_response += 1;
+    }
+
+    pub fn increment_thin_response(&mut self) {
+        self.thin_response += 1;
+    }
+
+    pub fn set_disallowed(&mut self, disallowed: usize) {
+        self.disallowed = disallowed as i32;
+    }
+
+    /// The number of bookmarks to be processed.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The number of bookmarks which were processed.
+    pub fn processed(&self) -> usize {
+        self.processed as usize
+    }
+
+    /// The number of bookmarks which were cached.
+    pub fn cached(&self) -> usize {
+        self.cached as usize
+    }
+
+    /// The number of bookmarks which couldn't be fetched or cached.
+    pub fn failed_response(&self) -> usize {
+        self.failed_response as usize
+    }
+
+    /// The number of bookmarks with binary content.
+    pub fn binary_response(&self) -> usize {
+        self.binary_response as usize
+    }
+
+    /// The number of bookmarks with empty content.
+    pub fn empty_response(&self) -> usize {
+        self.empty_response as usize
+    }
+
+    /// The number of bookmarks exceeding the maximum content size.
+    pub fn oversized_response(&self) -> usize {
+        self.oversized_response as usize
+    }
+
+    /// The number of bookmarks with less text than the minimum text length.
+    pub fn thin_response(&self) -> usize {
+        self.thin_response as usize
+    }
+
+    /// The number of bookmarks disallowed by `robots.txt`.
+    pub fn disallowed(&self) -> usize {
+        self.disallowed as usize
+    }
+
+    /// The number of bookmarks which were skipped, i.e. binary, thin, and, if
+    /// configured to be skipped, empty and oversized bookmarks.
+    pub fn ignored(&self) -> usize {
+        let skipped_oversized = match self.oversized_content {
+            OversizedContent::Skip => self.oversized_response(),
+            OversizedContent::Truncate => 0,
+        };
+        let skipped_empty = if self.cache_empty {
+            0
+        } else {
+            self.empty_response()
+        };
+
+        self.binary_response() + skipped_empty + skipped_oversized + self.thin_response()
+    }
+
+    /// A snapshot of the counts of the processed bookmarks.
+    pub fn process_report(&self) -> ProcessReport {
+        ProcessReport {
+            total: self.total(),
+            processed: self.processed(),
+            cached: self.cached(),
+            ignored: self.ignored(),
+            failed_response: self.failed_response(),
+            binary_response: self.binary_response(),
+            empty_response: self.empty_response(),
+            oversized_response: self.oversized_response(),
+            thin_response: self.thin_response(),
+            disallowed: self.disallowed(),
+        }
+    }
+}
+
+/// The counts of the bookmarks processed by a [`BookmarkService`], e.g. to
+/// inspect the results after [`BookmarkService::process`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessReport {
+    total: usize,
+    processed: usize,
+    cached: usize,
+    ignored: usize,
+    failed_response: usize,
+    binary_response: usize,
+    empty_response: usize,
+    oversized_response: usize,
+    thin_response: usize,
+    disallowed: usize,
+}
+
+impl ProcessReport {
+    /// The number of bookmarks to be processed.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The number of bookmarks which were processed.
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
+
+    /// The number of bookmarks which were cached.
+    pub fn cached(&self) -> usize {
+        self.cached
+    }
+
+    /// The number of bookmarks which were skipped.
+    pub fn ignored(&self) -> usize {
+        self.ignored
+    }
+
+    /// The number of bookmarks which couldn't be fetched or cached.
+    pub fn failed_response(&self) -> usize {
+        self.failed_response
+    }
+
+    /// The number of bookmarks with binary content.
+    pub fn binary_response(&self) -> usize {
+        self.binary_response
+    }
+
+    /// The number of bookmarks with empty content.
+    pub fn empty_response(&self) -> usize {
+        self.empty_response
+    }
+
+    /// The number of bookmarks exceeding the maximum content size.
+    pub fn oversized_response(&self) -> usize {
+        self.oversized_response
+    }
+
+    /// The number of bookmarks with less text than the minimum text length.
+    pub fn thin_response(&self) -> usize {
+        self.thin_response
+    }
+
+    /// The number of bookmarks disallowed by `robots.txt`.
+    pub fn disallowed(&self) -> usize {
+        self.disallowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use chrono::Utc;
+
+    #[test]
+    fn test_source_type_display() {
+        assert_eq!(SourceType::Firefox.to_string(), "Firefox");
+        assert_eq!(SourceType::Custom("work".to_owned()).to_string(), "work");
+        assert_eq!(
+            SourceType::Custom("personal list".to_owned()).to_string(),
+            "personal list"
+        );
+    }
+
+    #[test]
+    fn test_source_type_serialization() {
+        let sources = HashSet::from([
+            SourceType::Simple,
+            SourceType::Custom("work".to_owned()),
+            SourceType::Custom("personal".to_owned()),
+        ]);
+        let bookmark = JsonBookmark::new(
+            "https://url1.com".to_owned(),
+            1694989714351,
+            None,
+            sources.clone(),
+            HashSet::new(),
+        );
+        let bookmarks = JsonBookmarks {
+            bookmarks: vec![bookmark],
+        };
+
+        let serialized = json::serialize(&bookmarks).unwrap();
+        let serialized_str = String::from_utf8(serialized.clone()).unwrap();
+        assert!(serialized_str.contains(r#""Custom": "work""#));
+
+        let deserialized = json::deserialize::<JsonBookmarks>(&serialized).unwrap();
+        assert_eq!(deserialized, bookmarks);
+        assert_eq!(deserialized.iter().next().unwrap().sources, sources);
+    }
+
+    #[test]
+    fn test_underlying_type_supported_domains() {
+        // The underlying is resolved for any url of a supported domain without
+        // configuring the url.
+        for domain in SUPPORTED_UNDERLYING_DOMAINS {
+            let url = Url::parse(&format!("https://{domain}/item?id=00000000")).unwrap();
+            let bookmark = TargetBookmarkBuilder::new(url.clone(), Utc::now()).build();
+            assert_ne!(bookmark.underlying_type(), &UnderlyingType::None, "{url}");
+        }
+
+        let url = Url::parse("https://url1.com").unwrap();
+        assert_eq!(UnderlyingType::from(&url), UnderlyingType::None);
+    }
+    #[test]
+    fn test_json_bookmark_folders() {
+        let url = Url::parse("https://url1.com").unwrap();
+        let internal_folder = SourceFolder::new(SourceType::Internal, "dev".to_owned());
+        let bookmark = TargetBookmarkBuilder::new(url.clone(), Utc::now())
+            .with_folders(HashSet::from([
+                SourceFolder::new(SourceType::Firefox, "science".to_owned()),
+                internal_folder.clone(),
+            ]))
+            .build();
+
+        // Only the internal folders are persisted.
+        let json_bookmark = JsonBookmark::from(&bookmark);
+        assert_eq!(json_bookmark.folders, HashSet::from([internal_folder]));
+
+        // The folders of imported bookmarks are not serialized.
+        let bookmark = TargetBookmarkBuilder::new(url, Utc::now())
+            .with_folders(HashSet::from([SourceFolder::new(
+                SourceType::Firefox,
+                "science".to_owned(),
+            )]))
+            .build();
+        let serialized = json::serialize(&JsonBookmark::from(bookmark)).unwrap();
+        assert!(!String::from_utf8(serialized).unwrap().contains("folders"));
+    }
 }
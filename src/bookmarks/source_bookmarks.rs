@@ -1,10 +1,14 @@
 use super::SourceFolder;
-use crate::SourceType;
+use crate::{utils, SourceType};
 use log::debug;
-use std::collections::{
-    hash_map::{Entry, IntoIter, Iter, IterMut, Keys},
-    HashMap, HashSet,
+use std::{
+    collections::{
+        hash_map::{Entry, IntoIter, Iter, IterMut, Keys},
+        HashMap, HashSet,
+    },
+    mem,
 };
+use url::Url;
 
 /// A bookmark from a specific source, like Firefox or Chrome.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -121,6 +125,14 @@ impl SourceBookmarks {
         self.0.contains_key(url)
     }
 
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn iter(&self) -> Iter<String, SourceBookmark> {
         self.0.iter()
     }
@@ -159,6 +171,21 @@ impl SourceBookmarks {
             }
         }
     }
+
+    /// Strip the matching query parameters from the urls, which merges the
+    /// bookmarks whose urls differ only in the stripped query parameters.
+    pub fn strip_query(&mut self, params: &[String], include: &[String]) {
+        for (_, mut bookmark) in mem::take(&mut self.0) {
+            if let Ok(mut url) = Url::parse(&bookmark.url) {
+                if utils::strip_tracking_params(&mut url, params, include) {
+                    debug!("Strip query of source bookmark: {}", bookmark.url);
+                    bookmark.url = url.to_string();
+                }
+            }
+
+            self.insert(bookmark);
+        }
+    }
 }
 
 impl IntoIterator for SourceBookmarks {
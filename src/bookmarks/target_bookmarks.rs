@@ -1,5 +1,7 @@
 use super::{Action, JsonBookmark, SourceFolder, Status};
-use crate::{cache::CacheMode, SourceBookmarks, SourceType, UnderlyingType};
+use crate::{
+    cache::CacheMode, utils, CacheValidators, SourceBookmarks, SourceType, UnderlyingType,
+};
 use chrono::{DateTime, Utc};
 use log::debug;
 use std::collections::{
@@ -17,6 +19,11 @@ pub struct TargetBookmark {
     pub id: String,
     /// The url of the bookmark.
     pub url: Url,
+    /// The final url after following redirects if it differs from the url of
+    /// the bookmark.
+    pub resolved_url: Option<Url>,
+    /// The title of the bookmark.
+    pub title: Option<String>,
     /// The url of the underlying for supported `UnderlyingType`s.
     pub underlying_url: Option<Url>,
     /// The type of the underlying.
@@ -25,12 +32,26 @@ pub struct TargetBookmark {
     pub last_imported: i64,
     /// The timestamp in milliseconds when the bookmark was added to the cache.
     pub last_cached: Option<i64>,
+    /// The timestamp in milliseconds when the bookmark was removed from its
+    /// sources if removed bookmarks are kept for a grace period.
+    pub removed_at: Option<i64>,
     /// The source or sources this bookmark was imported from.
     pub sources: HashSet<SourceType>,
     /// The folder locations from which this bookmark was imported.
     pub source_folders: HashSet<SourceFolder>,
     /// The file format for the cached bookmark.
     pub cache_modes: HashSet<CacheMode>,
+    /// The hash of the cached content to detect changes when fetching again.
+    pub content_hash: Option<String>,
+    /// The outcome of the last fetch, e.g. `ok`, `timeout`, or `status 404`.
+    pub last_status: Option<String>,
+    /// The `ETag` header of the last fetch.
+    pub etag: Option<String>,
+    /// The `Last-Modified` header of the last fetch.
+    pub last_modified: Option<String>,
+    /// The human-readable filename of the cached bookmark, which is used
+    /// instead of the id if present.
+    pub filename: Option<String>,
     /// The status of an imported bookmark.
     pub status: Status,
     /// The action performed when processing [`TargetBookmark`] in
@@ -57,13 +78,21 @@ impl TargetBookmark {
         Self {
             id: Uuid::new_v4().to_string(),
             url,
+            resolved_url: None,
+            title: None,
             underlying_url: None,
             underlying_type,
             last_imported: last_imported.timestamp_millis(),
             last_cached: None,
+            removed_at: None,
             sources: HashSet::new(),
             source_folders: HashSet::new(),
             cache_modes: HashSet::new(),
+            content_hash: None,
+            last_status: None,
+            etag: None,
+            last_modified: None,
+            filename: None,
             status: Status::None,
             action: Action::None,
         }
@@ -77,6 +106,14 @@ impl TargetBookmark {
         &self.url
     }
 
+    pub fn resolved_url(&self) -> Option<&Url> {
+        self.resolved_url.as_ref()
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
     pub fn underlying_url(&self) -> Option<&Url> {
         self.underlying_url.as_ref()
     }
@@ -93,6 +130,10 @@ impl TargetBookmark {
         self.last_cached
     }
 
+    pub fn removed_at(&self) -> Option<i64> {
+        self.removed_at
+    }
+
     pub fn status(&self) -> &Status {
         &self.status
     }
@@ -105,14 +146,70 @@ impl TargetBookmark {
         &self.sources
     }
 
+    pub fn source_folders(&self) -> &HashSet<SourceFolder> {
+        &self.source_folders
+    }
+
     pub fn cache_modes(&self) -> &HashSet<CacheMode> {
         &self.cache_modes
     }
 
+    pub fn content_hash(&self) -> Option<&str> {
+        self.content_hash.as_deref()
+    }
+
+    pub fn last_status(&self) -> Option<&str> {
+        self.last_status.as_deref()
+    }
+
+    pub fn cache_validators(&self) -> CacheValidators {
+        CacheValidators {
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+        }
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    /// The name of the cached files, which is the human-readable filename if
+    /// present, or the id otherwise.
+    pub fn cache_name(&self) -> &str {
+        self.filename.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn set_filename(&mut self, filename: String) {
+        self.filename = Some(filename);
+    }
+
     pub fn set_url(&mut self, url: Url) {
         self.url = url;
     }
 
+    /// Set the final url after following redirects, which is only stored if
+    /// it differs from the url of the bookmark.
+    pub fn set_resolved_url(&mut self, resolved_url: Option<Url>) {
+        self.resolved_url = resolved_url.filter(|resolved_url| resolved_url != &self.url);
+    }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title);
+    }
+
+    pub fn set_content_hash(&mut self, content_hash: String) {
+        self.content_hash = Some(content_hash);
+    }
+
+    pub fn set_last_status(&mut self, last_status: impl Into<String>) {
+        self.last_status = Some(last_status.into());
+    }
+
+    pub fn set_cache_validators(&mut self, validators: CacheValidators) {
+        self.etag = validators.etag;
+        self.last_modified = validators.last_modified;
+    }
+
     pub fn set_underlying_url(&mut self, underlying_url: Url) {
         self.underlying_url = Some(underlying_url);
     }
@@ -129,6 +226,14 @@ impl TargetBookmark {
         self.last_cached = None;
     }
 
+    pub fn set_removed_at(&mut self, removed_at: DateTime<Utc>) {
+        self.removed_at = Some(removed_at.timestamp_millis());
+    }
+
+    pub fn unset_removed_at(&mut self) {
+        self.removed_at = None;
+    }
+
     pub fn set_status(&mut self, status: Status) {
         self.status = status;
     }
@@ -141,6 +246,10 @@ impl TargetBookmark {
         self.sources.insert(source);
     }
 
+    pub fn add_folder(&mut self, folder: SourceFolder) {
+        self.source_folders.insert(folder);
+    }
+
     pub fn add_cache_mode(&mut self, cache_mode: CacheMode) {
         self.cache_modes.insert(cache_mode);
     }
@@ -149,6 +258,58 @@ impl TargetBookmark {
         self.cache_modes.remove(cache_mode);
     }
 
+    /// Merge a bookmark with the same url, e.g. from the `bookmarks.json` of
+    /// another machine, and return `true` if the bookmark changed.
+    ///
+    /// `is_cached` checks if the cached file of a bookmark exists locally.
+    /// The id, the filename, and the cache state of this bookmark are kept if
+    /// it is cached, so that its cached file isn't orphaned. Otherwise, they
+    /// are taken from the other bookmark if that one is cached, or the cache
+    /// state is cleared.
+    pub fn merge(
+        &mut self,
+        other: TargetBookmark,
+        is_cached: impl Fn(&TargetBookmark) -> bool,
+    ) -> bool {
+        let before = self.clone();
+
+        if !is_cached(self) {
+            if is_cached(&other) {
+                self.id = other.id;
+                self.filename = other.filename;
+                self.resolved_url = other.resolved_url;
+                self.last_cached = other.last_cached;
+                self.cache_modes = other.cache_modes;
+                self.content_hash = other.content_hash;
+                self.last_status = other.last_status;
+                self.etag = other.etag;
+                self.last_modified = other.last_modified;
+            } else {
+                self.last_cached = None;
+                self.cache_modes.clear();
+            }
+        }
+
+        if self.title.is_none() {
+            self.title = other.title;
+        }
+
+        if self.underlying_url.is_none() {
+            self.underlying_url = other.underlying_url;
+        }
+
+        // A bookmark is only kept as removed if it was removed on both sides.
+        self.removed_at = self
+            .removed_at
+            .zip(other.removed_at)
+            .map(|(removed_at, other_removed_at)| removed_at.max(other_removed_at));
+        self.last_imported = self.last_imported.max(other.last_imported);
+        self.sources.extend(other.sources);
+        self.source_folders.extend(other.source_folders);
+
+        *self != before
+    }
+
     pub fn clear_cache_mode(&mut self) {
         self.cache_modes.clear();
     }
@@ -157,12 +318,17 @@ impl TargetBookmark {
 pub struct TargetBookmarkBuilder {
     id: String,
     url: Url,
+    title: Option<String>,
     underlying_url: Option<Url>,
     last_imported: DateTime<Utc>,
     last_cached: Option<DateTime<Utc>>,
     sources: HashSet<SourceType>,
     source_folders: HashSet<SourceFolder>,
     cache_modes: HashSet<CacheMode>,
+    content_hash: Option<String>,
+    last_status: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
     status: Status,
     action: Action,
 }
@@ -172,12 +338,17 @@ impl TargetBookmarkBuilder {
         TargetBookmarkBuilder {
             id: Uuid::new_v4().to_string(),
             url,
+            title: None,
             underlying_url: None,
             last_imported,
             last_cached: None,
             sources: HashSet::new(),
             source_folders: HashSet::new(),
             cache_modes: HashSet::new(),
+            content_hash: None,
+            last_status: None,
+            etag: None,
+            last_modified: None,
             status: Status::None,
             action: Action::None,
         }
@@ -191,17 +362,27 @@ impl TargetBookmarkBuilder {
         TargetBookmarkBuilder {
             id,
             url,
+            title: None,
             underlying_url: None,
             last_imported,
             last_cached: None,
             sources: HashSet::new(),
             source_folders: HashSet::new(),
             cache_modes: HashSet::new(),
+            content_hash: None,
+            last_status: None,
+            etag: None,
+            last_modified: None,
             status: Status::None,
             action: Action::None,
         }
     }
 
+    pub fn with_title(mut self, title: String) -> TargetBookmarkBuilder {
+        self.title = Some(title);
+        self
+    }
+
     pub fn with_status(mut self, status: Status) -> TargetBookmarkBuilder {
         self.status = status;
         self
@@ -238,15 +419,23 @@ impl TargetBookmarkBuilder {
         TargetBookmark {
             id: self.id,
             url: self.url,
+            resolved_url: None,
+            title: self.title,
             underlying_url: self.underlying_url,
             underlying_type,
             last_imported: self.last_imported.timestamp_millis(),
             last_cached: self
                 .last_cached
                 .map(|timestamp| timestamp.timestamp_millis()),
+            removed_at: None,
             sources: self.sources,
             source_folders: self.source_folders,
             cache_modes: self.cache_modes,
+            content_hash: self.content_hash,
+            last_status: self.last_status,
+            etag: self.etag,
+            last_modified: self.last_modified,
+            filename: None,
             status: self.status,
             action: self.action,
         }
@@ -260,16 +449,28 @@ impl TryFrom<JsonBookmark> for TargetBookmark {
         let url = Url::parse(&value.url)?;
         let underlying_type = UnderlyingType::from(&url);
 
+        let resolved_url = value
+            .resolved_url
+            .and_then(|resolved_url| Url::parse(&resolved_url).ok());
+
         Ok(Self {
             id: value.id,
             url,
+            resolved_url,
+            title: value.title,
             underlying_url: None,
             underlying_type,
             last_imported: value.last_imported,
             last_cached: value.last_cached,
+            removed_at: value.removed_at,
             sources: value.sources,
-            source_folders: HashSet::new(),
+            source_folders: value.folders,
             cache_modes: value.cache_modes,
+            content_hash: value.content_hash,
+            last_status: value.last_status,
+            etag: value.etag,
+            last_modified: value.last_modified,
+            filename: value.filename,
             status: Status::None,
             action: Action::None,
         })
@@ -278,7 +479,7 @@ impl TryFrom<JsonBookmark> for TargetBookmark {
 
 /// A wrapper for a collection of [`TargetBookmark`]s that is stored in the
 /// `bookmarks.json` file.
-#[derive(Debug, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct TargetBookmarks(HashMap<Url, TargetBookmark>);
 
 impl TargetBookmarks {
@@ -326,6 +527,20 @@ impl TargetBookmarks {
         self.0.iter()
     }
 
+    /// Returns the bookmarks ordered by url.
+    pub fn values_sorted(&self) -> Vec<&TargetBookmark> {
+        let mut bookmarks = self.0.values().collect::<Vec<_>>();
+        bookmarks.sort_by(|a, b| a.url.cmp(&b.url));
+        bookmarks
+    }
+
+    /// Returns the pairs of url and bookmark ordered by url.
+    pub fn iter_sorted(&self) -> Vec<(&Url, &TargetBookmark)> {
+        let mut bookmarks = self.0.iter().collect::<Vec<_>>();
+        bookmarks.sort_by(|a, b| a.0.cmp(b.0));
+        bookmarks
+    }
+
     pub fn iter_mut(&mut self) -> IterMut<Url, TargetBookmark> {
         self.0.iter_mut()
     }
@@ -387,6 +602,86 @@ impl TargetBookmarks {
     pub fn remove(&mut self, url: &Url) -> Option<TargetBookmark> {
         self.0.remove(url)
     }
+
+    /// Retains only the bookmarks specified by the predicate, e.g. to keep
+    /// only cached bookmarks.
+    pub fn retain(&mut self, f: impl FnMut(&Url, &mut TargetBookmark) -> bool) {
+        self.0.retain(f)
+    }
+
+    /// Assign human-readable filenames to the bookmarks which are fetched and
+    /// not cached yet. Bookmarks which are cached already keep their
+    /// filenames.
+    ///
+    /// If the filename of a url is taken by another url with the same slug
+    /// and hash prefix, a longer hash is used.
+    pub fn assign_filenames(&mut self) {
+        let mut filenames = self
+            .0
+            .values()
+            .map(|bookmark| bookmark.cache_name().to_owned())
+            .collect::<HashSet<_>>();
+        let mut bookmarks = self
+            .0
+            .values_mut()
+            .filter(|bookmark| {
+                bookmark.filename.is_none()
+                    && bookmark.last_cached.is_none()
+                    && matches!(
+                        bookmark.action,
+                        Action::FetchAndAdd | Action::FetchAndReplace
+                    )
+            })
+            .collect::<Vec<_>>();
+        // Assign filenames in a deterministic order.
+        bookmarks.sort_by(|a, b| a.url.cmp(&b.url));
+
+        for bookmark in bookmarks {
+            let filename = [8, 16, 64]
+                .into_iter()
+                .map(|hash_len| utils::readable_filename(&bookmark.url, hash_len))
+                .find(|filename| !filenames.contains(filename))
+                .unwrap_or_else(|| bookmark.id.clone());
+            debug!("Assign filename {filename} to {}", bookmark.url);
+            filenames.insert(filename.clone());
+            bookmark.filename = Some(filename);
+        }
+    }
+
+    /// Merge the bookmarks from another `bookmarks.json` and return the
+    /// number of added and updated bookmarks.
+    ///
+    /// `is_cached` checks if the cached file of a bookmark exists locally.
+    /// The cache state of added bookmarks is cleared if they aren't cached.
+    pub fn merge(
+        &mut self,
+        other: TargetBookmarks,
+        is_cached: impl Fn(&TargetBookmark) -> bool,
+    ) -> (usize, usize) {
+        let mut added = 0;
+        let mut updated = 0;
+
+        for (url, mut bookmark) in other {
+            match self.0.entry(url) {
+                Entry::Occupied(mut entry) => {
+                    if entry.get_mut().merge(bookmark, &is_cached) {
+                        updated += 1;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    if !is_cached(&bookmark) {
+                        bookmark.last_cached = None;
+                        bookmark.cache_modes.clear();
+                    }
+
+                    entry.insert(bookmark);
+                    added += 1;
+                }
+            }
+        }
+
+        (added, updated)
+    }
 }
 
 impl IntoIterator for TargetBookmarks {
@@ -417,3 +712,260 @@ impl TryFrom<SourceBookmarks> for TargetBookmarks {
         Ok(target_bookmarks)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_bookmarks_sorted() {
+        let now = Utc::now();
+        let urls = [
+            "https://url3.com",
+            "https://url1.com",
+            "https://url4.com/endpoint",
+            "https://url2.com",
+            "https://url4.com",
+        ]
+        .map(|url| Url::parse(url).unwrap());
+        let mut target_bookmarks = TargetBookmarks::default();
+
+        for url in &urls {
+            target_bookmarks.insert(TargetBookmark::new(url.clone(), now));
+        }
+
+        let mut expected_urls = urls.to_vec();
+        expected_urls.sort();
+
+        let sorted_urls = target_bookmarks
+            .values_sorted()
+            .into_iter()
+            .map(|bookmark| bookmark.url().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(sorted_urls, expected_urls);
+
+        let sorted_urls = target_bookmarks
+            .iter_sorted()
+            .into_iter()
+            .map(|(url, _)| url.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(sorted_urls, expected_urls);
+    }
+
+    #[test]
+    fn test_retain() {
+        let now = Utc::now();
+        let mut target_bookmarks = TargetBookmarks::default();
+
+        for url in ["https://url1.com", "https://url2.com", "https://url3.com"] {
+            let mut bookmark = TargetBookmark::new(Url::parse(url).unwrap(), now);
+
+            if url != "https://url2.com" {
+                bookmark.set_last_cached(now);
+            }
+
+            target_bookmarks.insert(bookmark);
+        }
+
+        // Keep only cached bookmarks, and mark them while retaining.
+        target_bookmarks.retain(|_, bookmark| {
+            bookmark.set_action(Action::FetchAndReplace);
+            bookmark.last_cached().is_some()
+        });
+
+        assert_eq!(
+            target_bookmarks
+                .iter_sorted()
+                .into_iter()
+                .map(|(url, bookmark)| (url.as_str(), bookmark.action()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("https://url1.com/", &Action::FetchAndReplace),
+                ("https://url3.com/", &Action::FetchAndReplace)
+            ]
+        );
+
+        target_bookmarks.retain(|url, _| url.as_str() != "https://url1.com/");
+        assert_eq!(target_bookmarks.len(), 1);
+        assert!(target_bookmarks.contains_key(&Url::parse("https://url3.com").unwrap()));
+
+        target_bookmarks.retain(|_, _| false);
+        assert!(target_bookmarks.is_empty());
+    }
+
+    #[test]
+    fn test_assign_filenames() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url.com/post").unwrap();
+        let url2 = Url::parse("https://url.com/post?page=2").unwrap();
+        let url3 = Url::parse("https://url.com/cached").unwrap();
+        let url4 = Url::parse("https://url.com/ignored").unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+
+        for url in [&url1, &url2] {
+            target_bookmarks.insert(
+                TargetBookmark::builder(url.clone(), now)
+                    .with_action(Action::FetchAndAdd)
+                    .build(),
+            );
+        }
+
+        // The cached bookmark keeps its id as filename.
+        let mut bookmark3 = TargetBookmark::builder_with_id("id3".to_owned(), url3.clone(), now)
+            .add_cache_mode(CacheMode::Text)
+            .with_action(Action::FetchAndReplace)
+            .build();
+        bookmark3.set_last_cached(now);
+        target_bookmarks.insert(bookmark3);
+        // The bookmark isn't fetched.
+        target_bookmarks.insert(TargetBookmark::new(url4.clone(), now));
+
+        // The short filename of url2 is taken already.
+        let taken_filename = utils::readable_filename(&url2, 8);
+        target_bookmarks
+            .get_mut(&url4)
+            .unwrap()
+            .set_filename(taken_filename.clone());
+
+        target_bookmarks.assign_filenames();
+
+        let filename1 = target_bookmarks.get(&url1).unwrap().cache_name();
+        let filename2 = target_bookmarks.get(&url2).unwrap().cache_name();
+        assert_eq!(filename1, utils::readable_filename(&url1, 8));
+        assert!(filename1.starts_with("url-com-post-"));
+        assert_eq!(filename2, utils::readable_filename(&url2, 16));
+        assert!(filename2.starts_with("url-com-post-"));
+        assert_eq!(target_bookmarks.get(&url3).unwrap().cache_name(), "id3");
+        assert_eq!(
+            target_bookmarks.get(&url4).unwrap().cache_name(),
+            taken_filename
+        );
+
+        // Assigned filenames are kept.
+        let before = target_bookmarks.clone();
+        target_bookmarks.assign_filenames();
+        assert_eq!(target_bookmarks, before);
+    }
+
+    #[test]
+    fn test_merge_target_bookmarks_disjoint() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(TargetBookmark::new(url1.clone(), now));
+        let mut other_bookmarks = TargetBookmarks::default();
+        other_bookmarks.insert(TargetBookmark::new(url2.clone(), now));
+        let other_bookmark = other_bookmarks.get(&url2).unwrap().clone();
+
+        let (added, updated) = target_bookmarks.merge(other_bookmarks, |_| false);
+        assert_eq!((added, updated), (1, 0));
+        assert_eq!(target_bookmarks.len(), 2);
+        assert!(target_bookmarks.contains_key(&url1));
+        assert_eq!(target_bookmarks.get(&url2), Some(&other_bookmark));
+    }
+
+    #[test]
+    fn test_merge_target_bookmarks_overlapping() {
+        let old = Utc::now() - chrono::Duration::days(1);
+        let new = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+
+        let mut bookmark1 = TargetBookmark::builder_with_id("id1".to_owned(), url1.clone(), old)
+            .add_source(SourceType::Firefox)
+            .add_cache_mode(CacheMode::Text)
+            .build();
+        bookmark1.set_last_cached(old);
+        let bookmark2 = TargetBookmark::builder_with_id("id2".to_owned(), url2.clone(), new)
+            .add_source(SourceType::Firefox)
+            .build();
+        let mut bookmark3 = TargetBookmark::builder_with_id("id3".to_owned(), url3.clone(), old)
+            .add_cache_mode(CacheMode::Text)
+            .build();
+        bookmark3.set_last_cached(old);
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(bookmark1);
+        target_bookmarks.insert(bookmark2);
+        target_bookmarks.insert(bookmark3);
+
+        // The other bookmark for url1 was cached more recently, but the local
+        // bookmark is cached.
+        let mut other_bookmark1 =
+            TargetBookmark::builder_with_id("id4".to_owned(), url1.clone(), old)
+                .add_source(SourceType::Chrome)
+                .add_cache_mode(CacheMode::Html)
+                .build();
+        other_bookmark1.set_last_cached(new);
+        // The cached file of the other bookmark for url2 exists locally.
+        let mut other_bookmark2 =
+            TargetBookmark::builder_with_id("id5".to_owned(), url2.clone(), new)
+                .add_source(SourceType::Firefox)
+                .add_cache_mode(CacheMode::Html)
+                .build();
+        other_bookmark2.set_last_cached(new);
+        // Neither bookmark for url3 has a cached file locally.
+        let mut other_bookmark3 =
+            TargetBookmark::builder_with_id("id6".to_owned(), url3.clone(), old)
+                .add_cache_mode(CacheMode::Html)
+                .build();
+        other_bookmark3.set_last_cached(new);
+        let mut other_bookmarks = TargetBookmarks::default();
+        other_bookmarks.insert(other_bookmark1);
+        other_bookmarks.insert(other_bookmark2);
+        other_bookmarks.insert(other_bookmark3);
+        let cached_files = HashSet::from(["id1", "id5"]);
+        let is_cached = |bookmark: &TargetBookmark| cached_files.contains(bookmark.cache_name());
+
+        let (added, updated) = target_bookmarks.merge(other_bookmarks, is_cached);
+        assert_eq!((added, updated), (0, 3));
+
+        let bookmark1 = target_bookmarks.get(&url1).unwrap();
+        assert_eq!(bookmark1.id(), "id1");
+        assert_eq!(bookmark1.last_cached(), Some(old.timestamp_millis()));
+        assert_eq!(
+            bookmark1.sources(),
+            &HashSet::from_iter([SourceType::Firefox, SourceType::Chrome])
+        );
+        assert_eq!(
+            bookmark1.cache_modes(),
+            &HashSet::from_iter([CacheMode::Text])
+        );
+
+        let bookmark2 = target_bookmarks.get(&url2).unwrap();
+        assert_eq!(bookmark2.id(), "id5");
+        assert_eq!(bookmark2.last_cached(), Some(new.timestamp_millis()));
+        assert_eq!(
+            bookmark2.cache_modes(),
+            &HashSet::from_iter([CacheMode::Html])
+        );
+
+        let bookmark3 = target_bookmarks.get(&url3).unwrap();
+        assert_eq!(bookmark3.id(), "id3");
+        assert_eq!(bookmark3.last_cached(), None);
+        assert!(bookmark3.cache_modes().is_empty());
+    }
+
+    #[test]
+    fn test_merge_target_bookmarks_added_uncached() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let mut other_bookmark =
+            TargetBookmark::builder_with_id("id1".to_owned(), url1.clone(), now)
+                .add_cache_mode(CacheMode::Text)
+                .build();
+        other_bookmark.set_last_cached(now);
+        let mut other_bookmarks = TargetBookmarks::default();
+        other_bookmarks.insert(other_bookmark);
+        let mut target_bookmarks = TargetBookmarks::default();
+
+        let (added, updated) = target_bookmarks.merge(other_bookmarks, |_| false);
+        assert_eq!((added, updated), (1, 0));
+
+        let bookmark = target_bookmarks.get(&url1).unwrap();
+        assert_eq!(bookmark.id(), "id1");
+        assert_eq!(bookmark.last_cached(), None);
+        assert!(bookmark.cache_modes().is_empty());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::errors::BogrepError;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use url::Url;
+
+/// Filter bookmarks by glob patterns for their urls.
+///
+/// A pattern matches if it matches the full url (e.g.
+/// `https://doc.rust-lang.org/*`) or the host of the url (e.g.
+/// `*.rust-lang.org`).
+#[derive(Debug, Clone, Default)]
+pub struct UrlFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl UrlFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, BogrepError> {
+        Ok(Self {
+            include: Self::build_glob_set(include)?,
+            exclude: Self::build_glob_set(exclude)?,
+        })
+    }
+
+    /// Check if the url is included and not excluded.
+    pub fn is_match(&self, url: &Url) -> bool {
+        let is_included = self
+            .include
+            .as_ref()
+            .map_or(true, |include| Self::matches(include, url));
+        let is_excluded = self
+            .exclude
+            .as_ref()
+            .is_some_and(|exclude| Self::matches(exclude, url));
+
+        is_included && !is_excluded
+    }
+
+    fn matches(glob_set: &GlobSet, url: &Url) -> bool {
+        glob_set.is_match(url.as_str())
+            || url.host_str().is_some_and(|host| glob_set.is_match(host))
+    }
+
+    fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, BogrepError> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(BogrepError::InvalidGlob)?);
+        }
+
+        let glob_set = builder.build().map_err(BogrepError::InvalidGlob)?;
+
+        Ok(Some(glob_set))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_match_empty() {
+        let url_filter = UrlFilter::new(&[], &[]).unwrap();
+        assert!(url_filter.is_match(&Url::parse("https://www.rust-lang.org/").unwrap()));
+    }
+
+    #[test]
+    fn test_is_match_include() {
+        let url_filter = UrlFilter::new(&["*.rust-lang.org".to_owned()], &[]).unwrap();
+        assert!(url_filter.is_match(&Url::parse("https://www.rust-lang.org/").unwrap()));
+        assert!(url_filter.is_match(&Url::parse("https://doc.rust-lang.org/book/").unwrap()));
+        assert!(!url_filter.is_match(&Url::parse("https://crates.io/").unwrap()));
+
+        let url_filter =
+            UrlFilter::new(&["https://doc.rust-lang.org/book/*".to_owned()], &[]).unwrap();
+        assert!(url_filter.is_match(&Url::parse("https://doc.rust-lang.org/book/ch01").unwrap()));
+        assert!(!url_filter.is_match(&Url::parse("https://doc.rust-lang.org/std/").unwrap()));
+    }
+
+    #[test]
+    fn test_is_match_exclude() {
+        let url_filter = UrlFilter::new(
+            &["*.rust-lang.org".to_owned()],
+            &["doc.rust-lang.org".to_owned()],
+        )
+        .unwrap();
+        assert!(url_filter.is_match(&Url::parse("https://www.rust-lang.org/").unwrap()));
+        assert!(!url_filter.is_match(&Url::parse("https://doc.rust-lang.org/book/").unwrap()));
+        assert!(!url_filter.is_match(&Url::parse("https://crates.io/").unwrap()));
+    }
+
+    #[test]
+    fn test_new_invalid_glob() {
+        let res = UrlFilter::new(&["[rust-lang.org".to_owned()], &[]);
+        assert!(matches!(res, Err(BogrepError::InvalidGlob(_))));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
     bookmarks::TargetBookmark,
     errors::BogrepError,
-    html,
+    html::{self, TextExtractor},
     utils::{self},
-    TargetBookmarks,
+    ManifestEntry, TargetBookmarks,
 };
 use async_trait::async_trait;
 use chrono::Utc;
@@ -12,13 +12,14 @@ use log::debug;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt,
-    fs::File,
+    fs::{self, File},
     io::Read,
     path::{Path, PathBuf},
     sync::Arc,
 };
+use url::Url;
 
 #[derive(Debug, ValueEnum, Clone, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -52,6 +53,15 @@ impl CacheMode {
         }
     }
 
+    /// Get the cache mode for the file extension of a cached file.
+    pub fn from_extension(extension: &str) -> Option<CacheMode> {
+        match extension {
+            "html" => Some(Self::Html),
+            "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
     pub fn suffix(&self) -> &str {
         match self {
             Self::Html => ".html",
@@ -76,6 +86,10 @@ pub trait Caching: Clone {
     /// Check if content of bookmark exists in cache.
     fn exists(&self, bookmark: &TargetBookmark) -> bool;
 
+    /// Check if the cache modes of a bookmark agree with the cached file for
+    /// the current cache mode.
+    fn is_consistent(&self, bookmark: &TargetBookmark) -> bool;
+
     /// Open the cached file for a bookmark.
     fn open(&self, bookmark: &TargetBookmark) -> Result<Option<impl Read>, BogrepError>;
 
@@ -83,6 +97,24 @@ pub trait Caching: Clone {
     // TODO: make get async
     fn get(&self, bookmark: &TargetBookmark) -> Result<Option<String>, BogrepError>;
 
+    /// Iterate over the urls and cached contents of the bookmarks ordered by
+    /// url, skipping the bookmarks which are not cached.
+    ///
+    /// The content of a bookmark is only read when the iterator is advanced.
+    fn iter_cached<'a>(
+        &'a self,
+        bookmarks: &'a TargetBookmarks,
+    ) -> impl Iterator<Item = Result<(Url, String), BogrepError>> + 'a {
+        bookmarks
+            .values_sorted()
+            .into_iter()
+            .filter_map(|bookmark| match self.get(bookmark) {
+                Ok(Some(content)) => Some(Ok((bookmark.url().to_owned(), content))),
+                Ok(None) => None,
+                Err(err) => Some(Err(err)),
+            })
+    }
+
     /// Add the content of a bookmark to cache.
     async fn add(&self, html: String, bookmark: &mut TargetBookmark)
         -> Result<String, BogrepError>;
@@ -105,6 +137,20 @@ pub trait Caching: Clone {
 
     /// Clear the cache, i.e. remove all files in the cache directory.
     fn clear(&self, bookmarks: &mut TargetBookmarks) -> Result<(), BogrepError>;
+
+    /// Clear the cache for a single `CacheMode`, i.e. remove all files with
+    /// the extension of the given `cache_mode`.
+    fn clear_mode(
+        &self,
+        bookmarks: &mut TargetBookmarks,
+        cache_mode: &CacheMode,
+    ) -> Result<(), BogrepError>;
+
+    /// Get the size and hash of the cached files for a bookmark.
+    fn manifest_entries(
+        &self,
+        bookmark: &TargetBookmark,
+    ) -> Result<Vec<ManifestEntry>, BogrepError>;
 }
 
 /// A cache to store the fetched bookmarks.
@@ -114,6 +160,12 @@ pub struct Cache {
     path: PathBuf,
     /// The file extension of the cached files.
     mode: CacheMode,
+    /// The strategy to extract the text for `CacheMode::Text`.
+    text_extractor: TextExtractor,
+    /// The permissions of the cached files on Unix, e.g. `0o600`.
+    file_mode: Option<u32>,
+    /// Normalize the whitespace of the text for `CacheMode::Text`.
+    normalize_whitespace: bool,
 }
 
 impl Cache {
@@ -122,19 +174,135 @@ impl Cache {
         Self {
             path: cache_path.to_owned(),
             mode: cache_mode,
+            text_extractor: TextExtractor::default(),
+            file_mode: None,
+            normalize_whitespace: false,
         }
     }
 
-    fn bookmark_path(&self, bookmark_id: &str) -> PathBuf {
-        self.path
-            .join(bookmark_id)
-            .with_extension(self.mode.extension())
+    /// Set the strategy to extract the text for `CacheMode::Text`.
+    pub fn with_text_extractor(mut self, text_extractor: TextExtractor) -> Self {
+        self.text_extractor = text_extractor;
+        self
     }
 
-    fn bookmark_path_by_cache_mode(&self, bookmark_id: &str, cache_mode: &CacheMode) -> PathBuf {
-        self.path
-            .join(bookmark_id)
-            .with_extension(cache_mode.extension())
+    /// Normalize the whitespace of the text for `CacheMode::Text`, i.e.
+    /// replace non-breaking spaces, collapse runs of whitespace, and trim
+    /// lines.
+    pub fn with_normalize_whitespace(mut self, normalize_whitespace: bool) -> Self {
+        self.normalize_whitespace = normalize_whitespace;
+        self
+    }
+
+    /// Convert the fetched HTML to the content of the cached file.
+    fn convert(&self, html: String, bookmark: &TargetBookmark) -> Result<String, BogrepError> {
+        match self.mode {
+            CacheMode::Html => Ok(html),
+            CacheMode::Text => {
+                let text = html::convert_to_text(&html, bookmark.url(), &self.text_extractor)?;
+
+                if self.normalize_whitespace {
+                    Ok(html::normalize_whitespace(&text))
+                } else {
+                    Ok(text)
+                }
+            }
+        }
+    }
+
+    /// Set the permissions of the cached files on Unix, e.g. `0o600`.
+    /// Defaults to the permissions of the operating system.
+    pub fn with_file_mode(mut self, file_mode: Option<u32>) -> Self {
+        self.file_mode = file_mode;
+        self
+    }
+
+    /// The path to the cache directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn bookmark_path(&self, cache_name: &str) -> Result<PathBuf, BogrepError> {
+        self.bookmark_path_by_cache_mode(cache_name, &self.mode)
+    }
+
+    pub(crate) fn bookmark_path_by_cache_mode(
+        &self,
+        cache_name: &str,
+        cache_mode: &CacheMode,
+    ) -> Result<PathBuf, BogrepError> {
+        let filename = utils::safe_filename(cache_name)?;
+        Ok(self
+            .path
+            .join(format!("{filename}.{}", cache_mode.extension())))
+    }
+
+    /// Check if a cached file exists for a bookmark in any of the cache
+    /// modes.
+    pub fn has_file(&self, bookmark: &TargetBookmark) -> bool {
+        Self::modes().iter().any(|cache_mode| {
+            self.bookmark_path_by_cache_mode(bookmark.cache_name(), cache_mode)
+                .is_ok_and(|cache_path| cache_path.is_file())
+        })
+    }
+
+    /// The cached files whose id doesn't belong to any of the bookmarks.
+    pub(crate) fn unreferenced_files(
+        &self,
+        bookmarks: &TargetBookmarks,
+    ) -> Result<Vec<PathBuf>, BogrepError> {
+        let mut unreferenced_files = vec![];
+
+        if !self.path.is_dir() {
+            return Ok(unreferenced_files);
+        }
+
+        let cache_names = bookmarks
+            .values()
+            .map(|bookmark| bookmark.cache_name())
+            .collect::<HashSet<_>>();
+
+        for entry in fs::read_dir(&self.path).map_err(BogrepError::ReadFile)? {
+            let path = entry.map_err(BogrepError::ReadFile)?.path();
+            // Ignore files which are not cached bookmarks, e.g. `.DS_Store`.
+            let is_cache_file = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .and_then(CacheMode::from_extension)
+                .is_some();
+            let cache_name = path.file_stem().and_then(|file_stem| file_stem.to_str());
+
+            if path.is_file()
+                && is_cache_file
+                && cache_name.is_some_and(|cache_name| !cache_names.contains(cache_name))
+            {
+                unreferenced_files.push(path);
+            }
+        }
+
+        unreferenced_files.sort();
+
+        Ok(unreferenced_files)
+    }
+
+    /// Remove the cached files which don't belong to any of the bookmarks.
+    ///
+    /// Returns the number of removed files and the freed bytes.
+    pub fn remove_unreferenced(
+        &self,
+        bookmarks: &TargetBookmarks,
+    ) -> Result<(usize, u64), BogrepError> {
+        let unreferenced_files = self.unreferenced_files(bookmarks)?;
+        let mut freed_bytes = 0;
+
+        for path in &unreferenced_files {
+            debug!("Remove unreferenced file: {}", path.display());
+            let metadata = fs::metadata(path).map_err(BogrepError::ReadFile)?;
+            utils::remove_file(path)?;
+            freed_bytes += metadata.len();
+        }
+
+        Ok((unreferenced_files.len(), freed_bytes))
     }
 }
 
@@ -149,16 +317,35 @@ impl Caching for Cache {
     }
 
     fn is_empty(&self) -> bool {
+        // Ignore files which are not cached bookmarks, e.g. `.DS_Store`.
         self.path.exists()
-            && std::fs::read_dir(&self.path).is_ok_and(|mut file| file.next().is_none())
+            && std::fs::read_dir(&self.path).is_ok_and(|mut entries| {
+                !entries.any(|entry| {
+                    entry.is_ok_and(|entry| {
+                        entry
+                            .path()
+                            .extension()
+                            .and_then(|extension| extension.to_str())
+                            .and_then(CacheMode::from_extension)
+                            .is_some()
+                    })
+                })
+            })
     }
 
     fn exists(&self, bookmark: &TargetBookmark) -> bool {
         bookmark.cache_modes().contains(self.mode()) && bookmark.last_cached.is_some()
     }
 
+    fn is_consistent(&self, bookmark: &TargetBookmark) -> bool {
+        bookmark.cache_modes().contains(self.mode())
+            == self
+                .bookmark_path(bookmark.cache_name())
+                .is_ok_and(|cache_path| cache_path.exists())
+    }
+
     fn open(&self, bookmark: &TargetBookmark) -> Result<Option<impl Read>, BogrepError> {
-        let cache_path = self.bookmark_path(bookmark.id());
+        let cache_path = self.bookmark_path(bookmark.cache_name())?;
         debug!("Open website: {}", cache_path.display());
 
         if cache_path.exists() {
@@ -171,10 +358,7 @@ impl Caching for Cache {
 
     fn get(&self, bookmark: &TargetBookmark) -> Result<Option<String>, BogrepError> {
         if let Some(mut cache_file) = self.open(bookmark)? {
-            debug!(
-                "Get website from cache: {}",
-                self.bookmark_path(bookmark.id()).display()
-            );
+            debug!("Get website from cache: {}", bookmark.id());
             let mut buf = String::new();
             cache_file
                 .read_to_string(&mut buf)
@@ -190,16 +374,14 @@ impl Caching for Cache {
         html: String,
         bookmark: &mut TargetBookmark,
     ) -> Result<String, BogrepError> {
-        let cache_path = self.bookmark_path(bookmark.id());
+        let cache_path = self.bookmark_path(bookmark.cache_name())?;
 
-        let content = match self.mode {
-            CacheMode::Html => html,
-            CacheMode::Text => html::convert_to_text(&html, bookmark.url())?,
-        };
+        let content = self.convert(html, bookmark)?;
 
         if !cache_path.exists() {
             debug!("Add website to cache: {}", cache_path.display());
-            utils::write_file_async(&cache_path, content.as_bytes()).await?;
+            utils::write_file_with_mode_async(&cache_path, content.as_bytes(), self.file_mode)
+                .await?;
 
             bookmark.set_last_cached(Utc::now());
             bookmark.add_cache_mode(self.mode.clone());
@@ -213,15 +395,12 @@ impl Caching for Cache {
         html: String,
         bookmark: &mut TargetBookmark,
     ) -> Result<String, BogrepError> {
-        let cache_path = self.bookmark_path(bookmark.id());
+        let cache_path = self.bookmark_path(bookmark.cache_name())?;
         debug!("Replace website in cache: {}", cache_path.display());
 
-        let content = match self.mode {
-            CacheMode::Html => html,
-            CacheMode::Text => html::convert_to_text(&html, bookmark.url())?,
-        };
+        let content = self.convert(html, bookmark)?;
 
-        utils::write_file_async(&cache_path, content.as_bytes()).await?;
+        utils::write_file_with_mode_async(&cache_path, content.as_bytes(), self.file_mode).await?;
 
         bookmark.set_last_cached(Utc::now());
         bookmark.add_cache_mode(self.mode.clone());
@@ -230,7 +409,7 @@ impl Caching for Cache {
     }
 
     async fn remove(&self, bookmark: &mut TargetBookmark) -> Result<(), BogrepError> {
-        let cache_path = self.bookmark_path(bookmark.id());
+        let cache_path = self.bookmark_path(bookmark.cache_name())?;
 
         if bookmark.last_cached().is_some() && cache_path.exists() {
             debug!("Remove website from cache: {}", cache_path.display());
@@ -246,7 +425,7 @@ impl Caching for Cache {
         let cache_modes = Cache::modes();
 
         for cache_mode in &cache_modes {
-            let cache_path = self.bookmark_path_by_cache_mode(bookmark.id(), cache_mode);
+            let cache_path = self.bookmark_path_by_cache_mode(bookmark.cache_name(), cache_mode)?;
 
             if cache_path.exists() {
                 debug!("Remove website from cache: {}", cache_path.display());
@@ -262,7 +441,7 @@ impl Caching for Cache {
     async fn remove_all(&self, bookmarks: &mut TargetBookmarks) -> Result<(), BogrepError> {
         debug!("Remove all cached websites");
         for bookmark in bookmarks.values_mut() {
-            let cache_path = self.bookmark_path(bookmark.id());
+            let cache_path = self.bookmark_path(bookmark.cache_name())?;
 
             if cache_path.exists() {
                 debug!("Remove website from cache: {}", cache_path.display());
@@ -285,7 +464,8 @@ impl Caching for Cache {
 
         for bookmark in bookmarks.values_mut() {
             for cache_mode in &cache_modes {
-                let cache_path = self.bookmark_path_by_cache_mode(bookmark.id(), cache_mode);
+                let cache_path =
+                    self.bookmark_path_by_cache_mode(bookmark.cache_name(), cache_mode)?;
 
                 if cache_path.exists() {
                     debug!("Remove website from cache: {}", cache_path.display());
@@ -298,6 +478,51 @@ impl Caching for Cache {
 
         Ok(())
     }
+
+    fn clear_mode(
+        &self,
+        bookmarks: &mut TargetBookmarks,
+        cache_mode: &CacheMode,
+    ) -> Result<(), BogrepError> {
+        debug!("Clear cache for mode {cache_mode}");
+
+        for bookmark in bookmarks.values_mut() {
+            let cache_path = self.bookmark_path_by_cache_mode(bookmark.cache_name(), cache_mode)?;
+
+            if cache_path.exists() {
+                debug!("Remove website from cache: {}", cache_path.display());
+                utils::remove_file(&cache_path)?;
+            }
+
+            bookmark.remove_cache_mode(cache_mode);
+
+            if bookmark.cache_modes().is_empty() {
+                bookmark.unset_last_cached();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn manifest_entries(
+        &self,
+        bookmark: &TargetBookmark,
+    ) -> Result<Vec<ManifestEntry>, BogrepError> {
+        let mut entries = vec![];
+
+        for cache_mode in Cache::modes() {
+            let cache_path =
+                self.bookmark_path_by_cache_mode(bookmark.cache_name(), &cache_mode)?;
+
+            if bookmark.cache_modes().contains(&cache_mode) && cache_path.exists() {
+                let content = utils::read_file(&cache_path)?;
+                let filename = format!("{}.{}", bookmark.cache_name(), cache_mode.extension());
+                entries.push(ManifestEntry::new(filename, cache_mode, &content));
+            }
+        }
+
+        Ok(entries)
+    }
 }
 
 /// A mock cache to store fetched bookmarks used in testing.
@@ -343,6 +568,12 @@ impl Caching for MockCache {
         self.get(bookmark).unwrap().is_some()
     }
 
+    fn is_consistent(&self, bookmark: &TargetBookmark) -> bool {
+        let cache_map = self.cache_map.lock();
+        bookmark.cache_modes().contains(self.mode())
+            == cache_map.contains_key(bookmark.cache_name())
+    }
+
     fn open(&self, _bookmark: &TargetBookmark) -> Result<Option<impl Read>, BogrepError> {
         Ok(None::<File>)
     }
@@ -350,7 +581,7 @@ impl Caching for MockCache {
     fn get(&self, bookmark: &TargetBookmark) -> Result<Option<String>, BogrepError> {
         let cache_map = self.cache_map.lock();
         let content = cache_map
-            .get(bookmark.id())
+            .get(bookmark.cache_name())
             .map(|content| content.to_owned());
         Ok(content)
     }
@@ -363,9 +594,11 @@ impl Caching for MockCache {
         let mut cache_map = self.cache_map.lock();
         let content = match self.mode {
             CacheMode::Html => html,
-            CacheMode::Text => html::convert_to_text(&html, bookmark.url())?,
+            CacheMode::Text => {
+                html::convert_to_text(&html, bookmark.url(), &TextExtractor::default())?
+            }
         };
-        cache_map.insert(bookmark.id().to_owned(), content.clone());
+        cache_map.insert(bookmark.cache_name().to_owned(), content.clone());
 
         bookmark.set_last_cached(Utc::now());
         bookmark.add_cache_mode(self.mode.clone());
@@ -381,9 +614,11 @@ impl Caching for MockCache {
         let mut cache_map = self.cache_map.lock();
         let content = match self.mode {
             CacheMode::Html => html,
-            CacheMode::Text => html::convert_to_text(&html, bookmark.url())?,
+            CacheMode::Text => {
+                html::convert_to_text(&html, bookmark.url(), &TextExtractor::default())?
+            }
         };
-        cache_map.insert(bookmark.id().to_owned(), content.clone());
+        cache_map.insert(bookmark.cache_name().to_owned(), content.clone());
 
         bookmark.set_last_cached(Utc::now());
         bookmark.add_cache_mode(self.mode.clone());
@@ -393,7 +628,7 @@ impl Caching for MockCache {
 
     async fn remove(&self, bookmark: &mut TargetBookmark) -> Result<(), BogrepError> {
         let mut cache_map = self.cache_map.lock();
-        cache_map.remove(bookmark.id());
+        cache_map.remove(bookmark.cache_name());
 
         bookmark.unset_last_cached();
         bookmark.remove_cache_mode(&self.mode);
@@ -403,7 +638,7 @@ impl Caching for MockCache {
 
     async fn remove_by_modes(&self, bookmark: &mut TargetBookmark) -> Result<(), BogrepError> {
         let mut cache_map = self.cache_map.lock();
-        cache_map.remove(bookmark.id());
+        cache_map.remove(bookmark.cache_name());
 
         bookmark.unset_last_cached();
         bookmark.remove_cache_mode(&self.mode);
@@ -415,7 +650,7 @@ impl Caching for MockCache {
         let mut cache_map = self.cache_map.lock();
 
         for bookmark in bookmarks.values_mut() {
-            cache_map.remove(bookmark.id());
+            cache_map.remove(bookmark.cache_name());
 
             bookmark.unset_last_cached();
             bookmark.remove_cache_mode(&self.mode);
@@ -435,13 +670,207 @@ impl Caching for MockCache {
 
         Ok(())
     }
+
+    fn clear_mode(
+        &self,
+        bookmarks: &mut TargetBookmarks,
+        cache_mode: &CacheMode,
+    ) -> Result<(), BogrepError> {
+        if cache_mode != &self.mode {
+            return Ok(());
+        }
+
+        let mut cache_map = self.cache_map.lock();
+
+        for bookmark in bookmarks.values_mut() {
+            cache_map.remove(bookmark.cache_name());
+
+            bookmark.unset_last_cached();
+            bookmark.remove_cache_mode(cache_mode);
+        }
+
+        Ok(())
+    }
+
+    fn manifest_entries(
+        &self,
+        bookmark: &TargetBookmark,
+    ) -> Result<Vec<ManifestEntry>, BogrepError> {
+        let cache_map = self.cache_map.lock();
+        let entries = cache_map
+            .get(bookmark.cache_name())
+            .map(|content| {
+                let filename = format!("{}.{}", bookmark.cache_name(), self.mode.extension());
+                ManifestEntry::new(filename, self.mode.clone(), content.as_bytes())
+            })
+            .into_iter()
+            .collect();
+        Ok(entries)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
-    use url::Url;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_empty() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        assert!(cache.is_empty());
+
+        utils::create_file(&temp_dir.path().join(".DS_Store")).unwrap();
+        assert!(cache.is_empty());
+
+        utils::create_file(&temp_dir.path().join("notes.md")).unwrap();
+        assert!(cache.is_empty());
+
+        utils::create_file(
+            &temp_dir
+                .path()
+                .join("dd30381b-8e67-4e84-9379-0852f60a7cd7.html"),
+        )
+        .unwrap();
+        assert!(!cache.is_empty());
+    }
+
+    #[test]
+    fn test_bookmark_path() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+
+        let res = cache.bookmark_path("dd30381b-8e67-4e84-9379-0852f60a7cd7");
+        assert_eq!(
+            res.unwrap(),
+            temp_dir
+                .path()
+                .join("dd30381b-8e67-4e84-9379-0852f60a7cd7.txt")
+        );
+
+        // An id with dots keeps its dots instead of replacing an extension.
+        let res = cache.bookmark_path_by_cache_mode("id.v2", &CacheMode::Html);
+        assert_eq!(res.unwrap(), temp_dir.path().join("id.v2.html"));
+
+        for id in ["../id", "dir/id", "/id", "..\\id", ".."] {
+            let res = cache.bookmark_path(id);
+            assert!(
+                matches!(res, Err(BogrepError::InvalidId(_))),
+                "Unexpected path for id {id}: {res:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_unreferenced() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let now = Utc::now();
+        let url = Url::parse("https://url.com").unwrap();
+        let bookmark = TargetBookmark::builder_with_id(
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+            url.clone(),
+            now,
+        )
+        .build();
+        let target_bookmarks = TargetBookmarks::new(HashMap::from_iter([(url, bookmark)]));
+
+        let referenced_files = [
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7.txt",
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7.html",
+            ".DS_Store",
+        ];
+        let unreferenced_files = [
+            "25b6357e-6eda-4367-8212-84376c6efe05.txt",
+            "a4d8f19b-92c1-4e68-a6e9-7d60b54024bc.html",
+        ];
+
+        for filename in referenced_files.iter().chain(&unreferenced_files) {
+            utils::write_file(&temp_dir.path().join(filename), "Test content".to_owned()).unwrap();
+        }
+
+        let res = cache.remove_unreferenced(&target_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), (2, 24));
+
+        for filename in referenced_files {
+            assert!(temp_dir.path().join(filename).exists());
+        }
+
+        for filename in unreferenced_files {
+            assert!(!temp_dir.path().join(filename).exists());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_add_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let cache = Cache::new(temp_path, CacheMode::Html).with_file_mode(Some(0o600));
+        let now = Utc::now();
+        let url = Url::parse("https://url.com").unwrap();
+        let mut bookmark = TargetBookmark::new(url, now);
+        let content = "<html><head></head><body><p>Test content</p></body></html>";
+
+        cache.add(content.to_owned(), &mut bookmark).await.unwrap();
+        let cache_path = cache.bookmark_path(bookmark.cache_name()).unwrap();
+        let mode = fs::metadata(&cache_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let cache = cache.with_file_mode(Some(0o640));
+        cache
+            .replace(content.to_owned(), &mut bookmark)
+            .await
+            .unwrap();
+        let mode = fs::metadata(&cache_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[tokio::test]
+    async fn test_iter_cached() {
+        let cache = MockCache::new(CacheMode::Text);
+        let now = Utc::now();
+        let mut bookmarks = TargetBookmarks::default();
+
+        for (url, content) in [
+            ("https://url2.com", Some("Content 2")),
+            ("https://url1.com", Some("Content 1")),
+            ("https://url3.com", None),
+        ] {
+            let mut bookmark = TargetBookmark::new(Url::parse(url).unwrap(), now);
+
+            if let Some(content) = content {
+                cache.add(content.to_owned(), &mut bookmark).await.unwrap();
+            }
+
+            bookmarks.insert(bookmark);
+        }
+
+        let res = cache.iter_cached(&bookmarks).collect::<Result<Vec<_>, _>>();
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            vec![
+                (
+                    Url::parse("https://url1.com").unwrap(),
+                    "Content 1".to_owned()
+                ),
+                (
+                    Url::parse("https://url2.com").unwrap(),
+                    "Content 2".to_owned()
+                )
+            ]
+        );
+
+        let mut iter = cache.iter_cached(&bookmarks);
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_some());
+        assert!(iter.next().is_none());
+    }
 
     #[tokio::test]
     async fn test_add_mode_html() {
@@ -584,4 +1013,43 @@ mod tests {
         let cache_map = cache.cache_map.lock();
         assert_eq!(cache_map.keys().len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_clear_mode() {
+        let temp_dir = tempdir().unwrap();
+        let text_cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let html_cache = Cache::new(temp_dir.path(), CacheMode::Html);
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let mut target_bookmarks = TargetBookmarks::new(HashMap::from_iter([
+            (url1.clone(), TargetBookmark::new(url1, now)),
+            (url2.clone(), TargetBookmark::new(url2, now)),
+        ]));
+        let content = "<html><head></head><body><p>Test content</p></body></html>";
+
+        for bookmark in target_bookmarks.values_mut() {
+            text_cache.add(content.to_owned(), bookmark).await.unwrap();
+            html_cache.add(content.to_owned(), bookmark).await.unwrap();
+        }
+
+        let res = text_cache.clear_mode(&mut target_bookmarks, &CacheMode::Html);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        for bookmark in target_bookmarks.values() {
+            let text_path = text_cache
+                .bookmark_path_by_cache_mode(bookmark.cache_name(), &CacheMode::Text)
+                .unwrap();
+            let html_path = text_cache
+                .bookmark_path_by_cache_mode(bookmark.cache_name(), &CacheMode::Html)
+                .unwrap();
+            assert!(text_path.exists());
+            assert!(!html_path.exists());
+            assert_eq!(
+                bookmark.cache_modes(),
+                &HashSet::from_iter([CacheMode::Text])
+            );
+            assert!(bookmark.last_cached().is_some());
+        }
+    }
 }
@@ -1,16 +1,24 @@
-use crate::{bookmarks::TargetBookmark, errors::BogrepError, Settings};
+use crate::{bookmarks::TargetBookmark, errors::BogrepError, html, utils, Settings};
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use parking_lot::Mutex;
 use reqwest::{
-    header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, ACCEPT_LANGUAGE, HOST, USER_AGENT},
-    Client as ReqwestClient, Url,
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_ENCODING, ACCEPT_LANGUAGE, ETAG, HOST,
+        IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT,
+    },
+    redirect::Policy,
+    Client as ReqwestClient, StatusCode, Url,
 };
 use std::{
-    collections::{hash_map::Entry, HashMap},
-    sync::Arc,
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 use tokio::time::{self, Duration};
 
@@ -19,6 +27,97 @@ use tokio::time::{self, Duration};
 pub trait Fetch: Clone {
     /// Fetch content of a website as HTML.
     async fn fetch(&self, bookmark: &TargetBookmark) -> Result<String, BogrepError>;
+
+    /// Fetch content of a website as HTML together with its cache validators
+    /// if the website was modified since the given cache validators.
+    ///
+    /// Returns [`BogrepError::NotModified`] if the website wasn't modified.
+    async fn fetch_modified(
+        &self,
+        bookmark: &TargetBookmark,
+        _validators: &CacheValidators,
+    ) -> Result<FetchedWebsite, BogrepError>
+    where
+        Self: Sync,
+    {
+        let html = self.fetch(bookmark).await?;
+        Ok(FetchedWebsite {
+            html,
+            ..Default::default()
+        })
+    }
+
+    /// Fetch the `robots.txt` of the host of a url, or `None` if it can't be
+    /// fetched.
+    async fn fetch_robots(&self, _url: &Url) -> Option<String>
+    where
+        Self: Sync,
+    {
+        None
+    }
+}
+
+/// A fetched website together with the metadata of the response.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FetchedWebsite {
+    /// The content of the website as HTML.
+    pub html: String,
+    /// The cache validators of the response.
+    pub validators: CacheValidators,
+    /// The final url after following redirects.
+    pub resolved_url: Option<Url>,
+}
+
+/// The `ETag` and `Last-Modified` headers of a fetched website, which are
+/// sent in conditional requests to avoid fetching unchanged websites.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Get the cache validators from the headers of a response.
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    /// Convert the cache validators to the headers of a conditional request.
+    fn to_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        if let Some(etag) = self
+            .etag
+            .as_ref()
+            .and_then(|etag| HeaderValue::from_str(etag).ok())
+        {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+
+        if let Some(last_modified) = self
+            .last_modified
+            .as_ref()
+            .and_then(|last_modified| HeaderValue::from_str(last_modified).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        headers
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +130,16 @@ pub struct ClientConfig {
     pub max_idle_connections_per_host: usize,
     /// The timeout for idle connections to be kept alive in milliseconds.
     pub idle_connections_timeout: u64,
+    /// Additional HTTP headers by domain.
+    pub headers: BTreeMap<String, BTreeMap<String, String>>,
+    /// Extract the text of binary documents like PDF instead of skipping
+    /// them.
+    pub include_binary: bool,
+    /// The `Accept-Language` header to request a language version of
+    /// websites.
+    pub accept_language: Option<String>,
+    /// Follow redirects instead of treating redirected websites as failed.
+    pub follow_redirects: bool,
 }
 
 impl ClientConfig {
@@ -40,7 +149,33 @@ impl ClientConfig {
             request_throttling: settings.request_throttling,
             max_idle_connections_per_host: settings.max_idle_connections_per_host,
             idle_connections_timeout: settings.idle_connections_timeout,
+            headers: settings.headers.clone(),
+            include_binary: false,
+            accept_language: settings.accept_language.clone(),
+            follow_redirects: true,
+        }
+    }
+
+    /// Override the configured request timeout in milliseconds.
+    pub fn with_request_timeout(mut self, request_timeout: Option<u64>) -> Self {
+        if let Some(request_timeout) = request_timeout {
+            self.request_timeout = request_timeout;
         }
+
+        self
+    }
+
+    /// Extract the text of binary documents like PDF instead of skipping
+    /// them.
+    pub fn with_include_binary(mut self, include_binary: bool) -> Self {
+        self.include_binary = include_binary;
+        self
+    }
+
+    /// Follow redirects instead of treating redirected websites as failed.
+    pub fn with_follow_redirects(mut self, follow_redirects: bool) -> Self {
+        self.follow_redirects = follow_redirects;
+        self
     }
 }
 
@@ -49,6 +184,14 @@ impl ClientConfig {
 pub struct Client {
     client: ReqwestClient,
     throttler: Option<Throttler>,
+    /// Additional HTTP headers by domain.
+    headers: Arc<HashMap<String, HeaderMap>>,
+    /// Extract the text of binary documents like PDF instead of skipping
+    /// them.
+    include_binary: bool,
+    /// The `Accept-Language` header to request a language version of
+    /// websites.
+    accept_language: Option<HeaderValue>,
 }
 
 impl Client {
@@ -59,18 +202,147 @@ impl Client {
             .timeout(Duration::from_millis(request_timeout))
             .pool_idle_timeout(Duration::from_millis(config.idle_connections_timeout))
             .pool_max_idle_per_host(config.max_idle_connections_per_host)
+            .redirect(if config.follow_redirects {
+                Policy::default()
+            } else {
+                Policy::none()
+            })
             .build()
             .map_err(BogrepError::CreateClient)?;
         let throttler = Some(Throttler::new(request_throttling));
-        Ok(Self { client, throttler })
+        let headers = Arc::new(Self::build_headers(&config.headers)?);
+        let accept_language = config
+            .accept_language
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()
+            .context("Invalid accept language")?;
+
+        if config.include_binary && !cfg!(feature = "pdf-extract") {
+            warn!("Binary documents are skipped because feature `pdf-extract` is disabled");
+        }
+
+        Ok(Self {
+            client,
+            throttler,
+            headers,
+            include_binary: config.include_binary,
+            accept_language,
+        })
+    }
+
+    fn build_headers(
+        headers: &BTreeMap<String, BTreeMap<String, String>>,
+    ) -> Result<HashMap<String, HeaderMap>, BogrepError> {
+        let mut headers_by_domain = HashMap::new();
+
+        for (domain, domain_headers) in headers {
+            let mut header_map = HeaderMap::new();
+
+            for (name, value) in domain_headers {
+                let invalid_header = || BogrepError::InvalidHeader {
+                    domain: domain.to_owned(),
+                    header: name.to_owned(),
+                };
+                let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid_header())?;
+                let value = HeaderValue::from_str(value).map_err(|_| invalid_header())?;
+                header_map.insert(name, value);
+            }
+
+            headers_by_domain.insert(domain.to_owned(), header_map);
+        }
+
+        Ok(headers_by_domain)
+    }
+
+    /// Read a local HTML file for bookmarks with the `file://` scheme.
+    async fn read_file(&self, bookmark: &TargetBookmark) -> Result<String, BogrepError> {
+        let path = bookmark
+            .url()
+            .to_file_path()
+            .map_err(|_| anyhow!("Invalid file url: {}", bookmark.url()))?;
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|err| BogrepError::OpenFile {
+                path: path.display().to_string(),
+                err,
+            })?;
+
+        if infer::archive::is_pdf(&bytes) {
+            return self.extract_binary(bytes, bookmark.url()).await;
+        }
+
+        let html = html::decode_html(&bytes, None);
+
+        if html.is_empty() {
+            Err(BogrepError::EmptyResponse(bookmark.url().to_string()))
+        } else {
+            Ok(html)
+        }
+    }
+
+    /// Extract the text of a binary document if `include_binary` is set.
+    /// Binary documents which can't be extracted are skipped.
+    async fn extract_binary(&self, bytes: Vec<u8>, url: &Url) -> Result<String, BogrepError> {
+        if self.include_binary {
+            extract_pdf(bytes, url).await
+        } else {
+            Err(BogrepError::BinaryResponse(url.to_string()))
+        }
+    }
+
+    /// Select the configured headers for a domain and its parent domains.
+    ///
+    /// Headers are scoped by domain to avoid leaking cookies across websites.
+    /// The configured `Accept-Language` header is sent to all domains, unless
+    /// it is overridden for a domain.
+    fn select_headers(&self, url: &Url) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        if let Some(accept_language) = &self.accept_language {
+            headers.insert(ACCEPT_LANGUAGE, accept_language.clone());
+        }
+
+        if let Some(host) = url.host_str() {
+            for (domain, domain_headers) in self.headers.iter() {
+                let is_subdomain = host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|subdomain| subdomain.is_empty() || subdomain.ends_with('.'));
+
+                if is_subdomain {
+                    headers.extend(domain_headers.clone());
+                }
+            }
+        }
+
+        headers
     }
 }
 
 #[async_trait]
 impl Fetch for Client {
     async fn fetch(&self, bookmark: &TargetBookmark) -> Result<String, BogrepError> {
+        let website = self
+            .fetch_modified(bookmark, &CacheValidators::default())
+            .await?;
+        Ok(website.html)
+    }
+
+    async fn fetch_modified(
+        &self,
+        bookmark: &TargetBookmark,
+        validators: &CacheValidators,
+    ) -> Result<FetchedWebsite, BogrepError> {
         debug!("Fetch bookmark ({})", bookmark.url());
 
+        if bookmark.url().scheme() == "file" {
+            let html = self.read_file(bookmark).await?;
+            return Ok(FetchedWebsite {
+                html,
+                ..Default::default()
+            });
+        }
+
         if let Some(throttler) = &self.throttler {
             throttler.throttle(bookmark).await?;
         }
@@ -121,26 +393,56 @@ impl Fetch for Client {
         let response = self
             .client
             .get(bookmark.url().to_owned())
+            .headers(self.select_headers(bookmark.url()))
+            .headers(validators.to_headers())
             .send()
             .await
             .map_err(BogrepError::HttpResponse)?;
 
-        if response.status().is_success() {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            Err(BogrepError::NotModified(bookmark.url().to_string()))
+        } else if response.status().is_success() {
+            let validators = CacheValidators::from_headers(response.headers());
+            let resolved_url = Some(response.url().to_owned());
+
+            if response.url() != bookmark.url() {
+                debug!(
+                    "Bookmark ({}) redirected to {}",
+                    bookmark.url(),
+                    response.url()
+                );
+            }
+
+            let fetched_website = |html| FetchedWebsite {
+                html,
+                validators,
+                resolved_url,
+            };
+
             if let Some(content_type) = response.headers().get(reqwest::header::CONTENT_TYPE) {
                 let content_type = content_type.to_str()?;
 
-                if !(content_type.starts_with("application/")
+                if content_type.starts_with("application/pdf") {
+                    let bytes = response
+                        .bytes()
+                        .await
+                        .map_err(BogrepError::ParseHttpResponse)?;
+                    let html = self.extract_binary(bytes.into(), bookmark.url()).await?;
+                    Ok(fetched_website(html))
+                } else if !(content_type.starts_with("application/")
                     || content_type.starts_with("image/")
                     || content_type.starts_with("audio/")
                     || content_type.starts_with("video/"))
                 {
-                    let html = response
-                        .text()
+                    let content_type = content_type.to_owned();
+                    let bytes = response
+                        .bytes()
                         .await
                         .map_err(BogrepError::ParseHttpResponse)?;
+                    let html = html::decode_html(&bytes, Some(&content_type));
 
                     if !html.is_empty() {
-                        Ok(html)
+                        Ok(fetched_website(html))
                     } else {
                         Err(BogrepError::EmptyResponse(bookmark.url().to_string()))
                     }
@@ -157,6 +459,61 @@ impl Fetch for Client {
             })
         }
     }
+
+    async fn fetch_robots(&self, url: &Url) -> Option<String> {
+        let robots_url = url.join("/robots.txt").ok()?;
+        debug!("Fetch robots.txt ({robots_url})");
+
+        let response = self
+            .client
+            .get(robots_url)
+            .headers(self.select_headers(url))
+            .send()
+            .await
+            .ok()?;
+
+        if response.status().is_success() {
+            response.text().await.ok()
+        } else {
+            None
+        }
+    }
+}
+
+/// Extract the text of a PDF document, and wrap it in an HTML document to
+/// cache it like a website.
+///
+/// The extraction is CPU-bound, and runs on a blocking thread to avoid stalling
+/// the runtime.
+#[cfg(feature = "pdf-extract")]
+async fn extract_pdf(bytes: Vec<u8>, url: &Url) -> Result<String, BogrepError> {
+    // Malformed documents can panic in `pdf_extract`, which fails the blocking
+    // task.
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|_| BogrepError::BinaryResponse(url.to_string()))?
+        .map_err(|err| {
+            debug!("Can't extract text from PDF ({url}): {err}");
+            BogrepError::BinaryResponse(url.to_string())
+        })?;
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Err(BogrepError::EmptyResponse(url.to_string()));
+    }
+
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    Ok(format!("<html><body><pre>{text}</pre></body></html>"))
+}
+
+#[cfg(not(feature = "pdf-extract"))]
+async fn extract_pdf(_bytes: Vec<u8>, url: &Url) -> Result<String, BogrepError> {
+    debug!("Can't extract text from PDF ({url}): feature `pdf-extract` is disabled");
+    Err(BogrepError::BinaryResponse(url.to_string()))
 }
 
 /// A throttler to limit the number of requests.
@@ -231,17 +588,83 @@ impl Throttler {
     }
 }
 
+/// A client which fetches websites from a local directory of fixtures instead
+/// of the network, e.g. to pre-seed content or to run offline.
+///
+/// The content of a url is read from `<dir>/<slug>.html`, where the slug is
+/// the human-readable filename of the url (see [`utils::readable_filename`]),
+/// or from `<dir>/<hash>.html`, where the hash is the SHA-256 hash of the url.
+#[derive(Debug, Clone)]
+pub struct DirClient {
+    /// The directory containing the fixtures.
+    dir: PathBuf,
+}
+
+impl DirClient {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The paths of the fixture files for a url in the order of lookup.
+    pub fn fixture_paths(&self, url: &Url) -> [PathBuf; 2] {
+        let slug = utils::readable_filename(url, 8);
+        let hash = utils::hash_content(url.as_str().as_bytes());
+        [
+            self.dir.join(format!("{slug}.html")),
+            self.dir.join(format!("{hash}.html")),
+        ]
+    }
+}
+
+#[async_trait]
+impl Fetch for DirClient {
+    async fn fetch(&self, bookmark: &TargetBookmark) -> Result<String, BogrepError> {
+        for path in self.fixture_paths(bookmark.url()) {
+            if path.is_file() {
+                debug!("Fetch fixture from {}", path.display());
+                return tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(BogrepError::ReadFile);
+            }
+        }
+
+        Err(BogrepError::HttpStatus {
+            status: StatusCode::NOT_FOUND.to_string(),
+            url: bookmark.url().to_string(),
+        })
+    }
+}
+
 /// A mock client to fetch websites used in testing.
 #[derive(Debug, Default, Clone)]
 pub struct MockClient {
     /// Mock the the HTML content.
     client_map: Arc<Mutex<HashMap<Url, String>>>,
+    /// The number of requests currently in flight.
+    in_flight: Arc<AtomicUsize>,
+    /// The maximum number of requests which were in flight at the same time.
+    max_in_flight: Arc<AtomicUsize>,
+    /// The urls of all fetched bookmarks in the order of the requests.
+    fetched_urls: Arc<Mutex<Vec<Url>>>,
+    /// Mock the cache validators.
+    validators_map: Arc<Mutex<HashMap<Url, CacheValidators>>>,
+    /// Mock the final urls after following redirects.
+    resolved_urls_map: Arc<Mutex<HashMap<Url, Url>>>,
 }
 
 impl MockClient {
     pub fn new() -> Self {
-        let client_map = Arc::new(Mutex::new(HashMap::new()));
-        Self { client_map }
+        Self::default()
+    }
+
+    /// The maximum number of requests which were in flight at the same time.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+
+    /// The urls of all fetched bookmarks in the order of the requests.
+    pub fn fetched_urls(&self) -> Vec<Url> {
+        self.fetched_urls.lock().clone()
     }
 
     pub fn add(&self, html: String, bookmark_url: &Url) -> Result<(), anyhow::Error> {
@@ -250,6 +673,16 @@ impl MockClient {
         Ok(())
     }
 
+    pub fn add_validators(&self, validators: CacheValidators, bookmark_url: &Url) {
+        let mut validators_map = self.validators_map.lock();
+        validators_map.insert(bookmark_url.clone(), validators);
+    }
+
+    pub fn add_resolved_url(&self, resolved_url: Url, bookmark_url: &Url) {
+        let mut resolved_urls_map = self.resolved_urls_map.lock();
+        resolved_urls_map.insert(bookmark_url.clone(), resolved_url);
+    }
+
     pub fn get(&self, bookmark_url: &Url) -> Option<String> {
         let client_map = self.client_map.lock();
         client_map
@@ -261,17 +694,186 @@ impl MockClient {
 #[async_trait]
 impl Fetch for MockClient {
     async fn fetch(&self, bookmark: &TargetBookmark) -> Result<String, BogrepError> {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        self.fetched_urls.lock().push(bookmark.url().to_owned());
+
+        // Yield to let other requests start before this one completes.
+        tokio::task::yield_now().await;
+
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
         let html = self
             .get(bookmark.url())
             .ok_or(anyhow!("Can't fetch bookmark"))?;
         Ok(html)
     }
+
+    async fn fetch_modified(
+        &self,
+        bookmark: &TargetBookmark,
+        validators: &CacheValidators,
+    ) -> Result<FetchedWebsite, BogrepError> {
+        let current_validators = self
+            .validators_map
+            .lock()
+            .get(bookmark.url())
+            .cloned()
+            .unwrap_or_default();
+
+        if !validators.is_empty() && validators == &current_validators {
+            self.fetched_urls.lock().push(bookmark.url().to_owned());
+            return Err(BogrepError::NotModified(bookmark.url().to_string()));
+        }
+
+        let html = self.fetch(bookmark).await?;
+        let resolved_url = self.resolved_urls_map.lock().get(bookmark.url()).cloned();
+        Ok(FetchedWebsite {
+            html,
+            validators: current_validators,
+            resolved_url,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Args, Subcommands};
+    use clap::Parser;
+    use std::fs;
     use tokio::{time::Instant, try_join};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[test]
+    fn test_client_config_request_timeout() {
+        let settings = Settings::default();
+
+        let client_config = ClientConfig::new(&settings).with_request_timeout(None);
+        assert_eq!(client_config.request_timeout, settings.request_timeout);
+
+        let args = Args::parse_from(["bogrep", "fetch", "--timeout", "120000"]);
+        let Some(Subcommands::Fetch(fetch_args)) = args.subcommands else {
+            panic!("Missing fetch subcommand");
+        };
+        let client_config = ClientConfig::new(&settings).with_request_timeout(fetch_args.timeout);
+        assert_eq!(client_config.request_timeout, 120_000);
+
+        let res = Args::try_parse_from(["bogrep", "sync", "--timeout", "0"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_select_headers() {
+        let mut settings = Settings::default();
+        settings
+            .add_header("example.com", "Cookie: session=1")
+            .unwrap();
+        let client = Client::new(&ClientConfig::new(&settings)).unwrap();
+
+        for url in ["https://example.com/path", "https://www.example.com"] {
+            let headers = client.select_headers(&Url::parse(url).unwrap());
+            assert_eq!(headers.get("cookie").unwrap(), "session=1");
+        }
+
+        for url in ["https://notexample.com", "https://example.com.org"] {
+            let headers = client.select_headers(&Url::parse(url).unwrap());
+            assert!(headers.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_headers() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/path"))
+            .and(header("cookie", "session=1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<html><body>Test content</body></html>"),
+            )
+            .mount(&mock_server)
+            .await;
+        let url = Url::parse(&format!("{}/path", mock_server.uri())).unwrap();
+        let bookmark = TargetBookmark::new(url.clone(), Utc::now());
+
+        let mut settings = Settings::default();
+        settings
+            .add_header(url.host_str().unwrap(), "Cookie: session=1")
+            .unwrap();
+        let client = Client::new(&ClientConfig::new(&settings)).unwrap();
+        let res = client.fetch(&bookmark).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        // Headers are not sent to other domains.
+        let mut settings = Settings::default();
+        settings
+            .add_header("example.com", "Cookie: session=1")
+            .unwrap();
+        let client = Client::new(&ClientConfig::new(&settings)).unwrap();
+        let res = client.fetch(&bookmark).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_accept_language() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/path"))
+            .and(header("accept-language", "de-DE"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<html><body>Test content</body></html>"),
+            )
+            .mount(&mock_server)
+            .await;
+        let url = Url::parse(&format!("{}/path", mock_server.uri())).unwrap();
+        let bookmark = TargetBookmark::new(url.clone(), Utc::now());
+
+        let mut settings = Settings::default();
+        settings.set_accept_language("de-DE").unwrap();
+        let client = Client::new(&ClientConfig::new(&settings)).unwrap();
+        let res = client.fetch(&bookmark).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        // No `Accept-Language` header is sent by default.
+        let client = Client::new(&ClientConfig::new(&Settings::default())).unwrap();
+        let res = client.fetch(&bookmark).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dir_client() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let client = DirClient::new(temp_path);
+
+        let url1 = Url::parse("https://url1.com/path").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let [slug_path, _] = client.fixture_paths(&url1);
+        let [_, hash_path] = client.fixture_paths(&url2);
+        fs::write(slug_path, "<html>fixture1</html>").unwrap();
+        fs::write(hash_path, "<html>fixture2</html>").unwrap();
+
+        let bookmark1 = TargetBookmark::new(url1, Utc::now());
+        let bookmark2 = TargetBookmark::new(url2, Utc::now());
+        let bookmark3 = TargetBookmark::new(url3, Utc::now());
+
+        let html = client.fetch(&bookmark1).await.unwrap();
+        assert_eq!(html, "<html>fixture1</html>");
+
+        let html = client.fetch(&bookmark2).await.unwrap();
+        assert_eq!(html, "<html>fixture2</html>");
+
+        let res = client.fetch(&bookmark3).await;
+        assert!(
+            matches!(res, Err(BogrepError::HttpStatus { ref status, .. }) if status == "404 Not Found")
+        );
+    }
 
     #[tokio::test]
     async fn test_throttle() {
@@ -322,4 +924,104 @@ mod tests {
         let last_fetched = throttler.update_fetch_time(&bookmark3, now).unwrap();
         assert_eq!(last_fetched, Some(now.timestamp_millis() + 2000));
     }
+
+    #[tokio::test]
+    async fn test_fetch_modified() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .and(header("If-None-Match", "\"etag1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .insert_header("etag", "\"etag1\"")
+                    .insert_header("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+                    .set_body_string("<html><body>Test content</body></html>"),
+            )
+            .mount(&mock_server)
+            .await;
+        let settings = Settings::default();
+        let mut client_config = ClientConfig::new(&settings);
+        client_config.request_throttling = 1;
+        let client = Client::new(&client_config).unwrap();
+        let bookmark = TargetBookmark::new(Url::parse(&mock_server.uri()).unwrap(), Utc::now());
+
+        let res = client
+            .fetch_modified(&bookmark, &CacheValidators::default())
+            .await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let website = res.unwrap();
+        let validators = website.validators;
+        assert_eq!(website.html, "<html><body>Test content</body></html>");
+        assert_eq!(
+            validators,
+            CacheValidators {
+                etag: Some("\"etag1\"".to_owned()),
+                last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_owned()),
+            }
+        );
+
+        let res = client.fetch_modified(&bookmark, &validators).await;
+        assert!(matches!(res, Err(BogrepError::NotModified(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_redirects() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/old"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("location", format!("{}/moved", mock_server.uri())),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/moved"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("location", format!("{}/new", mock_server.uri())),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/new"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/html")
+                    .set_body_string("<html><body>Test content</body></html>"),
+            )
+            .mount(&mock_server)
+            .await;
+        let settings = Settings::default();
+        let mut client_config = ClientConfig::new(&settings);
+        client_config.request_throttling = 1;
+        let url = Url::parse(&format!("{}/old", mock_server.uri())).unwrap();
+        let bookmark = TargetBookmark::new(url, Utc::now());
+
+        let client = Client::new(&client_config).unwrap();
+        let res = client
+            .fetch_modified(&bookmark, &CacheValidators::default())
+            .await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let website = res.unwrap();
+        assert_eq!(website.html, "<html><body>Test content</body></html>");
+        assert_eq!(
+            website.resolved_url,
+            Some(Url::parse(&format!("{}/new", mock_server.uri())).unwrap())
+        );
+
+        let client = Client::new(&client_config.with_follow_redirects(false)).unwrap();
+        let res = client.fetch(&bookmark).await;
+        assert!(
+            matches!(res, Err(BogrepError::HttpStatus { ref status, .. }) if status == "301 Moved Permanently")
+        );
+    }
 }
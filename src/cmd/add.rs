@@ -18,15 +18,31 @@ pub async fn add(config: Config, args: AddArgs) -> Result<(), anyhow::Error> {
         return Err(anyhow!("Invalid argument: Specify the URLs to be added"));
     }
 
+    if args.title.is_some() && urls.len() > 1 {
+        return Err(anyhow!(
+            "Invalid argument: `--title` can only be used for a single URL"
+        ));
+    }
+
     let now = Utc::now();
+    let run_mode = if args.fetch {
+        RunMode::FetchUrls(urls)
+    } else {
+        RunMode::AddUrls(urls)
+    };
     let service_config = ServiceConfig::new(
-        RunMode::AddUrls(urls),
+        run_mode,
         &config.settings.ignored_urls,
-        config.settings.max_concurrent_requests,
-    )?;
+        config.max_concurrent_requests(None),
+    )?
+    .with_title(args.title)
+    .with_folder(args.folder)
+    .with_settings(&config.settings);
     let client_config = ClientConfig::new(&config.settings);
     let cache_mode = CacheMode::new(&None, &config.settings.cache_mode);
-    let cache = Cache::new(&config.cache_path, cache_mode);
+    let cache = Cache::new(&config.cache_path, cache_mode)
+        .with_text_extractor(config.settings.extractor)
+        .with_file_mode(config.settings.cache_file_mode);
     let client = Client::new(&client_config)?;
     let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
     let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
@@ -0,0 +1,213 @@
+use crate::{
+    args::{BackupArgs, RestoreArgs},
+    bookmark_reader::ReadTarget,
+    utils, Config, Settings, TargetBookmarks,
+};
+use anyhow::{anyhow, Context};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::debug;
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+use tar::{Archive, Builder, EntryType};
+
+const BOOKMARKS_ENTRY: &str = "bookmarks.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const CACHE_ENTRY: &str = "cache";
+
+/// Bundle `bookmarks.json`, `settings.json`, and the cache directory into a
+/// compressed tarball.
+pub fn backup(config: &Config, args: &BackupArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let file = utils::create_file(&args.output)?;
+    let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+
+    builder
+        .append_path_with_name(&config.target_bookmark_file, BOOKMARKS_ENTRY)
+        .context("Can't add `bookmarks.json` to archive")?;
+    builder
+        .append_path_with_name(&config.settings_path, SETTINGS_ENTRY)
+        .context("Can't add `settings.json` to archive")?;
+
+    let mut cached_files = 0;
+
+    if config.cache_path.exists() {
+        builder.append_dir(CACHE_ENTRY, &config.cache_path)?;
+
+        for entry in fs::read_dir(&config.cache_path)? {
+            let path = entry?.path();
+
+            if path.is_file() {
+                let file_name = path.file_name().ok_or(anyhow!("Invalid path"))?;
+                builder
+                    .append_path_with_name(&path, Path::new(CACHE_ENTRY).join(file_name))
+                    .context(format!("Can't add {} to archive", path.display()))?;
+                cached_files += 1;
+            }
+        }
+    }
+
+    builder.into_inner()?.finish()?;
+
+    println!(
+        "Backed up {cached_files} cached files to {}",
+        args.output.display()
+    );
+
+    Ok(())
+}
+
+/// Unpack a tarball created by `bogrep backup` into the configured locations.
+pub fn restore(config: &Config, args: &RestoreArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    if !args.force {
+        let mut target_bookmarks = TargetBookmarks::default();
+        let mut target_reader = utils::open_file_in_read_mode(&config.target_bookmark_file)?;
+        target_reader.read(&mut target_bookmarks)?;
+
+        if !target_bookmarks.is_empty() {
+            return Err(anyhow!(
+                "Bookmarks already exist: use `--force` to overwrite them"
+            ));
+        }
+    }
+
+    // Validate all entries before unpacking anything, so that an invalid
+    // archive doesn't leave a partial restore behind.
+    let file = utils::open_file_in_read_mode(&args.input)?;
+    let mut archive = Archive::new(GzDecoder::new(file));
+
+    for entry in archive.entries()? {
+        let entry = entry?;
+
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+
+        let path = entry.path()?;
+
+        if restore_path(config, &path).is_none() {
+            return Err(anyhow!("Invalid entry in archive: {}", path.display()));
+        }
+    }
+
+    fs::create_dir_all(&config.cache_path)?;
+
+    if args.force {
+        clear_cache(&config.cache_path)?;
+    }
+
+    let file = utils::open_file_in_read_mode(&args.input)?;
+    let mut archive = Archive::new(GzDecoder::new(file));
+    let mut cached_files = 0;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+
+        let path = entry.path()?.into_owned();
+        let Some(target_path) = restore_path(config, &path) else {
+            return Err(anyhow!("Invalid entry in archive: {}", path.display()));
+        };
+
+        if path.starts_with(CACHE_ENTRY) {
+            let mut file = match config.settings.cache_file_mode {
+                Some(file_mode) => utils::create_file_with_mode(&target_path, file_mode)?,
+                None => utils::create_file(&target_path)?,
+            };
+            io::copy(&mut entry, &mut file)
+                .context(format!("Can't unpack {}", target_path.display()))?;
+            cached_files += 1;
+        } else {
+            entry
+                .unpack(&target_path)
+                .context(format!("Can't unpack {}", target_path.display()))?;
+        }
+    }
+
+    // Keep the local cache path, which may differ from the cache path of the
+    // backed up settings.
+    let mut settings = Settings::init(&config.settings_path)?;
+    settings.cache_path = config.settings.cache_path.clone();
+    utils::write_settings(&config.settings_path, &settings)?;
+
+    println!(
+        "Restored {cached_files} cached files from {}",
+        args.input.display()
+    );
+
+    Ok(())
+}
+
+/// Remove all files in the cache directory.
+fn clear_cache(cache_path: &Path) -> Result<(), anyhow::Error> {
+    debug!("Clear cache at {}", cache_path.display());
+
+    for entry in fs::read_dir(cache_path)? {
+        let path = entry?.path();
+
+        if path.is_file() {
+            utils::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Map an entry of the archive to the configured location, and reject
+/// entries which would be unpacked outside of it.
+fn restore_path(config: &Config, path: &Path) -> Option<PathBuf> {
+    if path == Path::new(BOOKMARKS_ENTRY) {
+        Some(config.target_bookmark_file.clone())
+    } else if path == Path::new(SETTINGS_ENTRY) {
+        Some(config.settings_path.clone())
+    } else {
+        let file_name = path.strip_prefix(CACHE_ENTRY).ok()?;
+        let mut components = file_name.components();
+
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(file_name)), None) => Some(config.cache_path.join(file_name)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_path() {
+        let config = Config {
+            settings_path: PathBuf::from("home/settings.json"),
+            cache_path: PathBuf::from("home/cache"),
+            target_bookmark_file: PathBuf::from("home/bookmarks.json"),
+            target_bookmark_lock_file: PathBuf::from("home/bookmarks-lock.json"),
+            manifest_file: PathBuf::from("home/manifest.json"),
+            index_file: PathBuf::from("home/index.json"),
+            settings: Settings::default(),
+        };
+
+        assert_eq!(
+            restore_path(&config, Path::new("bookmarks.json")),
+            Some(PathBuf::from("home/bookmarks.json"))
+        );
+        assert_eq!(
+            restore_path(&config, Path::new("settings.json")),
+            Some(PathBuf::from("home/settings.json"))
+        );
+        assert_eq!(
+            restore_path(&config, Path::new("cache/id.txt")),
+            Some(PathBuf::from("home/cache/id.txt"))
+        );
+        assert_eq!(restore_path(&config, Path::new("cache/../id.txt")), None);
+        assert_eq!(restore_path(&config, Path::new("cache/dir/id.txt")), None);
+        assert_eq!(restore_path(&config, Path::new("other.json")), None);
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{
+    args::CatArgs,
+    bookmark_reader::ReadTarget,
+    html::{self, TextExtractor},
+    utils, Cache, CacheMode, Caching, Config, TargetBookmark, TargetBookmarks,
+};
+use anyhow::anyhow;
+use clap::ValueEnum;
+use log::debug;
+use std::path::Path;
+use url::Url;
+
+/// The format to print the cached content of a bookmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CatMode {
+    /// Print the content cached as text.
+    Text,
+    /// Print the content cached as HTML.
+    Html,
+    /// Convert the content cached as HTML to markdown.
+    Markdown,
+}
+
+/// Print the cached content of a bookmark.
+pub fn cat(config: &Config, args: &CatArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let mut target_bookmarks = TargetBookmarks::default();
+    let mut target_reader = utils::open_file_in_read_mode(&config.target_bookmark_file)?;
+    target_reader.read(&mut target_bookmarks)?;
+
+    let bookmark = find_bookmark(&target_bookmarks, &args.bookmark)
+        .ok_or(anyhow!("Bookmark not found: {}", args.bookmark))?;
+    let content = read_content(
+        &config.cache_path,
+        bookmark,
+        args.mode,
+        &config.settings.cache_mode,
+    )?;
+    let content = match args.as_format {
+        Some(format) => convert_content(content, format, bookmark, &config.settings.extractor)?,
+        None => content.display(),
+    };
+
+    println!("{content}");
+
+    Ok(())
+}
+
+/// Find a bookmark by its url or id.
+fn find_bookmark<'a>(
+    target_bookmarks: &'a TargetBookmarks,
+    url_or_id: &str,
+) -> Option<&'a TargetBookmark> {
+    Url::parse(url_or_id)
+        .ok()
+        .and_then(|url| target_bookmarks.get(&url))
+        .or_else(|| {
+            target_bookmarks
+                .values()
+                .find(|bookmark| bookmark.id() == url_or_id)
+        })
+}
+
+/// The cached content of a bookmark together with the cache mode it was read
+/// from.
+#[derive(Debug, PartialEq)]
+struct CachedContent {
+    content: String,
+    cache_mode: CacheMode,
+    mode: Option<CatMode>,
+}
+
+impl CachedContent {
+    /// The content to print if no conversion was requested.
+    fn display(self) -> String {
+        match self.mode {
+            Some(CatMode::Markdown) => html::convert_to_markdown(&self.content),
+            _ => self.content,
+        }
+    }
+}
+
+/// Read the cached content of a bookmark.
+///
+/// If no mode is given, the configured cache mode is preferred over the other
+/// available cache modes.
+fn read_content(
+    cache_path: &Path,
+    bookmark: &TargetBookmark,
+    mode: Option<CatMode>,
+    configured: &CacheMode,
+) -> Result<CachedContent, anyhow::Error> {
+    let get = |cache_mode: CacheMode| {
+        Cache::new(cache_path, cache_mode.clone())
+            .get(bookmark)?
+            .map(|content| CachedContent {
+                content,
+                cache_mode: cache_mode.clone(),
+                mode,
+            })
+            .ok_or(anyhow!(
+                "Bookmark {} is not cached in {cache_mode} mode",
+                bookmark.url()
+            ))
+    };
+
+    match mode {
+        Some(CatMode::Text) => get(CacheMode::Text),
+        Some(CatMode::Html) | Some(CatMode::Markdown) => get(CacheMode::Html),
+        None => {
+            let mut cache_modes = vec![configured.clone()];
+            cache_modes.extend(Cache::modes().into_iter().filter(|mode| mode != configured));
+
+            cache_modes
+                .into_iter()
+                .find_map(|cache_mode| get(cache_mode).ok())
+                .ok_or(anyhow!(
+                    "Bookmark {} is not cached, run `bogrep fetch` first",
+                    bookmark.url()
+                ))
+        }
+    }
+}
+
+/// Convert the cached content of a bookmark to the given format for display
+/// without rewriting the cache.
+fn convert_content(
+    cached: CachedContent,
+    format: CatMode,
+    bookmark: &TargetBookmark,
+    text_extractor: &TextExtractor,
+) -> Result<String, anyhow::Error> {
+    let content = match (cached.cache_mode, format) {
+        (CacheMode::Html, CatMode::Html) => cached.content,
+        (CacheMode::Html, CatMode::Text) => {
+            html::convert_to_text(&cached.content, bookmark.url(), text_extractor)?
+        }
+        (CacheMode::Html, CatMode::Markdown) => html::convert_to_markdown(&cached.content),
+        (CacheMode::Text, CatMode::Text) | (CacheMode::Text, CatMode::Markdown) => cached.content,
+        (CacheMode::Text, CatMode::Html) => {
+            return Err(anyhow!(
+                "Can't convert bookmark {} cached as text to HTML",
+                bookmark.url()
+            ))
+        }
+    };
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TargetBookmarkBuilder;
+    use chrono::Utc;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_content() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let now = Utc::now();
+        let url = Url::parse("https://url1.com").unwrap();
+        let bookmark = TargetBookmarkBuilder::new(url.clone(), now).build();
+        fs::write(
+            temp_path.join(format!("{}.html", bookmark.id())),
+            "<html><body><h1>Title</h1></body></html>",
+        )
+        .unwrap();
+
+        let mut target_bookmarks = TargetBookmarks::default();
+        target_bookmarks.insert(bookmark.clone());
+        assert_eq!(
+            find_bookmark(&target_bookmarks, url.as_str()),
+            Some(&bookmark)
+        );
+        assert_eq!(
+            find_bookmark(&target_bookmarks, bookmark.id()),
+            Some(&bookmark)
+        );
+        assert!(find_bookmark(&target_bookmarks, "https://url2.com").is_none());
+
+        let res = read_content(temp_path, &bookmark, None, &CacheMode::Text);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap().display(),
+            "<html><body><h1>Title</h1></body></html>"
+        );
+
+        let res = read_content(
+            temp_path,
+            &bookmark,
+            Some(CatMode::Markdown),
+            &CacheMode::Text,
+        );
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap().display(), "Title\n==========");
+
+        let res = read_content(temp_path, &bookmark, Some(CatMode::Text), &CacheMode::Text);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_convert_content() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let url = Url::parse("https://url1.com").unwrap();
+        let bookmark = TargetBookmarkBuilder::new(url, Utc::now()).build();
+        let html = "<html><body><h1>Title</h1><p>Some text</p></body></html>";
+        fs::write(temp_path.join(format!("{}.html", bookmark.id())), html).unwrap();
+
+        let convert = |format| {
+            let cached = read_content(temp_path, &bookmark, None, &CacheMode::Text).unwrap();
+            convert_content(cached, format, &bookmark, &TextExtractor::DomText)
+        };
+
+        let res = convert(CatMode::Text);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), "Title Some text");
+
+        let res = convert(CatMode::Html);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), html);
+
+        let res = convert(CatMode::Markdown);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), "Title\n==========\n\nSome text");
+
+        // The cache is not rewritten.
+        assert!(!temp_path.join(format!("{}.txt", bookmark.id())).exists());
+
+        let cached = CachedContent {
+            content: String::from("Title"),
+            cache_mode: CacheMode::Text,
+            mode: None,
+        };
+        let res = convert_content(cached, CatMode::Html, &bookmark, &TextExtractor::DomText);
+        assert!(res.is_err());
+    }
+}
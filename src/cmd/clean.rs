@@ -1,8 +1,8 @@
 use crate::{
-    args::CleanArgs, cache::CacheMode, client::ClientConfig, utils, BookmarkManager,
-    BookmarkService, Cache, Client, Config, RunMode, ServiceConfig,
+    args::CleanArgs, bookmarks::Status, cache::CacheMode, client::ClientConfig, utils,
+    BookmarkManager, BookmarkService, Cache, Caching, Client, Config, RunMode, ServiceConfig,
 };
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use log::debug;
 
 /// Clean up cache for removed bookmarks.
@@ -10,13 +10,25 @@ pub async fn clean(config: &Config, args: &CleanArgs) -> Result<(), anyhow::Erro
     debug!("{args:?}");
 
     let now = Utc::now();
+
+    if args.unreferenced {
+        return clean_unreferenced(config, now);
+    }
+
+    if args.expired {
+        return clean_expired(config, now).await;
+    }
+
+    if let Some(cache_mode) = &args.cache_mode {
+        return clean_cache_mode(config, cache_mode, now);
+    }
+
     let run_mode = if args.all {
         RunMode::RemoveAll
     } else {
         RunMode::Remove
     };
-    let service_config =
-        ServiceConfig::new(run_mode, &[], config.settings.max_concurrent_requests)?;
+    let service_config = ServiceConfig::new(run_mode, &[], config.max_concurrent_requests(None))?;
     let client_config = ClientConfig::new(&config.settings);
     let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
     let cache = Cache::new(&config.cache_path, cache_mode);
@@ -29,3 +41,63 @@ pub async fn clean(config: &Config, args: &CleanArgs) -> Result<(), anyhow::Erro
 
     Ok(())
 }
+
+/// Remove bookmarks which were removed from the sources longer ago than the
+/// removal grace period, together with their cache.
+async fn clean_expired(config: &Config, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    let removal_grace_period = config.settings.removal_grace_period.unwrap_or_default();
+    let removed_before = now - TimeDelta::milliseconds(removal_grace_period as i64);
+    let cache = Cache::new(&config.cache_path, config.settings.cache_mode.clone());
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+    bookmark_manager.import(now)?;
+
+    let expired = bookmark_manager.expire_bookmarks(removed_before);
+
+    for bookmark in bookmark_manager.target_bookmarks_mut().values_mut() {
+        if bookmark.status() == &Status::Removed {
+            cache.remove_by_modes(bookmark).await?;
+        }
+    }
+
+    bookmark_manager.finish();
+    bookmark_manager.export()?;
+
+    println!("Removed {expired} expired bookmarks");
+
+    Ok(())
+}
+
+/// Remove the cached files of a single cache mode for all bookmarks.
+fn clean_cache_mode(
+    config: &Config,
+    cache_mode: &CacheMode,
+    now: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    let cache = Cache::new(&config.cache_path, config.settings.cache_mode.clone());
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+    bookmark_manager.import(now)?;
+
+    cache.clear_mode(bookmark_manager.target_bookmarks_mut(), cache_mode)?;
+
+    bookmark_manager.finish();
+    bookmark_manager.export()?;
+
+    Ok(())
+}
+
+/// Remove cached files whose id doesn't belong to any bookmark.
+fn clean_unreferenced(config: &Config, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    let cache = Cache::new(&config.cache_path, config.settings.cache_mode.clone());
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+    bookmark_manager.import(now)?;
+
+    let (removed_files, freed_bytes) =
+        cache.remove_unreferenced(bookmark_manager.target_bookmarks())?;
+
+    println!("Removed {removed_files} unreferenced files, freed {freed_bytes} bytes");
+
+    Ok(())
+}
@@ -1,25 +1,69 @@
 use crate::{
-    bookmark_reader::SourceReader, bookmarks::RawSource, json, settings::SettingsArgs, utils,
-    Config, ConfigArgs, Settings,
+    bookmark_reader::{SourceOs, SourceReader},
+    bookmarks::RawSource,
+    json,
+    settings::SettingsArgs,
+    utils, Config, ConfigArgs, Settings,
 };
 use anyhow::{anyhow, Context};
 use log::{debug, warn};
-use std::{fs, io::Write};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 /// Configure the source files to import the bookmarks, the cache mode, or the
 /// ignoure urls .
 pub fn configure(mut config: Config, args: ConfigArgs) -> Result<(), anyhow::Error> {
     debug!("{args:?}");
 
+    if args.list_sources {
+        let home_dir = dirs::home_dir().ok_or(anyhow!("Missing home dir"))?;
+        let source_os = utils::get_supported_os();
+        list_sources(
+            &config.settings,
+            &home_dir,
+            source_os.as_ref(),
+            io::stdout().lock(),
+        )?;
+        return Ok(());
+    }
+
     if args.dry_run {
         println!("Running in dry mode ...")
     }
 
+    if args.reset_settings.reset || args.reset_settings.reset_sources {
+        let reset = if args.reset_settings.reset {
+            "settings"
+        } else {
+            "sources"
+        };
+        println!(
+            "Reset {reset} in {}? yes (y) or no (n)",
+            config.settings_path.display()
+        );
+
+        if confirm(io::stdin().lock())? {
+            reset_settings(&mut config.settings, args.reset_settings.reset_sources);
+            println!("Reset {reset}");
+        } else {
+            println!("Aborting ...");
+            return Ok(());
+        }
+    }
+
     let source_path = args
         .set_source
         .source
         .as_ref()
-        .map(|source_path| fs::canonicalize(source_path).context("Invalid source path"))
+        .map(|source_path| match fs::canonicalize(source_path) {
+            Ok(source_path) => Ok(source_path),
+            // The source path may appear later, e.g. after the browser is
+            // installed.
+            Err(_) => std::path::absolute(source_path).context("Invalid source path"),
+        })
         .transpose()?;
     let source_folders = &args.set_source.folders;
 
@@ -29,14 +73,46 @@ pub fn configure(mut config: Config, args: ConfigArgs) -> Result<(), anyhow::Err
         ));
     }
 
-    let source = source_path.map(|source_path| RawSource::new(source_path, source_folders.clone()));
+    if source_path.is_none() && args.set_source.format.is_some() {
+        return Err(anyhow!(
+            "Missing source: specifiy `--source` to configure the format"
+        ));
+    }
+
+    if source_path.is_none() && args.set_source.source_type.is_some() {
+        return Err(anyhow!(
+            "Missing source: specifiy `--source` to configure the source type"
+        ));
+    }
+
+    // The folders of an existing source are set without replacing the
+    // source.
+    let (source_path, import_folders) = match args.set_source.import_folders {
+        Some(import_folders) => (None, Some((source_path, import_folders))),
+        None => (source_path, None),
+    };
+    let source = source_path.map(|source_path| {
+        RawSource::new(source_path, source_folders.clone())
+            .with_format(args.set_source.format)
+            .with_label(args.set_source.source_type.clone())
+    });
 
     if let Some(ref source) = source {
-        // Validate source file
-        SourceReader::init(source)?;
+        validate_source(source);
     }
 
-    let settings_file = utils::open_and_truncate_file(&config.settings_path)?;
+    let cache_path = args
+        .set_cache_path
+        .cache_path
+        .as_ref()
+        .map(|cache_path| init_cache_path(cache_path, args.dry_run))
+        .transpose()?;
+
+    if let Some(cache_path) = &cache_path {
+        if args.set_cache_path.migrate && !args.dry_run {
+            migrate_cache(&config.cache_path, cache_path)?;
+        }
+    }
 
     let settings_args = SettingsArgs::new(
         source,
@@ -50,9 +126,90 @@ pub fn configure(mut config: Config, args: ConfigArgs) -> Result<(), anyhow::Err
         args.set_max_idle_connections_per_host
             .max_idle_connections_per_host,
         args.set_idle_connections_timeout.idle_connections_timeout,
+        args.set_max_content_size.max_content_size,
+        args.set_max_content_size.oversized_content,
+        args.set_extractor.extractor,
+        cache_path,
+        args.set_headers
+            .headers
+            .chunks_exact(2)
+            .map(|header| (header[0].clone(), header[1].clone()))
+            .collect(),
+        args.set_underlying_depth.underlying_depth,
+        import_folders,
+        args.set_respect_robots.respect_robots,
+        args.set_accept_language.accept_language,
+        args.set_cache_file_mode.cache_file_mode,
+        args.set_removal_grace_period
+            .removal_grace_period
+            .map(|removal_grace_period| removal_grace_period.as_millis() as u64),
+        args.set_query.strip_query,
+        args.set_query.include_query,
     );
 
-    configure_settings(&mut config.settings, &settings_args, settings_file)?;
+    // The settings are written only if they are valid, so that an invalid
+    // argument doesn't leave an empty settings file behind.
+    let mut settings_json = Vec::new();
+    configure_settings(&mut config.settings, &settings_args, &mut settings_json)?;
+    utils::write_file_atomically(&config.settings_path, &settings_json)?;
+
+    Ok(())
+}
+
+/// List the configured sources and the discovered sources which are not
+/// configured yet.
+fn list_sources(
+    settings: &Settings,
+    home_dir: &Path,
+    source_os: Option<&SourceOs>,
+    mut writer: impl Write,
+) -> Result<(), anyhow::Error> {
+    let canonicalize = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+    let configured_paths = settings
+        .sources
+        .iter()
+        .map(|source| canonicalize(&source.path))
+        .collect::<Vec<_>>();
+
+    writeln!(writer, "Configured sources:")?;
+
+    if settings.sources.is_empty() {
+        writeln!(writer, "  none")?;
+    }
+
+    for source in &settings.sources {
+        if source.folders.is_empty() {
+            writeln!(writer, "  {}", source.path.display())?;
+        } else {
+            writeln!(
+                writer,
+                "  {} (folders: {})",
+                source.path.display(),
+                source.folders.join(", ")
+            )?;
+        }
+    }
+
+    let discovered_sources = match source_os {
+        Some(source_os) => SourceReader::discover_sources(home_dir, source_os)?,
+        None => vec![],
+    };
+    let unconfigured_sources = discovered_sources
+        .iter()
+        .filter(|(_, path)| !configured_paths.contains(&canonicalize(path)))
+        .collect::<Vec<_>>();
+
+    writeln!(writer, "Discovered sources which are not configured:")?;
+
+    if unconfigured_sources.is_empty() {
+        writeln!(writer, "  none")?;
+    }
+
+    for (source_type, path) in unconfigured_sources {
+        writeln!(writer, "  {source_type}: {}", path.display())?;
+    }
+
+    writer.flush()?;
 
     Ok(())
 }
@@ -66,6 +223,10 @@ fn configure_settings(
         settings.set_source(source.clone())?;
     }
 
+    if let Some((source_path, folders)) = &settings_args.import_folders {
+        settings.set_source_folders(source_path.as_deref(), folders.clone())?;
+    }
+
     if let Some(cache_mode) = &settings_args.cache_mode {
         settings.set_cache_mode(cache_mode.clone());
     }
@@ -94,6 +255,54 @@ fn configure_settings(
         settings.set_idle_connections_timeout(idle_connections_timeout);
     }
 
+    if let Some(extractor) = &settings_args.extractor {
+        settings.set_extractor(*extractor);
+    }
+
+    if let Some(underlying_depth) = settings_args.underlying_depth {
+        settings.set_underlying_depth(underlying_depth);
+    }
+
+    if let Some(respect_robots) = settings_args.respect_robots {
+        settings.set_respect_robots(respect_robots);
+    }
+
+    if let Some(cache_file_mode) = settings_args.cache_file_mode {
+        settings.set_cache_file_mode(cache_file_mode);
+    }
+
+    if let Some(removal_grace_period) = settings_args.removal_grace_period {
+        settings.set_removal_grace_period(removal_grace_period);
+    }
+
+    if let Some(strip_query) = &settings_args.strip_query {
+        settings.set_strip_query(strip_query);
+    }
+
+    if !settings_args.include_query.is_empty() {
+        settings.set_include_query(&settings_args.include_query);
+    }
+
+    if let Some(accept_language) = &settings_args.accept_language {
+        settings.set_accept_language(accept_language)?;
+    }
+
+    if let Some(cache_path) = &settings_args.cache_path {
+        settings.set_cache_path(cache_path.clone());
+    }
+
+    for (domain, header) in &settings_args.headers {
+        settings.add_header(domain, header)?;
+    }
+
+    if let Some(max_content_size_bytes) = settings_args.max_content_size_bytes {
+        settings.set_max_content_size_bytes(max_content_size_bytes);
+    }
+
+    if let Some(oversized_content) = &settings_args.oversized_content {
+        settings.set_oversized_content(oversized_content.clone());
+    }
+
     if settings_args.max_open_files.is_some() && settings_args.max_concurrent_requests.is_some() {
         #[cfg(not(any(target_os = "windows")))]
         crate::config::set_file_descriptor_limit(
@@ -120,11 +329,166 @@ fn configure_settings(
     Ok(())
 }
 
+/// Check that the source can be imported, and warn otherwise.
+fn validate_source(source: &RawSource) {
+    if !source.path.exists() {
+        warn!(
+            "Source path '{}' doesn't exist yet and can't be imported",
+            source.path.display()
+        );
+    } else if let Err(err) = SourceReader::init_all(source) {
+        warn!(
+            "Source '{}' can't be imported: {err}",
+            source.path.display()
+        );
+    }
+}
+
+/// Restore the default settings, or remove the configured sources only.
+fn reset_settings(settings: &mut Settings, sources_only: bool) {
+    if sources_only {
+        debug!("Reset sources");
+        settings.sources.clear();
+    } else {
+        debug!("Reset settings");
+        *settings = Settings::default();
+    }
+}
+
+/// Read the confirmation of the user.
+fn confirm(mut reader: impl io::BufRead) -> Result<bool, anyhow::Error> {
+    loop {
+        let mut input = String::new();
+
+        if reader.read_line(&mut input)? == 0 {
+            return Ok(false);
+        }
+
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => {
+                println!("Invalid input. Please try again");
+                continue;
+            }
+        }
+    }
+}
+
+/// Create the cache directory if it doesn't exist, and check that it is
+/// writable.
+///
+/// In dry mode, the cache directory is neither created nor checked.
+fn init_cache_path(cache_path: &Path, dry_run: bool) -> Result<PathBuf, anyhow::Error> {
+    if dry_run {
+        return match fs::canonicalize(cache_path) {
+            Ok(cache_path) => Ok(cache_path),
+            Err(_) => std::path::absolute(cache_path).context("Invalid cache path"),
+        };
+    }
+
+    fs::create_dir_all(cache_path).context(format!(
+        "Can't create cache directory at {}",
+        cache_path.display()
+    ))?;
+    let cache_path = fs::canonicalize(cache_path).context("Invalid cache path")?;
+
+    let probe_path = cache_path.join(".bogrep-probe");
+    fs::write(&probe_path, b"").context(format!(
+        "Cache directory is not writable: {}",
+        cache_path.display()
+    ))?;
+    fs::remove_file(&probe_path)?;
+
+    Ok(cache_path)
+}
+
+/// Move the cached files from the current cache directory to the new cache
+/// directory.
+fn migrate_cache(from_path: &Path, to_path: &Path) -> Result<(), anyhow::Error> {
+    if !from_path.exists() || fs::canonicalize(from_path)? == to_path {
+        return Ok(());
+    }
+
+    let mut migrated_files = 0;
+
+    for entry in fs::read_dir(from_path)? {
+        let from_file = entry?.path();
+
+        if !from_file.is_file() {
+            continue;
+        }
+
+        if let Some(file_name) = from_file.file_name() {
+            let to_file = to_path.join(file_name);
+
+            // Renaming fails if the directories are on different file
+            // systems.
+            if fs::rename(&from_file, &to_file).is_err() {
+                fs::copy(&from_file, &to_file).context(format!(
+                    "Can't copy {} to {}",
+                    from_file.display(),
+                    to_file.display()
+                ))?;
+                fs::remove_file(&from_file)?;
+            }
+
+            migrated_files += 1;
+        }
+    }
+
+    println!(
+        "Migrated {migrated_files} files from {} to {}",
+        from_path.display(),
+        to_path.display()
+    );
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::CacheMode;
+    use crate::{CacheMode, OversizedContent};
     use std::{io::Cursor, path::PathBuf};
+    use tempfile::tempdir;
+
+    #[cfg(not(any(target_os = "windows")))]
+    #[test]
+    fn test_list_sources() {
+        let source_os = SourceOs::Linux;
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        crate::test_utils::tests::create_test_files(temp_path, &source_os);
+        let discovered_sources = SourceReader::discover_sources(temp_path, &source_os).unwrap();
+        assert_eq!(discovered_sources.len(), 10);
+        let (_, configured_path) = &discovered_sources[0];
+        let mut settings = Settings::default();
+        settings
+            .set_source(RawSource::new(configured_path, vec!["dev".to_owned()]))
+            .unwrap();
+        let mut cursor = Cursor::new(Vec::new());
+
+        let res = list_sources(&settings, temp_path, Some(&source_os), &mut cursor);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let output = String::from_utf8(cursor.into_inner()).unwrap();
+        let (configured, discovered) = output
+            .split_once("Discovered sources which are not configured:\n")
+            .unwrap();
+        assert_eq!(
+            configured,
+            format!(
+                "Configured sources:\n  {} (folders: dev)\n",
+                configured_path.display()
+            )
+        );
+        assert_eq!(discovered.lines().count(), 9);
+
+        for (source_type, path) in &discovered_sources[1..] {
+            assert!(discovered.contains(&format!("  {source_type}: {}\n", path.display())));
+        }
+    }
 
     #[test]
     fn test_configure_source() {
@@ -134,6 +498,8 @@ mod tests {
             source: Some(RawSource {
                 path: PathBuf::from("test_data/bookmarks_simple.txt"),
                 folders: vec!["dev".to_string(), "articles".to_string()],
+                format: None,
+                label: None,
             }),
             ..Default::default()
         };
@@ -143,6 +509,7 @@ mod tests {
 
         let actual_settings = String::from_utf8(cursor.into_inner()).unwrap();
         let expected_settings = r#"{
+    "version": 2,
     "sources": [
         {
             "source": "test_data/bookmarks_simple.txt",
@@ -178,6 +545,7 @@ mod tests {
         assert!(res.is_ok(), "{}", res.unwrap_err());
         let actual_settings = String::from_utf8(cursor.into_inner()).unwrap();
         let expected_settings = r#"{
+    "version": 2,
     "sources": [],
     "ignored_urls": [],
     "underlying_urls": [],
@@ -192,6 +560,53 @@ mod tests {
         assert_eq!(actual_settings, expected_settings);
     }
 
+    #[test]
+    fn test_configure_underlying_depth() {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut settings = Settings::default();
+        let settings_args = SettingsArgs {
+            underlying_depth: Some(2),
+            ..Default::default()
+        };
+
+        let res = configure_settings(&mut settings, &settings_args, &mut cursor);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(settings.underlying_depth, 2);
+        let actual_settings = String::from_utf8(cursor.into_inner()).unwrap();
+        assert!(actual_settings.contains(r#""underlying_depth": 2"#));
+    }
+
+    #[test]
+    fn test_configure_max_content_size() {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut settings = Settings::default();
+        let settings_args = SettingsArgs {
+            max_content_size_bytes: Some(1_000_000),
+            oversized_content: Some(OversizedContent::Truncate),
+            ..Default::default()
+        };
+
+        let res = configure_settings(&mut settings, &settings_args, &mut cursor);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        let actual_settings = String::from_utf8(cursor.into_inner()).unwrap();
+        let expected_settings = r#"{
+    "version": 2,
+    "sources": [],
+    "ignored_urls": [],
+    "underlying_urls": [],
+    "cache_mode": "text",
+    "max_open_files": 500,
+    "max_concurrent_requests": 500,
+    "request_timeout": 60000,
+    "request_throttling": 3000,
+    "max_idle_connections_per_host": 1,
+    "idle_connections_timeout": 5000,
+    "max_content_size_bytes": 1000000,
+    "oversized_content": "truncate"
+}"#;
+        assert_eq!(actual_settings, expected_settings);
+    }
+
     #[test]
     fn test_configure_urls() {
         let mut cursor = Cursor::new(Vec::new());
@@ -210,6 +625,7 @@ mod tests {
         let actual_settings = String::from_utf8(cursor.into_inner()).unwrap();
 
         let expected_settings = r#"{
+    "version": 2,
     "sources": [],
     "ignored_urls": [
         "https://url1.com/",
@@ -248,4 +664,73 @@ mod tests {
         let res = configure_settings(&mut settings, &settings_args, &mut cursor);
         assert!(res.is_ok(), "{}", res.unwrap_err());
     }
+
+    #[test]
+    fn test_reset_settings() {
+        let default_settings = Settings::default();
+        let mut settings = Settings::default();
+        settings.sources.push(RawSource::new(
+            PathBuf::from("test_data/bookmarks_simple.txt"),
+            vec![],
+        ));
+        settings.set_request_throttling(1);
+
+        reset_settings(&mut settings, true);
+        assert!(settings.sources.is_empty());
+        assert_eq!(settings.request_throttling, 1);
+
+        reset_settings(&mut settings, false);
+        assert_eq!(settings, default_settings);
+    }
+
+    #[test]
+    fn test_confirm() {
+        assert!(confirm(Cursor::new("y\n")).unwrap());
+        assert!(confirm(Cursor::new("yes\n")).unwrap());
+        assert!(!confirm(Cursor::new("n\n")).unwrap());
+        assert!(confirm(Cursor::new("invalid\ny\n")).unwrap());
+        assert!(!confirm(Cursor::new("")).unwrap());
+    }
+
+    #[test]
+    fn test_init_cache_path_dry_run() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let cache_path = temp_path.join("external/cache");
+
+        let res = init_cache_path(&cache_path, true);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(res.unwrap().ends_with("external/cache"));
+        assert!(!temp_path.join("external").exists());
+    }
+
+    #[test]
+    fn test_migrate_cache() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let from_path = temp_path.join("cache");
+        fs::create_dir_all(&from_path).unwrap();
+        fs::write(from_path.join("1.txt"), "content_1").unwrap();
+        fs::write(from_path.join("2.html"), "content_2").unwrap();
+
+        let res = init_cache_path(&temp_path.join("external/cache"), false);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        let to_path = res.unwrap();
+        assert!(to_path.exists());
+
+        let res = migrate_cache(&from_path, &to_path);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        assert!(!from_path.join("1.txt").exists());
+        assert!(!from_path.join("2.html").exists());
+        assert_eq!(
+            fs::read_to_string(to_path.join("1.txt")).unwrap(),
+            "content_1"
+        );
+        assert_eq!(
+            fs::read_to_string(to_path.join("2.html")).unwrap(),
+            "content_2"
+        );
+        assert!(!to_path.join(".bogrep-probe").exists());
+    }
 }
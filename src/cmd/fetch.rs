@@ -1,11 +1,11 @@
 use crate::{
-    bookmarks::{BookmarkManager, BookmarkService, RunMode, ServiceConfig},
+    bookmarks::{BookmarkManager, BookmarkService, RunMode, ServiceConfig, UrlFilter},
     cache::CacheMode,
     client::ClientConfig,
-    utils, Cache, Client, Config, FetchArgs,
+    utils, Cache, Client, Config, FetchArgs, Manifest,
 };
 use chrono::Utc;
-use log::debug;
+use log::{debug, warn};
 
 /// Fetch and cache bookmarks.
 pub async fn fetch(config: &Config, args: &FetchArgs) -> Result<(), anyhow::Error> {
@@ -16,35 +16,104 @@ pub async fn fetch(config: &Config, args: &FetchArgs) -> Result<(), anyhow::Erro
     }
 
     let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
-    let cache = Cache::new(&config.cache_path, cache_mode);
-    let client_config = ClientConfig::new(&config.settings);
+    let text_extractor = args.extractor.unwrap_or(config.settings.extractor);
+    let cache = Cache::new(&config.cache_path, cache_mode)
+        .with_text_extractor(text_extractor)
+        .with_normalize_whitespace(args.normalize_whitespace)
+        .with_file_mode(config.settings.cache_file_mode);
+    let client_config = ClientConfig::new(&config.settings)
+        .with_request_timeout(args.timeout)
+        .with_include_binary(args.include_binary)
+        .with_follow_redirects(!args.no_follow_redirects);
     let client = Client::new(&client_config)?;
     let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
     let now = Utc::now();
+    let mut untracked_urls = vec![];
     let run_mode = if args.dry_run {
         RunMode::DryRun
     } else if !args.diff.is_empty() {
         let diff_urls = utils::parse_urls(&args.diff)?;
         RunMode::FetchDiff(diff_urls)
-    } else if !args.urls.is_empty() {
-        let fetch_urls = utils::parse_urls(&args.urls)?;
+    } else if !args.urls.is_empty() || args.input_file.is_some() {
+        let mut fetch_urls = utils::parse_urls(&args.urls)?;
+
+        if let Some(input_file) = &args.input_file {
+            let input_urls = utils::read_urls(input_file)?;
+
+            if !args.add {
+                // Urls which are given by `--urls` are added in any case.
+                untracked_urls = input_urls
+                    .iter()
+                    .filter(|url| !fetch_urls.contains(url))
+                    .cloned()
+                    .collect();
+            }
+
+            fetch_urls.extend(input_urls);
+        }
 
         if args.replace {
             RunMode::FetchAllUrls(fetch_urls)
         } else {
             RunMode::FetchUrls(fetch_urls)
         }
+    } else if args.only_new {
+        RunMode::FetchNew
     } else if args.replace {
         RunMode::FetchAll
     } else {
         RunMode::Fetch
     };
-    let service_config =
-        ServiceConfig::new(run_mode, &[], config.settings.max_concurrent_requests)?;
-    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
-    let bookmark_service = BookmarkService::new(service_config, client, cache);
+    let max_concurrent_requests = config.max_concurrent_requests(args.concurrency);
+    let ignored_urls = if args.only_new {
+        config.settings.ignored_urls.as_slice()
+    } else {
+        &[]
+    };
+    let service_config = ServiceConfig::new(run_mode, ignored_urls, max_concurrent_requests)?
+        .with_progress_bar(!args.quiet)
+        .with_url_filter(UrlFilter::new(&args.include, &args.exclude)?)
+        .with_slice(args.offset, args.limit)
+        .with_manifest(args.write_manifest.then(|| config.manifest_file.to_owned()))
+        .with_checkpoint(args.checkpoint_every, &config.target_bookmark_file)
+        .with_settings(&config.settings)
+        .with_resolve_underlying(!args.no_underlying)
+        .with_only_underlying(args.only_underlying)
+        .with_human_readable(args.human_readable)
+        .with_respect_robots(
+            args.respect_robots || (config.settings.respect_robots && !args.ignore_robots),
+        )
+        .with_min_text_length(args.min_text_length.or(config.settings.min_text_length))
+        .with_ignored_report(args.ignored_report)
+        .with_untracked_urls(untracked_urls)
+        .with_diff_summary(args.summary)
+        .with_cache_empty(args.continue_on_empty);
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer))
+        .with_soft_delete(config.settings.removal_grace_period.is_some())
+        .with_strip_query(&config.settings.strip_query, &config.settings.include_query);
+
+    if args.only_new {
+        // New bookmarks are imported from the configured sources.
+        bookmark_manager.add_sources(&config.settings.sources)?;
+    }
+
+    let bookmark_service =
+        BookmarkService::new(service_config, client, cache).with_strategy(args.strategy);
 
     bookmark_service.run(&mut bookmark_manager, now).await?;
 
+    if args.write_manifest && !args.dry_run && config.manifest_file.exists() {
+        let manifest = Manifest::read(&config.manifest_file)?;
+        let mismatch = manifest.verify(&config.cache_path)?;
+
+        for filename in &mismatch.missing {
+            warn!("Missing or modified cached file in manifest: {filename}");
+        }
+
+        for filename in &mismatch.extra {
+            warn!("Cached file not in manifest: {filename}");
+        }
+    }
+
     Ok(())
 }
@@ -1,12 +1,14 @@
 use crate::{
     args::ImportArgs,
-    bookmarks::{BookmarkManager, BookmarkService, RunMode, ServiceConfig},
+    bookmark_reader::SourceReader,
+    bookmarks::{BookmarkManager, BookmarkService, RawSource, RunMode, ServiceConfig},
     client::ClientConfig,
-    cmd, utils, Cache, CacheMode, Client, Config,
+    cmd, utils, Cache, CacheMode, Client, Config, Settings,
 };
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use chrono::Utc;
 use log::debug;
+use std::{fs, path::Path};
 
 /// Import bookmarks from the configured source files and store unique bookmarks
 /// in cache.
@@ -20,6 +22,14 @@ pub async fn import(config: Config, args: ImportArgs) -> Result<(), anyhow::Erro
     let mut config = config;
     let home_dir = dirs::home_dir().ok_or(anyhow!("Missing home dir"))?;
 
+    if let Some([old_source, new_source]) = args.replace_source.as_deref() {
+        replace_source(
+            &mut config.settings,
+            Path::new(old_source),
+            Path::new(new_source),
+        )?;
+    }
+
     if config.settings.sources.is_empty() {
         if let Some(source_os) = utils::get_supported_os() {
             cmd::init_sources(&mut config.settings, &home_dir, &source_os)?;
@@ -44,15 +54,75 @@ pub async fn import(config: Config, args: ImportArgs) -> Result<(), anyhow::Erro
     let service_config = ServiceConfig::new(
         run_mode,
         &config.settings.ignored_urls,
-        config.settings.max_concurrent_requests,
-    )?;
+        config.max_concurrent_requests(None),
+    )?
+    .with_ignored_report(args.ignored_report);
+
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer))
+        .with_no_remove(args.no_remove)
+        .with_progress_bar(!args.quiet)
+        .with_soft_delete(config.settings.removal_grace_period.is_some())
+        .with_strip_query(&config.settings.strip_query, &config.settings.include_query);
+    let sources = config
+        .settings
+        .sources
+        .iter()
+        .map(|source| {
+            let mut source = source.clone();
+
+            if let Some(format) = args.format {
+                source = source.with_format(Some(format));
+            }
+
+            if let Some(label) = &args.source_type {
+                source = source.with_label(Some(label.clone()));
+            }
 
-    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
-    bookmark_manager.add_sources(&config.settings.sources)?;
+            source
+        })
+        .collect::<Vec<_>>();
+    bookmark_manager.add_sources(&sources)?;
 
     let bookmark_service = BookmarkService::new(service_config, client, cache);
 
     bookmark_service.run(&mut bookmark_manager, now).await?;
 
+    // The replaced source is only persisted after a successful import.
+    if args.replace_source.is_some() && !args.dry_run {
+        utils::write_settings(&config.settings_path, &config.settings)?;
+    }
+
+    Ok(())
+}
+
+/// Replace the path of a configured source, and keep its folders and format.
+fn replace_source(
+    settings: &mut Settings,
+    old_source: &Path,
+    new_source: &Path,
+) -> Result<(), anyhow::Error> {
+    // The old source may not exist anymore.
+    let old_path = fs::canonicalize(old_source).unwrap_or(old_source.to_owned());
+    let new_path = fs::canonicalize(new_source).context("Invalid source path")?;
+    let position = settings
+        .sources
+        .iter()
+        .position(|source| source.path == old_path || source.path == old_source)
+        .ok_or(anyhow!("Source not configured: {}", old_source.display()))?;
+
+    let old = &settings.sources[position];
+    let new = RawSource::new(new_path, old.folders.clone()).with_format(old.format);
+
+    // Validate the new source before replacing the old source.
+    SourceReader::init_all(&new)?;
+
+    debug!(
+        "Replace source {} with {}",
+        old.path.display(),
+        new.path.display()
+    );
+    settings.sources.remove(position);
+    settings.set_source(new)?;
+
     Ok(())
 }
@@ -0,0 +1,40 @@
+use crate::{
+    args::IndexArgs, bookmark_reader::ReadTarget, cache::CacheMode, utils, Cache, Caching, Config,
+    Index, TargetBookmarks,
+};
+use anyhow::anyhow;
+use log::debug;
+
+/// Build an inverted index of the cached bookmarks.
+pub fn index(config: &Config, args: &IndexArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
+    let cache = Cache::new(&config.cache_path, cache_mode.clone());
+
+    let mut target_bookmarks = TargetBookmarks::default();
+    let mut target_reader = utils::open_file_in_read_mode(&config.target_bookmark_file)?;
+    target_reader.read(&mut target_bookmarks)?;
+
+    if target_bookmarks.is_empty() {
+        return Err(anyhow!("Missing bookmarks, run `bogrep import` first"));
+    }
+
+    let mut index = Index::new(cache_mode);
+
+    for bookmark in target_bookmarks.values_sorted() {
+        if let Some(content) = cache.get(bookmark)? {
+            index.insert(bookmark, &content);
+        }
+    }
+
+    index.write(&config.index_file)?;
+
+    println!(
+        "Indexed {} bookmarks with {} tokens",
+        index.len(),
+        index.tokens.len()
+    );
+
+    Ok(())
+}
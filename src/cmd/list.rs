@@ -0,0 +1,154 @@
+use crate::{
+    args::ListArgs, bookmark_reader::ReadTarget, utils, Config, TargetBookmark, TargetBookmarks,
+};
+use log::debug;
+use std::io::{self, Write};
+
+/// List the bookmarks, or the bookmarks which failed to cache.
+pub fn list(config: &Config, args: &ListArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let mut target_bookmarks = TargetBookmarks::default();
+    let mut target_reader = utils::open_file_in_read_mode(&config.target_bookmark_file)?;
+    target_reader.read(&mut target_bookmarks)?;
+
+    list_bookmarks(&target_bookmarks, args, io::stdout().lock())
+}
+
+fn list_bookmarks(
+    target_bookmarks: &TargetBookmarks,
+    args: &ListArgs,
+    mut writer: impl Write,
+) -> Result<(), anyhow::Error> {
+    let since = args
+        .since
+        .and_then(|since| since.and_hms_opt(0, 0, 0))
+        .map(|since| since.and_utc().timestamp_millis());
+    let mut bookmarks = target_bookmarks
+        .values()
+        .filter(|bookmark| !args.failed || is_failed(bookmark))
+        .filter(|bookmark| {
+            args.source.as_ref().map_or(true, |source| {
+                bookmark
+                    .sources()
+                    .iter()
+                    .any(|source_type| source_type.to_string().eq_ignore_ascii_case(source))
+            })
+        })
+        .filter(|bookmark| since.map_or(true, |since| bookmark.last_imported() >= since))
+        .collect::<Vec<_>>();
+    bookmarks.sort_by(|a, b| a.url().cmp(b.url()));
+
+    for bookmark in &bookmarks {
+        match bookmark.last_status() {
+            Some(last_status) if args.failed => {
+                writeln!(writer, "{} ({last_status})", bookmark.url())?
+            }
+            _ => writeln!(writer, "{}", bookmark.url())?,
+        }
+    }
+
+    if args.failed {
+        writeln!(writer, "Found {} failed bookmarks", bookmarks.len())?;
+    }
+
+    Ok(())
+}
+
+/// A bookmark failed to cache if it was fetched without success.
+fn is_failed(bookmark: &TargetBookmark) -> bool {
+    bookmark.last_cached().is_none()
+        && bookmark
+            .last_status()
+            .is_some_and(|last_status| last_status != "ok")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, SourceType, Subcommands};
+    use chrono::{TimeZone, Utc};
+    use clap::Parser;
+    use std::io::Cursor;
+    use url::Url;
+
+    fn list_args(args: &[&str]) -> ListArgs {
+        let args = Args::parse_from([&["bogrep", "list"], args].concat());
+        let Some(Subcommands::List(list_args)) = args.subcommands else {
+            panic!("Missing list subcommand");
+        };
+        list_args
+    }
+
+    fn create_bookmarks() -> TargetBookmarks {
+        let old = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let new = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let mut target_bookmarks = TargetBookmarks::default();
+
+        // Cached bookmark.
+        let mut bookmark = TargetBookmark::builder(Url::parse("https://url1.com").unwrap(), old)
+            .add_source(SourceType::Firefox)
+            .build();
+        bookmark.set_last_cached(new);
+        bookmark.set_last_status("ok");
+        target_bookmarks.insert(bookmark);
+
+        // Failed bookmark from an older import.
+        let mut bookmark = TargetBookmark::builder(Url::parse("https://url2.com").unwrap(), old)
+            .add_source(SourceType::Firefox)
+            .build();
+        bookmark.set_last_status("status 404");
+        target_bookmarks.insert(bookmark);
+
+        // Failed bookmark from a newer import.
+        let mut bookmark = TargetBookmark::builder(Url::parse("https://url3.com").unwrap(), new)
+            .add_source(SourceType::Chrome)
+            .build();
+        bookmark.set_last_status("timeout");
+        target_bookmarks.insert(bookmark);
+
+        // Bookmark which wasn't fetched yet.
+        let bookmark = TargetBookmark::builder(Url::parse("https://url4.com").unwrap(), new)
+            .add_source(SourceType::Chrome)
+            .build();
+        target_bookmarks.insert(bookmark);
+
+        target_bookmarks
+    }
+
+    fn list_output(target_bookmarks: &TargetBookmarks, args: &[&str]) -> String {
+        let mut cursor = Cursor::new(Vec::new());
+        let res = list_bookmarks(target_bookmarks, &list_args(args), &mut cursor);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        String::from_utf8(cursor.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_list_bookmarks() {
+        let target_bookmarks = create_bookmarks();
+
+        assert_eq!(
+            list_output(&target_bookmarks, &[]),
+            "https://url1.com/\nhttps://url2.com/\nhttps://url3.com/\nhttps://url4.com/\n"
+        );
+        assert_eq!(
+            list_output(&target_bookmarks, &["--failed"]),
+            "https://url2.com/ (status 404)\nhttps://url3.com/ (timeout)\nFound 2 failed bookmarks\n"
+        );
+        assert_eq!(
+            list_output(&target_bookmarks, &["--failed", "--source", "firefox"]),
+            "https://url2.com/ (status 404)\nFound 1 failed bookmarks\n"
+        );
+        assert_eq!(
+            list_output(&target_bookmarks, &["--failed", "--since", "2024-03-01"]),
+            "https://url3.com/ (timeout)\nFound 1 failed bookmarks\n"
+        );
+        assert_eq!(
+            list_output(
+                &target_bookmarks,
+                &["--source", "chrome", "--since", "2024-06-01"]
+            ),
+            "https://url3.com/\nhttps://url4.com/\n"
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    args::MergeArgs,
+    bookmark_reader::{ReadTarget, ReadWriteTarget},
+    utils, Cache, CacheMode, Config, TargetBookmarks,
+};
+use log::debug;
+
+/// Merge the bookmarks from another `bookmarks.json`, e.g. from another
+/// machine.
+///
+/// The cache state of a merged bookmark is only kept if its cached file exists
+/// locally.
+pub fn merge(config: &Config, args: &MergeArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let mut other_bookmarks = TargetBookmarks::default();
+    let mut other_reader = utils::open_file_in_read_mode(&args.path)?;
+    other_reader.read(&mut other_bookmarks)?;
+
+    let mut target_bookmarks = TargetBookmarks::default();
+    let mut target_reader_writer =
+        utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    target_reader_writer.read_target(&mut target_bookmarks)?;
+
+    let cache_mode = CacheMode::new(&None, &config.settings.cache_mode);
+    let cache = Cache::new(&config.cache_path, cache_mode);
+    let (added, updated) =
+        target_bookmarks.merge(other_bookmarks, |bookmark| cache.has_file(bookmark));
+
+    target_reader_writer.write_target(&target_bookmarks)?;
+
+    println!("Merged bookmarks: {added} added, {updated} updated");
+
+    Ok(())
+}
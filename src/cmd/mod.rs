@@ -1,19 +1,37 @@
 mod add;
+mod backup;
+mod cat;
 mod clean;
 mod configure;
 mod fetch;
 mod import;
+mod index;
 mod init;
+mod list;
+mod merge;
+mod prune;
+mod reindex;
 mod remove;
 mod search;
 mod sync;
+mod verify;
+mod watch;
 
 pub use add::add;
+pub use backup::{backup, restore};
+pub use cat::{cat, CatMode};
 pub use clean::clean;
 pub use configure::configure;
 pub use fetch::fetch;
 pub use import::import;
+pub use index::index;
 pub use init::{init, init_sources};
+pub use list::list;
+pub use merge::merge;
+pub use prune::prune;
+pub use reindex::reindex;
 pub use remove::remove;
-pub use search::search;
+pub use search::{search, Granularity};
 pub use sync::sync;
+pub use verify::verify;
+pub use watch::watch;
@@ -0,0 +1,144 @@
+use crate::{
+    args::PruneArgs, bookmark_reader::ReadWriteTarget, utils, Cache, Caching, Config,
+    TargetBookmarks,
+};
+use chrono::{DateTime, Utc};
+use log::debug;
+use url::Url;
+
+/// Remove bookmarks which weren't imported for a given duration, together
+/// with their cached files.
+pub async fn prune(config: &Config, args: &PruneArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    if args.dry_run {
+        println!("Running in dry mode ...")
+    }
+
+    let now = Utc::now();
+    let imported_before = now - chrono::Duration::from_std(args.older_than)?;
+    let cached_before = args
+        .cached_older_than
+        .map(|cached_older_than| chrono::Duration::from_std(cached_older_than).map(|d| now - d))
+        .transpose()?;
+
+    let mut target_bookmarks = TargetBookmarks::default();
+    let mut target_reader_writer =
+        utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    target_reader_writer.read_target(&mut target_bookmarks)?;
+
+    let stale_urls = stale_bookmarks(&target_bookmarks, imported_before, cached_before);
+
+    if args.dry_run {
+        for url in &stale_urls {
+            println!("{url}");
+        }
+
+        println!("Pruned {} bookmarks (dry run)", stale_urls.len());
+        return Ok(());
+    }
+
+    let cache = Cache::new(&config.cache_path, config.settings.cache_mode.clone());
+
+    for url in &stale_urls {
+        if let Some(mut bookmark) = target_bookmarks.remove(url) {
+            debug!("Prune bookmark: {url}");
+            cache.remove_by_modes(&mut bookmark).await?;
+        }
+    }
+
+    target_reader_writer.write_target(&target_bookmarks)?;
+
+    println!("Pruned {} bookmarks", stale_urls.len());
+
+    Ok(())
+}
+
+/// The urls of the bookmarks which were last imported before the given time,
+/// and, if given, were last cached before the given time or never cached.
+fn stale_bookmarks(
+    target_bookmarks: &TargetBookmarks,
+    imported_before: DateTime<Utc>,
+    cached_before: Option<DateTime<Utc>>,
+) -> Vec<Url> {
+    let imported_before = imported_before.timestamp_millis();
+    let cached_before = cached_before.map(|cached_before| cached_before.timestamp_millis());
+
+    target_bookmarks
+        .values_sorted()
+        .into_iter()
+        .filter(|bookmark| bookmark.last_imported() < imported_before)
+        .filter(|bookmark| {
+            cached_before.map_or(true, |cached_before| {
+                bookmark
+                    .last_cached()
+                    .map_or(true, |last_cached| last_cached < cached_before)
+            })
+        })
+        .map(|bookmark| bookmark.url().to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TargetBookmark;
+    use chrono::Duration;
+
+    #[test]
+    fn test_stale_bookmarks() {
+        let now = Utc::now();
+        let mut target_bookmarks = TargetBookmarks::default();
+        let bookmarks = [
+            // Recently imported.
+            ("https://url1.com", now - Duration::days(1), None),
+            // Imported long ago, and never cached.
+            ("https://url2.com", now - Duration::days(100), None),
+            // Imported long ago, and cached long ago.
+            (
+                "https://url3.com",
+                now - Duration::days(100),
+                Some(now - Duration::days(100)),
+            ),
+            // Imported long ago, but cached recently.
+            (
+                "https://url4.com",
+                now - Duration::days(100),
+                Some(now - Duration::days(1)),
+            ),
+        ];
+
+        for (url, last_imported, last_cached) in bookmarks {
+            let mut bookmark = TargetBookmark::new(Url::parse(url).unwrap(), last_imported);
+
+            if let Some(last_cached) = last_cached {
+                bookmark.set_last_cached(last_cached);
+            }
+
+            target_bookmarks.insert(bookmark);
+        }
+
+        let urls = |urls: &[&str]| {
+            urls.iter()
+                .map(|url| Url::parse(url).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            stale_bookmarks(&target_bookmarks, now - Duration::days(30), None),
+            urls(&["https://url2.com", "https://url3.com", "https://url4.com"])
+        );
+        assert_eq!(
+            stale_bookmarks(
+                &target_bookmarks,
+                now - Duration::days(30),
+                Some(now - Duration::days(30))
+            ),
+            urls(&["https://url2.com", "https://url3.com"])
+        );
+        assert_eq!(
+            stale_bookmarks(&target_bookmarks, now - Duration::days(365), None),
+            urls(&[])
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    args::ReindexArgs,
+    bookmarks::{BookmarkManager, BookmarkService, RunMode, ServiceConfig},
+    client::ClientConfig,
+    errors::BogrepError,
+    utils, Cache, CacheMode, Caching, Client, Config, TargetBookmarks,
+};
+use chrono::Utc;
+use log::debug;
+use url::Url;
+
+/// Rebuild the cache in the configured cache mode.
+///
+/// Cached HTML is converted to text without fetching the website again.
+/// Bookmarks whose cached content can't be converted are refetched.
+pub async fn reindex(config: &Config, args: &ReindexArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    if args.dry_run {
+        println!("Running in dry mode ...")
+    }
+
+    let now = Utc::now();
+    let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
+    let text_extractor = args.extractor.unwrap_or(config.settings.extractor);
+    let cache = Cache::new(&config.cache_path, cache_mode)
+        .with_text_extractor(text_extractor)
+        .with_normalize_whitespace(args.normalize_whitespace)
+        .with_file_mode(config.settings.cache_file_mode);
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+    bookmark_manager.import(now)?;
+
+    let (converted, refetch_urls) = convert_bookmarks(
+        &cache,
+        bookmark_manager.target_bookmarks_mut(),
+        args.dry_run,
+    )
+    .await?;
+
+    if !args.dry_run {
+        bookmark_manager.export()?;
+    }
+
+    println!(
+        "Converted {converted} bookmarks to {}, {} bookmarks need to be refetched",
+        cache.mode(),
+        refetch_urls.len()
+    );
+
+    if !refetch_urls.is_empty() && !args.dry_run {
+        let client_config = ClientConfig::new(&config.settings);
+        let client = Client::new(&client_config)?;
+        let target_reader_writer =
+            utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+        let service_config = ServiceConfig::new(
+            RunMode::FetchAllUrls(refetch_urls),
+            &[],
+            config.max_concurrent_requests(None),
+        )?
+        .with_settings(&config.settings);
+        let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+        let bookmark_service = BookmarkService::new(service_config, client, cache);
+
+        bookmark_service.run(&mut bookmark_manager, now).await?;
+    }
+
+    Ok(())
+}
+
+/// Convert the cached websites to the cache mode of the given cache.
+///
+/// Returns the number of converted bookmarks, and the urls of the cached
+/// bookmarks which have to be refetched.
+async fn convert_bookmarks(
+    cache: &Cache,
+    bookmarks: &mut TargetBookmarks,
+    dry_run: bool,
+) -> Result<(usize, Vec<Url>), BogrepError> {
+    let cache_mode = cache.mode().clone();
+    let mut converted = 0;
+    let mut refetch_urls = vec![];
+
+    for bookmark in bookmarks.values_mut() {
+        let cache_path = cache.bookmark_path_by_cache_mode(bookmark.cache_name(), &cache_mode)?;
+
+        if bookmark.cache_modes().is_empty()
+            || (bookmark.cache_modes().contains(&cache_mode) && cache_path.exists())
+        {
+            continue;
+        }
+
+        let html_path =
+            cache.bookmark_path_by_cache_mode(bookmark.cache_name(), &CacheMode::Html)?;
+
+        if cache_mode == CacheMode::Text && html_path.exists() {
+            debug!("Convert {} to {cache_mode}", html_path.display());
+
+            if !dry_run {
+                let html = utils::read_file_to_string(&html_path)?;
+                cache.replace(html, bookmark).await?;
+            }
+
+            converted += 1;
+        } else {
+            // The cached text doesn't contain enough information to rebuild
+            // the HTML.
+            refetch_urls.push(bookmark.url().to_owned());
+        }
+    }
+
+    refetch_urls.sort();
+
+    Ok((converted, refetch_urls))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TargetBookmark;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_convert_bookmarks_html_to_text() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let html = "<html><head><title>title_content</title></head><body><p>paragraph_content with enough readable content</p></body></html>";
+
+        let mut bookmarks = TargetBookmarks::default();
+        let bookmark1 = TargetBookmark::builder(url1.clone(), now)
+            .add_cache_mode(CacheMode::Html)
+            .build();
+        fs::write(temp_path.join(format!("{}.html", bookmark1.id())), html).unwrap();
+        // Bookmark 2 is not cached yet.
+        let bookmark2 = TargetBookmark::new(url2.clone(), now);
+        let bookmark3 = TargetBookmark::builder(url3.clone(), now)
+            .add_cache_mode(CacheMode::Text)
+            .build();
+        fs::write(
+            temp_path.join(format!("{}.txt", bookmark3.id())),
+            "text_content",
+        )
+        .unwrap();
+        bookmarks.insert(bookmark1);
+        bookmarks.insert(bookmark2);
+        bookmarks.insert(bookmark3);
+
+        let cache = Cache::new(temp_path, CacheMode::Text);
+        let res = convert_bookmarks(&cache, &mut bookmarks, false).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), (1, vec![]));
+
+        let bookmark1 = bookmarks.get(&url1).unwrap();
+        assert!(bookmark1.cache_modes().contains(&CacheMode::Html));
+        assert!(bookmark1.cache_modes().contains(&CacheMode::Text));
+        assert_eq!(
+            fs::read_to_string(temp_path.join(format!("{}.txt", bookmark1.id()))).unwrap(),
+            "paragraph_content with enough readable content"
+        );
+        assert!(bookmarks.get(&url2).unwrap().cache_modes().is_empty());
+
+        let cache = Cache::new(temp_path, CacheMode::Html);
+        let res = convert_bookmarks(&cache, &mut bookmarks, false).await;
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), (0, vec![url3]));
+    }
+}
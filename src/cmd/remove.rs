@@ -22,7 +22,7 @@ pub async fn remove(config: Config, args: RemoveArgs) -> Result<(), anyhow::Erro
     let service_config = ServiceConfig::new(
         RunMode::RemoveUrls(urls.clone()),
         &[],
-        config.settings.max_concurrent_requests,
+        config.max_concurrent_requests(None),
     )?;
     let client_config = ClientConfig::new(&config.settings);
     let cache_mode = CacheMode::new(&None, &config.settings.cache_mode);
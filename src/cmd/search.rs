@@ -1,21 +1,347 @@
 use crate::{
-    bookmark_reader::ReadTarget, cache::CacheMode, utils, Args, Cache, Caching, Config,
-    TargetBookmarks,
+    bookmark_reader::ReadTarget, cache::CacheMode, utils, Args, Cache, Caching, Config, Index,
+    TargetBookmark, TargetBookmarks,
 };
 use anyhow::anyhow;
+use clap::ValueEnum;
 use colored::Colorize;
 use log::debug;
 use regex::{Captures, Regex};
-use std::{borrow::Cow, io};
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    io::{self, Write},
+    iter, mem,
+    time::Instant,
+};
 
 /// Maximum number of characters per line displayed in the search result.
 const MAX_COLUMNS: usize = 1000;
 
-pub fn search(pattern: &str, config: &Config, args: &Args) -> Result<(), anyhow::Error> {
-    debug!("{:?}", pattern);
+/// The lines and patterns matched in a cached website.
+#[derive(Debug, Default, PartialEq)]
+struct Matches {
+    /// The matched lines.
+    lines: Vec<String>,
+    /// The 1-based line numbers of the matched lines.
+    line_numbers: Vec<usize>,
+    /// The indices of the matched patterns.
+    patterns: BTreeSet<usize>,
+}
+
+/// The segments of the cached content which are matched and printed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Granularity {
+    /// Match lines.
+    #[default]
+    Line,
+    /// Match paragraphs, which are separated by empty lines.
+    Paragraph,
+    /// Match sentences, which end with `.`, `?`, or `!`.
+    Sentence,
+}
+
+/// A paragraph of the cached content, whose lines are joined by a whitespace.
+#[derive(Debug, Default, PartialEq)]
+struct Paragraph {
+    text: String,
+    /// The byte offsets in the text and the 1-based line numbers of the
+    /// joined lines.
+    line_offsets: Vec<(usize, usize)>,
+}
+
+impl Paragraph {
+    /// The line number of the line containing the byte offset.
+    fn line_number(&self, offset: usize) -> usize {
+        self.line_offsets
+            .iter()
+            .take_while(|(line_offset, _)| *line_offset <= offset)
+            .last()
+            .map(|(_, line_number)| *line_number)
+            .unwrap_or(1)
+    }
+
+    /// Split the paragraph into sentences with the line number where each
+    /// sentence starts.
+    fn sentences(&self) -> Vec<(usize, String)> {
+        split_sentences(&self.text)
+            .into_iter()
+            .map(|(offset, sentence)| (self.line_number(offset), sentence.to_owned()))
+            .collect()
+    }
+}
+
+/// An iterator over the paragraphs of lines.
+struct Paragraphs<I> {
+    lines: iter::Enumerate<I>,
+}
+
+impl<I> Iterator for Paragraphs<I>
+where
+    I: Iterator<Item = Result<String, io::Error>>,
+{
+    type Item = Result<Paragraph, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut paragraph: Option<Paragraph> = None;
+
+        for (index, line) in self.lines.by_ref() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err)),
+            };
+            let line = line.trim();
+
+            if line.is_empty() {
+                if paragraph.is_some() {
+                    break;
+                } else {
+                    continue;
+                }
+            }
+
+            let paragraph = paragraph.get_or_insert_with(Paragraph::default);
+
+            if !paragraph.text.is_empty() {
+                paragraph.text.push(' ');
+            }
+
+            paragraph
+                .line_offsets
+                .push((paragraph.text.len(), index + 1));
+            paragraph.text.push_str(line);
+        }
+
+        paragraph.map(Ok)
+    }
+}
+
+/// Split a text into sentences with their byte offsets in the text.
+///
+/// A sentence ends with `.`, `?`, or `!`, optionally followed by closing
+/// quotes or brackets, which are followed by a whitespace.
+fn split_sentences(text: &str) -> Vec<(usize, &str)> {
+    let mut sentences = vec![];
+    let mut push_sentence = |start: usize, end: usize| {
+        let sentence = &text[start..end];
+        let trimmed = sentence.trim_start();
+        let offset = start + sentence.len() - trimmed.len();
+        let trimmed = trimmed.trim_end();
+
+        if !trimmed.is_empty() {
+            sentences.push((offset, trimmed));
+        }
+    };
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if !matches!(c, '.' | '?' | '!') {
+            continue;
+        }
+
+        let mut end = index + c.len_utf8();
+
+        while let Some(&(index, c)) = chars.peek() {
+            if matches!(c, '.' | '?' | '!' | '"' | '\'' | ')' | ']' | '”' | '’') {
+                end = index + c.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if chars.peek().is_none_or(|(_, c)| c.is_whitespace()) {
+            push_sentence(start, end);
+            start = end;
+        }
+    }
+
+    push_sentence(start, text.len());
+    sentences
+}
+
+/// Split the content into segments of the given granularity, with the 1-based
+/// line number where each segment starts.
+fn segments<'a>(
+    reader: impl io::BufRead + 'a,
+    granularity: Granularity,
+) -> Box<dyn Iterator<Item = Result<(usize, String), io::Error>> + 'a> {
+    let lines = reader.lines();
+
+    match granularity {
+        Granularity::Line => Box::new(
+            lines
+                .enumerate()
+                .map(|(index, line)| line.map(|line| (index + 1, line))),
+        ),
+        Granularity::Paragraph => Box::new(
+            Paragraphs {
+                lines: lines.enumerate(),
+            }
+            .map(|paragraph| paragraph.map(|paragraph| (paragraph.line_number(0), paragraph.text))),
+        ),
+        Granularity::Sentence => Box::new(
+            Paragraphs {
+                lines: lines.enumerate(),
+            }
+            .flat_map(|paragraph| match paragraph {
+                Ok(paragraph) => paragraph
+                    .sentences()
+                    .into_iter()
+                    .map(Ok)
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            }),
+        ),
+    }
+}
+
+/// Statistics about a search.
+#[derive(Debug, Default, PartialEq)]
+struct SearchStats {
+    /// The number of searched bookmarks with cached websites, or of all
+    /// bookmarks if urls are searched.
+    searched: usize,
+    /// The number of bookmarks with matches.
+    matches: usize,
+    /// The total number of matched lines.
+    matched_lines: usize,
+}
+
+/// A placeholder in a template for search results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Url,
+    Title,
+    Line,
+    LineNumber,
+}
+
+impl Placeholder {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "url" => Some(Self::Url),
+            "title" => Some(Self::Title),
+            "line" => Some(Self::Line),
+            "line_number" => Some(Self::LineNumber),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// A template to format search results, e.g. `{url}: {line}`.
+///
+/// A template containing `{line}` or `{line_number}` is rendered for each
+/// matched line, otherwise it is rendered once for each matched bookmark.
+#[derive(Debug, PartialEq)]
+struct Template(Vec<Segment>);
+
+impl Template {
+    fn parse(template: &str) -> Result<Self, anyhow::Error> {
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut name = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(anyhow!(
+                                    "Invalid template: unclosed placeholder `{{{name}`"
+                                ))
+                            }
+                        }
+                    }
+
+                    let placeholder = Placeholder::parse(&name).ok_or(anyhow!(
+                        "Invalid template: unknown placeholder `{{{name}}}`, expected one of \
+                        `{{url}}`, `{{title}}`, `{{line}}`, `{{line_number}}`"
+                    ))?;
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(mem::take(&mut literal)));
+                    }
+
+                    segments.push(Segment::Placeholder(placeholder));
+                }
+                '\\' => match chars.next() {
+                    Some('n') => literal.push('\n'),
+                    Some('t') => literal.push('\t'),
+                    Some(c) => {
+                        literal.push('\\');
+                        literal.push(c);
+                    }
+                    None => literal.push('\\'),
+                },
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self(segments))
+    }
+
+    fn is_per_line(&self) -> bool {
+        self.0.iter().any(|segment| {
+            matches!(
+                segment,
+                Segment::Placeholder(Placeholder::Line | Placeholder::LineNumber)
+            )
+        })
+    }
+
+    /// Render the template for a bookmark, and optionally a matched line with
+    /// its line number.
+    fn render(&self, bookmark: &TargetBookmark, line: Option<(&str, usize)>) -> String {
+        let mut rendered = String::new();
+
+        for segment in &self.0 {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Placeholder(Placeholder::Url) => {
+                    rendered.push_str(bookmark.url().as_str())
+                }
+                Segment::Placeholder(Placeholder::Title) => {
+                    rendered.push_str(bookmark.title.as_deref().unwrap_or_default())
+                }
+                Segment::Placeholder(Placeholder::Line) => {
+                    rendered.push_str(line.map(|(line, _)| line).unwrap_or_default())
+                }
+                Segment::Placeholder(Placeholder::LineNumber) => {
+                    if let Some((_, line_number)) = line {
+                        rendered.push_str(&line_number.to_string());
+                    }
+                }
+            }
+        }
+
+        rendered
+    }
+}
+
+pub fn search(patterns: &[String], config: &Config, args: &Args) -> Result<(), anyhow::Error> {
+    debug!("{:?}", patterns);
+
+    let start = Instant::now();
 
     let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
-    let cache = Cache::new(&config.cache_path, cache_mode);
+    let cache = Cache::new(&config.cache_path, cache_mode.clone());
 
     let mut target_bookmarks = TargetBookmarks::default();
     let mut target_reader = utils::open_file_in_read_mode(&config.target_bookmark_file)?;
@@ -23,95 +349,378 @@ pub fn search(pattern: &str, config: &Config, args: &Args) -> Result<(), anyhow:
 
     if target_bookmarks.is_empty() {
         Err(anyhow!("Missing bookmarks, run `bogrep import` first"))
+    } else if args.exclude_cached {
+        let uncached = list_uncached(
+            &target_bookmarks.values_sorted(),
+            &cache,
+            io::stdout().lock(),
+        )?;
+        println!("Found {uncached} bookmarks without cached content");
+        Ok(())
+    } else if args.url {
+        // The urls are searched without reading the cache.
+        let stats = search_urls(patterns, &target_bookmarks.values_sorted(), args)?;
+
+        if stats.matches == 0 {
+            println!("No matches in bookmarks");
+        } else {
+            println!("Found matches in {} bookmarks", stats.matches);
+        }
+
+        if args.stats {
+            eprintln!(
+                "Searched {} bookmarks, found matches in {} bookmarks in {:.2?}",
+                stats.searched,
+                stats.matches,
+                start.elapsed()
+            );
+        }
+
+        Ok(())
     } else {
-        let matches = search_bookmarks(pattern, &target_bookmarks, &cache, args)?;
+        let index = if config.index_file.exists() {
+            Some(Index::read(&config.index_file)?)
+        } else {
+            None
+        };
+        let bookmarks = select_bookmarks(
+            patterns,
+            &target_bookmarks,
+            index
+                .as_ref()
+                .filter(|index| index.cache_mode == cache_mode),
+            args,
+        );
+        let stats = search_bookmarks(patterns, &bookmarks, &cache, args)?;
 
-        if matches == 0 {
+        if stats.matches == 0 {
             println!("No matches in bookmarks");
         } else {
-            println!("Found matches in {matches} bookmarks");
+            println!("Found matches in {} bookmarks", stats.matches);
+        }
+
+        if args.stats {
+            // Print to stderr to keep the piped search results clean.
+            eprintln!(
+                "Searched {} of {} bookmarks, found matches in {} bookmarks ({} matched lines) in {:.2?}",
+                stats.searched,
+                target_bookmarks.len(),
+                stats.matches,
+                stats.matched_lines,
+                start.elapsed()
+            );
         }
 
         Ok(())
     }
 }
 
-#[allow(clippy::comparison_chain)]
-fn search_bookmarks(
-    pattern: &str,
-    bookmarks: &TargetBookmarks,
+/// List the bookmarks whose cached content is missing or empty, regardless
+/// of whether the bookmarks are marked as cached.
+fn list_uncached(
+    bookmarks: &[&TargetBookmark],
     cache: &impl Caching,
-    args: &Args,
-) -> Result<i64, anyhow::Error> {
-    let mut matches = 0;
-    let mut re = pattern.to_owned();
+    mut writer: impl Write,
+) -> Result<usize, anyhow::Error> {
+    let mut uncached = 0;
 
-    if args.word_regexp {
-        re = format!(r"\b{}\b", regex::escape(&re));
-    }
+    for bookmark in bookmarks {
+        let content = cache.get(bookmark)?;
 
-    if args.ignore_case {
-        re = format!("(?i){re}")
+        if content.is_none_or(|content| content.trim().is_empty()) {
+            uncached += 1;
+            writeln!(writer, "{}", bookmark.url())?;
+        }
     }
 
-    let regex = Regex::new(&re)?;
+    Ok(uncached)
+}
+
+/// Select the bookmarks to be searched.
+///
+/// For literal patterns, the bookmarks are preselected by the index if
+/// available. Regex patterns are searched in all bookmarks.
+fn select_bookmarks<'a>(
+    patterns: &[String],
+    bookmarks: &'a TargetBookmarks,
+    index: Option<&Index>,
+    args: &Args,
+) -> Vec<&'a TargetBookmark> {
+    let index = match index {
+        Some(index) if patterns.iter().all(|pattern| is_literal(pattern)) => index,
+        _ => return bookmarks.values_sorted(),
+    };
+    debug!("Preselect bookmarks by index");
+
+    let candidates = patterns
+        .iter()
+        .map(|pattern| {
+            index
+                .candidates(bookmarks, pattern)
+                .into_iter()
+                .map(|bookmark| bookmark.id())
+                .collect::<BTreeSet<_>>()
+        })
+        .reduce(|a, b| {
+            if args.and {
+                a.intersection(&b).copied().collect()
+            } else {
+                a.union(&b).copied().collect()
+            }
+        })
+        .unwrap_or_default();
+
+    bookmarks
+        .values_sorted()
+        .into_iter()
+        .filter(|bookmark| candidates.contains(bookmark.id()))
+        .collect()
+}
+
+/// Check if the pattern doesn't contain any regex syntax.
+fn is_literal(pattern: &str) -> bool {
+    !pattern.contains(|c| {
+        matches!(
+            c,
+            '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$'
+        )
+    })
+}
+
+#[allow(clippy::comparison_chain)]
+fn search_bookmarks(
+    patterns: &[String],
+    bookmarks: &[&TargetBookmark],
+    cache: &impl Caching,
+    args: &Args,
+) -> Result<SearchStats, anyhow::Error> {
+    let mut stats = SearchStats::default();
+    let regexes = patterns
+        .iter()
+        .map(|pattern| build_regex(pattern, args))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Combine the patterns to highlight all matches in a line.
+    let regex = Regex::new(
+        &regexes
+            .iter()
+            .map(|regex| format!("(?:{})", regex.as_str()))
+            .collect::<Vec<_>>()
+            .join("|"),
+    )?;
     debug!("Use regex: {regex}");
+    let template = args.template.as_deref().map(Template::parse).transpose()?;
 
-    for bookmark in bookmarks.values() {
+    for bookmark in bookmarks {
         if let Some(cache_file) = cache.open(bookmark)? {
             let reader = io::BufReader::new(cache_file);
-            let matched_lines = find_matches(reader, &regex)?;
+            let bookmark_matches = find_matches(reader, &regexes, &regex, args.granularity)?;
+            stats.searched += 1;
+
+            let is_match = if args.and {
+                bookmark_matches.patterns.len() == regexes.len()
+            } else {
+                !bookmark_matches.patterns.is_empty()
+            };
+
+            if !is_match {
+                continue;
+            }
+
+            let matched_lines = bookmark_matches.lines;
+            stats.matched_lines += matched_lines.len();
+
+            if let Some(template) = &template {
+                stats.matches += 1;
+
+                if template.is_per_line() {
+                    for (matched_line, line_number) in
+                        matched_lines.iter().zip(&bookmark_matches.line_numbers)
+                    {
+                        let matched_line = color_matches(matched_line, &regex);
+                        println!(
+                            "{}",
+                            template.render(bookmark, Some((&matched_line, *line_number)))
+                        );
+                    }
+                } else {
+                    println!("{}", template.render(bookmark, None));
+                }
+
+                continue;
+            }
 
             if matched_lines.len() == 1 {
-                matches += 1;
+                stats.matches += 1;
                 println!("Match in bookmark: {}", bookmark.url().to_string().blue());
             } else if matched_lines.len() > 1 {
-                matches += 1;
+                stats.matches += 1;
                 println!("Matches in bookmark: {}", bookmark.url().to_string().blue());
             }
 
+            if patterns.len() > 1 {
+                let matched_patterns = bookmark_matches
+                    .patterns
+                    .iter()
+                    .map(|index| patterns[*index].as_str())
+                    .collect::<Vec<_>>();
+                println!("Matched patterns: {}", matched_patterns.join(", "));
+            }
+
             if !args.files_with_matches {
-                for matched_line in &matched_lines {
-                    println!("{}", color_matches(matched_line, &regex));
+                for (matched_line, line_number) in
+                    matched_lines.iter().zip(&bookmark_matches.line_numbers)
+                {
+                    if args.line_number {
+                        println!(
+                            "{}:{}",
+                            line_number.to_string().green(),
+                            color_matches(matched_line, &regex)
+                        );
+                    } else {
+                        println!("{}", color_matches(matched_line, &regex));
+                    }
                 }
             }
         }
     }
 
-    Ok(matches)
+    Ok(stats)
+}
+
+/// Search the urls of the bookmarks, and print the matched urls.
+fn search_urls(
+    patterns: &[String],
+    bookmarks: &[&TargetBookmark],
+    args: &Args,
+) -> Result<SearchStats, anyhow::Error> {
+    let mut stats = SearchStats::default();
+    let regexes = patterns
+        .iter()
+        .map(|pattern| build_regex(pattern, args))
+        .collect::<Result<Vec<_>, _>>()?;
+    let regex = Regex::new(
+        &regexes
+            .iter()
+            .map(|regex| format!("(?:{})", regex.as_str()))
+            .collect::<Vec<_>>()
+            .join("|"),
+    )?;
+    let template = args.template.as_deref().map(Template::parse).transpose()?;
+
+    for bookmark in bookmarks {
+        stats.searched += 1;
+        let url = bookmark.url().as_str();
+        let mut matched_patterns = regexes.iter().filter(|regex| regex.is_match(url));
+
+        let is_match = if args.and {
+            matched_patterns.count() == regexes.len()
+        } else {
+            matched_patterns.next().is_some()
+        };
+
+        if !is_match {
+            continue;
+        }
+
+        stats.matches += 1;
+        stats.matched_lines += 1;
+
+        if let Some(template) = &template {
+            let matched_url = color_matches(url, &regex);
+            println!("{}", template.render(bookmark, Some((&matched_url, 1))));
+        } else {
+            println!("{}", color_matches(url, &regex));
+        }
+    }
+
+    Ok(stats)
 }
 
-/// Find the matched lines for the regex in a file.
-fn find_matches(reader: impl io::BufRead, regex: &Regex) -> Result<Vec<String>, anyhow::Error> {
-    let mut matched_lines = vec![];
+fn build_regex(pattern: &str, args: &Args) -> Result<Regex, regex::Error> {
+    let mut re = pattern.to_owned();
+
+    if args.word_regexp {
+        re = format!(r"\b{}\b", regex::escape(&re));
+    }
 
-    for line in reader.lines() {
+    if args.ignore_case {
+        re = format!("(?i){re}")
+    }
+
+    Regex::new(&re)
+}
+
+/// Find the matched lines and patterns in a file. The file is segmented into
+/// lines, paragraphs, or sentences depending on the granularity.
+///
+/// The `regex` combines all `regexes` and is used to truncate long lines
+/// around the first match.
+fn find_matches(
+    reader: impl io::BufRead,
+    regexes: &[Regex],
+    regex: &Regex,
+    granularity: Granularity,
+) -> Result<Matches, anyhow::Error> {
+    let mut matches = Matches::default();
+
+    for segment in segments(reader, granularity) {
         let start_index;
         let end_index;
-        let line = line?;
+        let (line_number, line) = segment?;
+        let mut is_match = false;
+
+        for (pattern_index, regex) in regexes.iter().enumerate() {
+            if regex.is_match(&line) {
+                matches.patterns.insert(pattern_index);
+                is_match = true;
+            }
+        }
+
+        if is_match {
+            matches.line_numbers.push(line_number);
 
-        if regex.is_match(&line) {
             if line.len() >= MAX_COLUMNS {
                 let first_match = regex.find(&line).ok_or(anyhow!("Can't find first match"))?;
                 let match_start = first_match.start();
                 let match_end = first_match.end();
                 let half_max = MAX_COLUMNS / 2;
-                start_index = match_start.saturating_sub(half_max);
-                end_index = (match_end + half_max).min(line.len());
+                // Truncate at char boundaries for multibyte characters like
+                // non-breaking spaces.
+                start_index = floor_char_boundary(&line, match_start.saturating_sub(half_max));
+                end_index = ceil_char_boundary(&line, match_end + half_max);
             } else {
                 start_index = 0;
                 end_index = line.len();
             }
 
-            if let Some(truncated_line) = line.get(start_index..end_index) {
-                matched_lines.push(truncated_line.to_owned());
-            } else {
-                matched_lines.push(line.to_owned());
-            }
+            matches.lines.push(line[start_index..end_index].to_owned());
         }
     }
 
-    Ok(matched_lines)
+    Ok(matches)
+}
+
+/// The largest char boundary in the string which is not greater than the
+/// index.
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+/// The smallest char boundary in the string which is not less than the index.
+fn ceil_char_boundary(s: &str, index: usize) -> usize {
+    let mut index = index.min(s.len());
+
+    while !s.is_char_boundary(index) {
+        index += 1;
+    }
+
+    index
 }
 
 /// Display search pattern in bold red.
@@ -124,7 +733,12 @@ fn color_matches<'a>(matched_line: &'a str, regex: &Regex) -> Cow<'a, str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Cursor;
+    use crate::TargetBookmark;
+    use chrono::Utc;
+    use clap::Parser;
+    use std::{fs, io::Cursor};
+    use tempfile::tempdir;
+    use url::Url;
 
     #[tokio::test]
     async fn test_find_matches_regex_case_insensitive() {
@@ -133,10 +747,10 @@ mod tests {
         let re = "(?i)reed-solomon code".to_string();
         let regex = Regex::new(&re).unwrap();
 
-        let res = find_matches(cursor, &regex);
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
         assert!(res.is_ok());
 
-        let matched_lines = res.unwrap();
+        let matched_lines = res.unwrap().lines;
         assert_eq!(matched_lines, vec!["line 4 Reed-Solomon code"]);
     }
 
@@ -147,10 +761,10 @@ mod tests {
         let re = "Reed-Solomon code".to_string();
         let regex = Regex::new(&re).unwrap();
 
-        let res = find_matches(cursor, &regex);
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
         assert!(res.is_ok());
 
-        let matched_lines = res.unwrap();
+        let matched_lines = res.unwrap().lines;
         assert_eq!(matched_lines, vec!["line 3 Reed-Solomon code"]);
     }
 
@@ -161,10 +775,10 @@ mod tests {
         let re = r"(?i)\breed-solomon\b".to_string();
         let regex = Regex::new(&re).unwrap();
 
-        let res = find_matches(cursor, &regex);
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
         assert!(res.is_ok());
 
-        let matched_lines = res.unwrap();
+        let matched_lines = res.unwrap().lines;
         assert_eq!(matched_lines, vec!["line 4 Reed-Solomon code"]);
     }
 
@@ -175,10 +789,10 @@ mod tests {
         let re = r"(?i)\bReed-Solomon\b".to_string();
         let regex = Regex::new(&re).unwrap();
 
-        let res = find_matches(cursor, &regex);
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
         assert!(res.is_ok());
 
-        let matched_lines = res.unwrap();
+        let matched_lines = res.unwrap().lines;
         assert_eq!(matched_lines, vec!["line 4 Reed-Solomon code"]);
     }
 
@@ -189,12 +803,442 @@ mod tests {
         let re = "(?i)reed-solomon code".to_string();
         let regex = Regex::new(&re).unwrap();
 
-        let res = find_matches(cursor, &regex);
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
         assert!(res.is_ok());
-        let matched_lines = res.unwrap();
+        let matched_lines = res.unwrap().lines;
         assert_eq!(
             matched_lines,
             vec!["— 1,000 numbers. The less efficient code would require sending 2,000 numbers to identify an error, and 3,000 to correct it. But if you use the code that involves interpolating a polynomial through given points, you only need 1,001 numbers to find the error, and 1,002 to correct it. (You can add more points to identify and correct more potential errors.) As the length of your message increases, the difference in efficiency between the two codes grows starker.The more efficient code is called a Reed-Solomon code. Since its introduction in 1960, mathematicians have made further breakthroughs, developing algorithms that can correct more errors with greater efficiency. “It’s very elegant, clean, concrete,” saidSwastik Kopparty, a mathematician and computer scientist at the University of Toronto. “It can be taught to a second-year undergraduate in half an hour.”Reed-Solomon codes have been particularly useful for storing and transmitting information electronically. But the same concept has also b".to_owned()]
         );
     }
+
+    #[test]
+    fn test_find_matches_multibyte_more_than_1000_chars() {
+        // The truncation of the line around the match falls into the middle of
+        // a non-breaking space.
+        let content = format!(
+            "{}xReed-Solomon code{}",
+            "\u{a0}".repeat(600),
+            "\u{a0}".repeat(600)
+        );
+        let cursor = Cursor::new(content.clone());
+        let regex = Regex::new("Reed-Solomon").unwrap();
+
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let matched_lines = res.unwrap().lines;
+        assert_eq!(matched_lines.len(), 1);
+        let matched_line = &matched_lines[0];
+        assert!(matched_line.len() < content.len());
+        assert!(matched_line.starts_with('\u{a0}'));
+        assert!(matched_line.ends_with('\u{a0}'));
+        assert!(matched_line.contains("xReed-Solomon code"));
+        assert_eq!(
+            color_matches(matched_line, &regex)
+                .matches("Reed-Solomon")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_char_boundary() {
+        let s = "a\u{a0}b";
+        assert_eq!(floor_char_boundary(s, 0), 0);
+        assert_eq!(floor_char_boundary(s, 2), 1);
+        assert_eq!(ceil_char_boundary(s, 2), 3);
+        assert_eq!(floor_char_boundary(s, 10), s.len());
+        assert_eq!(ceil_char_boundary(s, 10), s.len());
+    }
+
+    const PARAGRAPHS: &str = "Reed-Solomon codes correct errors.\nThey were introduced\nin 1960. Are they useful?\n\n\nYes! Reed-Solomon codes are used\nin cloud computing.\n";
+
+    #[test]
+    fn test_find_matches_granularity_line() {
+        let regex = Regex::new("Reed-Solomon|1960").unwrap();
+
+        let res = find_matches(
+            Cursor::new(PARAGRAPHS),
+            &[regex.clone()],
+            &regex,
+            Granularity::Line,
+        );
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec![
+                "Reed-Solomon codes correct errors.",
+                "in 1960. Are they useful?",
+                "Yes! Reed-Solomon codes are used",
+            ]
+        );
+        assert_eq!(matches.line_numbers, vec![1, 3, 6]);
+    }
+
+    #[test]
+    fn test_find_matches_granularity_paragraph() {
+        let regex = Regex::new("1960").unwrap();
+
+        let res = find_matches(
+            Cursor::new(PARAGRAPHS),
+            &[regex.clone()],
+            &regex,
+            Granularity::Paragraph,
+        );
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec![
+                "Reed-Solomon codes correct errors. They were introduced in 1960. Are they useful?"
+            ]
+        );
+        assert_eq!(matches.line_numbers, vec![1]);
+
+        // Patterns can match across the lines of a paragraph.
+        let regex = Regex::new("used in cloud").unwrap();
+        let res = find_matches(
+            Cursor::new(PARAGRAPHS),
+            &[regex.clone()],
+            &regex,
+            Granularity::Paragraph,
+        );
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec!["Yes! Reed-Solomon codes are used in cloud computing."]
+        );
+        assert_eq!(matches.line_numbers, vec![6]);
+    }
+
+    #[test]
+    fn test_find_matches_granularity_sentence() {
+        let regex = Regex::new("Reed-Solomon|1960").unwrap();
+
+        let res = find_matches(
+            Cursor::new(PARAGRAPHS),
+            &[regex.clone()],
+            &regex,
+            Granularity::Sentence,
+        );
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec![
+                "Reed-Solomon codes correct errors.",
+                "They were introduced in 1960.",
+                "Reed-Solomon codes are used in cloud computing.",
+            ]
+        );
+        assert_eq!(matches.line_numbers, vec![1, 2, 6]);
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("First sentence. \"Second?\" Third!! Version 1.0 is out"),
+            vec![
+                (0, "First sentence."),
+                (16, "\"Second?\""),
+                (26, "Third!!"),
+                (34, "Version 1.0 is out")
+            ]
+        );
+        assert!(split_sentences("  ").is_empty());
+    }
+
+    #[test]
+    fn test_find_matches_line_numbers() {
+        let content =
+            "line 1\nline 2 Reed-Solomon code\nline 3\n\nline 5 Reed-Solomon code\nline 6";
+        let cursor = Cursor::new(content);
+        let regex = Regex::new("Reed-Solomon").unwrap();
+
+        let res = find_matches(cursor, &[regex.clone()], &regex, Granularity::Line);
+        assert!(res.is_ok());
+
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec!["line 2 Reed-Solomon code", "line 5 Reed-Solomon code"]
+        );
+        assert_eq!(matches.line_numbers, vec![2, 5]);
+    }
+
+    #[test]
+    fn test_find_matches_multiple_patterns() {
+        let content = "line 1 Reed-Solomon\nline 2\nline 3 polynomial\nline 4";
+        let cursor = Cursor::new(content);
+        let regexes = vec![
+            Regex::new("Reed-Solomon").unwrap(),
+            Regex::new("polynomial").unwrap(),
+            Regex::new("interpolation").unwrap(),
+        ];
+        let regex = Regex::new("(?:Reed-Solomon)|(?:polynomial)|(?:interpolation)").unwrap();
+
+        let res = find_matches(cursor, &regexes, &regex, Granularity::Line);
+        assert!(res.is_ok());
+
+        let matches = res.unwrap();
+        assert_eq!(
+            matches.lines,
+            vec!["line 1 Reed-Solomon", "line 3 polynomial"]
+        );
+        assert_eq!(matches.patterns, BTreeSet::from([0, 1]));
+    }
+
+    #[test]
+    fn test_template_render() {
+        let now = Utc::now();
+        let bookmark = TargetBookmark::builder(Url::parse("https://url1.com").unwrap(), now)
+            .with_title("Title 1".to_owned())
+            .build();
+
+        let template = Template::parse("{url}:{line_number}: {line}").unwrap();
+        assert!(template.is_per_line());
+        assert_eq!(
+            template.render(&bookmark, Some(("Test content", 3))),
+            "https://url1.com/:3: Test content"
+        );
+
+        let template = Template::parse("{title}\\t{url}").unwrap();
+        assert!(!template.is_per_line());
+        assert_eq!(
+            template.render(&bookmark, None),
+            "Title 1\thttps://url1.com/"
+        );
+
+        let template = Template::parse("Match in bookmark: {url}\\n{line}").unwrap();
+        assert_eq!(
+            template.render(&bookmark, Some(("Test content", 1))),
+            "Match in bookmark: https://url1.com/\nTest content"
+        );
+    }
+
+    #[test]
+    fn test_template_invalid() {
+        let res = Template::parse("{url} {content}");
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid template: unknown placeholder `{content}`, expected one of `{url}`, `{title}`, `{line}`, `{line_number}`"
+        );
+
+        let res = Template::parse("{url");
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid template: unclosed placeholder `{url`"
+        );
+    }
+
+    #[test]
+    fn test_search_bookmarks_and_or() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let cache = Cache::new(temp_path, CacheMode::Text);
+        let now = Utc::now();
+        let contents = [
+            ("https://url1.com", "Reed-Solomon code\npolynomial"),
+            ("https://url2.com", "Reed-Solomon code"),
+            ("https://url3.com", "interpolation of a polynomial"),
+        ];
+        let mut bookmarks = TargetBookmarks::default();
+
+        for (url, content) in contents {
+            let bookmark = TargetBookmark::new(Url::parse(url).unwrap(), now);
+            fs::write(temp_path.join(format!("{}.txt", bookmark.id())), content).unwrap();
+            bookmarks.insert(bookmark);
+        }
+
+        let patterns = vec!["Reed-Solomon".to_owned(), "polynomial".to_owned()];
+
+        let args = Args::parse_from(["bogrep", "--and", "Reed-Solomon", "polynomial"]);
+        let res = search_bookmarks(&patterns, &bookmarks.values_sorted(), &cache, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            SearchStats {
+                searched: 3,
+                matches: 1,
+                matched_lines: 2
+            }
+        );
+
+        let args = Args::parse_from(["bogrep", "--or", "Reed-Solomon", "polynomial"]);
+        let res = search_bookmarks(&patterns, &bookmarks.values_sorted(), &cache, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            SearchStats {
+                searched: 3,
+                matches: 3,
+                matched_lines: 4
+            }
+        );
+
+        let args = Args::parse_from(["bogrep", "Reed-Solomon", "polynomial"]);
+        let res = search_bookmarks(&patterns, &bookmarks.values_sorted(), &cache, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap().matches, 3);
+    }
+
+    #[test]
+    fn test_search_urls() {
+        let now = Utc::now();
+        let mut bookmarks = TargetBookmarks::default();
+
+        for url in [
+            "https://github.com/quambene/bogrep",
+            "https://docs.rs/regex/latest/regex/",
+            "https://en.wikipedia.org/wiki/Reed-Solomon_error_correction",
+        ] {
+            bookmarks.insert(TargetBookmark::new(Url::parse(url).unwrap(), now));
+        }
+
+        let bookmarks = bookmarks.values_sorted();
+
+        let patterns = vec!["github".to_owned()];
+        let args = Args::parse_from(["bogrep", "--url", "github"]);
+        let res = search_urls(&patterns, &bookmarks, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            SearchStats {
+                searched: 3,
+                matches: 1,
+                matched_lines: 1
+            }
+        );
+
+        let patterns = vec![r"^https://(docs\.rs|github\.com)/".to_owned()];
+        let args = Args::parse_from(["bogrep", "--url", &patterns[0]]);
+        let res = search_urls(&patterns, &bookmarks, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap().matches, 2);
+
+        let patterns = vec!["WIKIPEDIA".to_owned(), "solomon".to_owned()];
+        let args = Args::parse_from(["bogrep", "--url", "-i", "--and", "WIKIPEDIA", "solomon"]);
+        let res = search_urls(&patterns, &bookmarks, &args);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap().matches, 1);
+    }
+
+    #[test]
+    fn test_list_uncached() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let cache = Cache::new(temp_path, CacheMode::Text);
+        let now = Utc::now();
+        let mut bookmarks = TargetBookmarks::default();
+
+        // Cached bookmark.
+        let mut bookmark = TargetBookmark::new(Url::parse("https://url1.com").unwrap(), now);
+        bookmark.set_last_cached(now);
+        bookmark.add_cache_mode(CacheMode::Text);
+        fs::write(temp_path.join(format!("{}.txt", bookmark.id())), "content").unwrap();
+        bookmarks.insert(bookmark);
+
+        // Bookmark marked as cached, but the cache file is missing.
+        let mut bookmark = TargetBookmark::new(Url::parse("https://url2.com").unwrap(), now);
+        bookmark.set_last_cached(now);
+        bookmark.add_cache_mode(CacheMode::Text);
+        bookmarks.insert(bookmark);
+
+        // Bookmark with empty cache file.
+        let bookmark = TargetBookmark::new(Url::parse("https://url3.com").unwrap(), now);
+        fs::write(temp_path.join(format!("{}.txt", bookmark.id())), "\n").unwrap();
+        bookmarks.insert(bookmark);
+
+        // Bookmark which wasn't fetched yet.
+        let bookmark = TargetBookmark::new(Url::parse("https://url4.com").unwrap(), now);
+        bookmarks.insert(bookmark);
+
+        let mut cursor = Cursor::new(Vec::new());
+        let res = list_uncached(&bookmarks.values_sorted(), &cache, &mut cursor);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(cursor.into_inner()).unwrap(),
+            "https://url2.com/\nhttps://url3.com/\nhttps://url4.com/\n"
+        );
+
+        let args = Args::parse_from(["bogrep", "--exclude-cached"]);
+        assert!(args.exclude_cached);
+
+        let res = Args::try_parse_from(["bogrep", "--exclude-cached", "pattern"]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_select_bookmarks_index() {
+        let temp_dir = tempdir().unwrap();
+        let temp_path = temp_dir.path();
+        let cache = Cache::new(temp_path, CacheMode::Text);
+        let now = Utc::now();
+        let contents = [
+            ("https://url1.com", "Reed-Solomon code\npolynomial"),
+            ("https://url2.com", "Reed-Solomon code"),
+            ("https://url3.com", "interpolation of a Polynomial"),
+            ("https://url4.com", "algebraic geometry codes"),
+        ];
+        let mut bookmarks = TargetBookmarks::default();
+        let mut index = Index::new(CacheMode::Text);
+
+        for (url, content) in contents {
+            let mut bookmark = TargetBookmark::new(Url::parse(url).unwrap(), now);
+            bookmark.set_last_cached(now);
+            fs::write(temp_path.join(format!("{}.txt", bookmark.id())), content).unwrap();
+            index.insert(&bookmark, content);
+            bookmarks.insert(bookmark);
+        }
+
+        for query in [
+            vec!["Reed-Solomon"],
+            vec!["polynomial"],
+            vec!["-i", "polynomial"],
+            vec!["code"],
+            vec!["geometry code"],
+            vec!["--and", "code", "polynomial"],
+            vec!["--or", "code", "polynomial"],
+            vec!["missing"],
+        ] {
+            let args = Args::parse_from(["bogrep"].into_iter().chain(query.clone()));
+            let patterns = &args.patterns;
+
+            let scanned = select_bookmarks(patterns, &bookmarks, None, &args);
+            assert_eq!(scanned.len(), 4);
+            let res = search_bookmarks(patterns, &scanned, &cache, &args);
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+            let scanned_matches = res.unwrap();
+
+            let indexed = select_bookmarks(patterns, &bookmarks, Some(&index), &args);
+            let res = search_bookmarks(patterns, &indexed, &cache, &args);
+            assert!(res.is_ok(), "{}", res.unwrap_err());
+            let indexed_matches = res.unwrap();
+
+            assert_eq!(
+                indexed_matches.matches, scanned_matches.matches,
+                "Query: {query:?}"
+            );
+            assert_eq!(
+                indexed_matches.matched_lines, scanned_matches.matched_lines,
+                "Query: {query:?}"
+            );
+            assert!(indexed.len() >= indexed_matches.matches, "Query: {query:?}");
+        }
+
+        let args = Args::parse_from(["bogrep", "geometry"]);
+        let indexed = select_bookmarks(&args.patterns, &bookmarks, Some(&index), &args);
+        assert_eq!(indexed.len(), 1);
+
+        // Regex patterns are searched in all bookmarks.
+        let args = Args::parse_from(["bogrep", "geo.etry"]);
+        let indexed = select_bookmarks(&args.patterns, &bookmarks, Some(&index), &args);
+        assert_eq!(indexed.len(), 4);
+    }
 }
@@ -18,8 +18,15 @@ pub async fn sync(config: &Config, args: &SyncArgs) -> Result<(), anyhow::Error>
     }
 
     let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
-    let cache = Cache::new(&config.cache_path, cache_mode);
-    let client_config = ClientConfig::new(&config.settings);
+    let text_extractor = args.extractor.unwrap_or(config.settings.extractor);
+    let cache = Cache::new(&config.cache_path, cache_mode)
+        .with_text_extractor(text_extractor)
+        .with_normalize_whitespace(args.normalize_whitespace)
+        .with_file_mode(config.settings.cache_file_mode);
+    let client_config = ClientConfig::new(&config.settings)
+        .with_request_timeout(args.timeout)
+        .with_include_binary(args.include_binary)
+        .with_follow_redirects(!args.no_follow_redirects);
     let client = Client::new(&client_config)?;
     let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
     let now = Utc::now();
@@ -28,12 +35,24 @@ pub async fn sync(config: &Config, args: &SyncArgs) -> Result<(), anyhow::Error>
     } else {
         RunMode::Sync
     };
+    let max_concurrent_requests = config.max_concurrent_requests(args.concurrency);
     let service_config = ServiceConfig::new(
         run_mode,
         &config.settings.ignored_urls,
-        config.settings.max_concurrent_requests,
-    )?;
-    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+        max_concurrent_requests,
+    )?
+    .with_settings(&config.settings)
+    .with_resolve_underlying(!args.no_underlying)
+    .with_only_underlying(args.only_underlying)
+    .with_human_readable(args.human_readable)
+    .with_respect_robots(
+        args.respect_robots || (config.settings.respect_robots && !args.ignore_robots),
+    )
+    .with_min_text_length(args.min_text_length.or(config.settings.min_text_length))
+    .with_ignored_report(args.ignored_report);
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer))
+        .with_soft_delete(config.settings.removal_grace_period.is_some())
+        .with_strip_query(&config.settings.strip_query, &config.settings.include_query);
     bookmark_manager.add_sources(&config.settings.sources)?;
     let bookmark_service = BookmarkService::new(service_config, client, cache);
 
@@ -0,0 +1,282 @@
+use crate::{
+    args::VerifyArgs, bookmarks::BookmarkManager, errors::BogrepError, utils, Cache, CacheMode,
+    Caching, Config, TargetBookmarks,
+};
+use chrono::{DateTime, Utc};
+use log::debug;
+use std::{fmt, path::PathBuf};
+use url::Url;
+
+/// An inconsistency between the bookmarks in `bookmarks.json` and the files in
+/// the cache directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// The bookmark claims a cache mode, but the cached file is missing.
+    Missing { url: Url, cache_mode: CacheMode },
+    /// The cached file exists, but the cache mode is not tracked for the
+    /// bookmark.
+    Untracked { url: Url, cache_mode: CacheMode },
+    /// The cached file doesn't belong to any bookmark.
+    Orphaned { path: PathBuf },
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::Missing { url, cache_mode } => {
+                write!(f, "Missing {cache_mode} file in cache for bookmark: {url}")
+            }
+            Inconsistency::Untracked { url, cache_mode } => {
+                write!(
+                    f,
+                    "Untracked {cache_mode} file in cache for bookmark: {url}"
+                )
+            }
+            Inconsistency::Orphaned { path } => {
+                write!(f, "Orphaned file in cache: {}", path.display())
+            }
+        }
+    }
+}
+
+/// Verify that the cache is consistent with the bookmarks in `bookmarks.json`.
+pub fn verify(config: &Config, args: &VerifyArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    let now = Utc::now();
+    let cache = Cache::new(&config.cache_path, config.settings.cache_mode.clone());
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer));
+    bookmark_manager.import(now)?;
+
+    let inconsistencies = find_inconsistencies(&cache, bookmark_manager.target_bookmarks())?;
+
+    for inconsistency in &inconsistencies {
+        println!("{inconsistency}");
+    }
+
+    if inconsistencies.is_empty() {
+        println!("Cache is consistent with bookmarks");
+    } else if args.fix {
+        fix_inconsistencies(
+            &inconsistencies,
+            bookmark_manager.target_bookmarks_mut(),
+            now,
+        )?;
+        bookmark_manager.export()?;
+        println!("Fixed {} inconsistencies", inconsistencies.len());
+    } else {
+        println!(
+            "Found {} inconsistencies, run `bogrep verify --fix` to fix them",
+            inconsistencies.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Cross-check the `cache_modes` of the bookmarks against the files in the
+/// cache directory.
+fn find_inconsistencies(
+    cache: &Cache,
+    target_bookmarks: &TargetBookmarks,
+) -> Result<Vec<Inconsistency>, BogrepError> {
+    let mut inconsistencies = vec![];
+
+    for bookmark in target_bookmarks.values_sorted() {
+        for cache_mode in Cache::modes() {
+            let cache_path =
+                cache.bookmark_path_by_cache_mode(bookmark.cache_name(), &cache_mode)?;
+            let is_tracked = bookmark.cache_modes().contains(&cache_mode);
+
+            if is_tracked && !cache_path.exists() {
+                inconsistencies.push(Inconsistency::Missing {
+                    url: bookmark.url().to_owned(),
+                    cache_mode,
+                });
+            } else if !is_tracked && cache_path.exists() {
+                inconsistencies.push(Inconsistency::Untracked {
+                    url: bookmark.url().to_owned(),
+                    cache_mode,
+                });
+            }
+        }
+    }
+
+    inconsistencies.extend(
+        cache
+            .unreferenced_files(target_bookmarks)?
+            .into_iter()
+            .map(|path| Inconsistency::Orphaned { path }),
+    );
+
+    Ok(inconsistencies)
+}
+
+/// Reconcile the bookmarks with the cache directory: drop phantom cache modes,
+/// track existing cache files, and delete orphaned files.
+fn fix_inconsistencies(
+    inconsistencies: &[Inconsistency],
+    target_bookmarks: &mut TargetBookmarks,
+    now: DateTime<Utc>,
+) -> Result<(), BogrepError> {
+    for inconsistency in inconsistencies {
+        match inconsistency {
+            Inconsistency::Missing { url, cache_mode } => {
+                if let Some(bookmark) = target_bookmarks.get_mut(url) {
+                    bookmark.remove_cache_mode(cache_mode);
+
+                    if bookmark.cache_modes().is_empty() {
+                        bookmark.unset_last_cached();
+                    }
+                }
+            }
+            Inconsistency::Untracked { url, cache_mode } => {
+                if let Some(bookmark) = target_bookmarks.get_mut(url) {
+                    bookmark.add_cache_mode(cache_mode.clone());
+
+                    if bookmark.last_cached().is_none() {
+                        bookmark.set_last_cached(now);
+                    }
+                }
+            }
+            Inconsistency::Orphaned { path } => {
+                utils::remove_file(path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TargetBookmark;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    fn create_target_bookmarks(url: &Url, cache_modes: &[CacheMode]) -> TargetBookmarks {
+        let now = Utc::now();
+        let mut bookmark = TargetBookmark::builder_with_id(
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7".to_owned(),
+            url.clone(),
+            now,
+        )
+        .build();
+
+        for cache_mode in cache_modes {
+            bookmark.add_cache_mode(cache_mode.clone());
+            bookmark.set_last_cached(now);
+        }
+
+        TargetBookmarks::new(HashMap::from_iter([(url.clone(), bookmark)]))
+    }
+
+    #[test]
+    fn test_find_inconsistencies_consistent() {
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let url = Url::parse("https://url1.com").unwrap();
+        let target_bookmarks = create_target_bookmarks(&url, &[CacheMode::Text]);
+        let bookmark = target_bookmarks.get(&url).unwrap();
+        utils::create_file(
+            &cache
+                .bookmark_path_by_cache_mode(bookmark.id(), &CacheMode::Text)
+                .unwrap(),
+        )
+        .unwrap();
+        // Unrelated files are ignored.
+        utils::create_file(&temp_dir.path().join(".DS_Store")).unwrap();
+
+        let res = find_inconsistencies(&cache, &target_bookmarks);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(res.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_missing_file() {
+        let now = Utc::now();
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let url = Url::parse("https://url1.com").unwrap();
+        let mut target_bookmarks = create_target_bookmarks(&url, &[CacheMode::Text]);
+
+        let inconsistencies = find_inconsistencies(&cache, &target_bookmarks).unwrap();
+        assert_eq!(
+            inconsistencies,
+            vec![Inconsistency::Missing {
+                url: url.clone(),
+                cache_mode: CacheMode::Text
+            }]
+        );
+
+        fix_inconsistencies(&inconsistencies, &mut target_bookmarks, now).unwrap();
+        let bookmark = target_bookmarks.get(&url).unwrap();
+        assert!(bookmark.cache_modes().is_empty());
+        assert!(bookmark.last_cached().is_none());
+        assert!(find_inconsistencies(&cache, &target_bookmarks)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_verify_untracked_file() {
+        let now = Utc::now();
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let url = Url::parse("https://url1.com").unwrap();
+        let mut target_bookmarks = create_target_bookmarks(&url, &[]);
+        let bookmark = target_bookmarks.get(&url).unwrap();
+        utils::create_file(
+            &cache
+                .bookmark_path_by_cache_mode(bookmark.id(), &CacheMode::Html)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let inconsistencies = find_inconsistencies(&cache, &target_bookmarks).unwrap();
+        assert_eq!(
+            inconsistencies,
+            vec![Inconsistency::Untracked {
+                url: url.clone(),
+                cache_mode: CacheMode::Html
+            }]
+        );
+
+        fix_inconsistencies(&inconsistencies, &mut target_bookmarks, now).unwrap();
+        let bookmark = target_bookmarks.get(&url).unwrap();
+        assert!(bookmark.cache_modes().contains(&CacheMode::Html));
+        assert_eq!(bookmark.last_cached(), Some(now.timestamp_millis()));
+        assert!(find_inconsistencies(&cache, &target_bookmarks)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_verify_orphaned_file() {
+        let now = Utc::now();
+        let temp_dir = tempdir().unwrap();
+        let cache = Cache::new(temp_dir.path(), CacheMode::Text);
+        let url = Url::parse("https://url1.com").unwrap();
+        let mut target_bookmarks = create_target_bookmarks(&url, &[]);
+        let orphaned_path = temp_dir
+            .path()
+            .join("511b1590-e6de-4989-bca4-96dc61730508.txt");
+        utils::create_file(&orphaned_path).unwrap();
+
+        let inconsistencies = find_inconsistencies(&cache, &target_bookmarks).unwrap();
+        assert_eq!(
+            inconsistencies,
+            vec![Inconsistency::Orphaned {
+                path: orphaned_path.clone()
+            }]
+        );
+
+        fix_inconsistencies(&inconsistencies, &mut target_bookmarks, now).unwrap();
+        assert!(!orphaned_path.exists());
+        assert!(find_inconsistencies(&cache, &target_bookmarks)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    args::WatchArgs,
+    bookmarks::{BookmarkManager, BookmarkService, RunMode, ServiceConfig},
+    cache::CacheMode,
+    client::ClientConfig,
+    utils, Cache, Client, Config,
+};
+use chrono::Utc;
+use log::debug;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::{signal, time};
+
+/// Synchronize and fetch bookmarks periodically until interrupted.
+pub async fn watch(config: &Config, args: &WatchArgs) -> Result<(), anyhow::Error> {
+    debug!("{args:?}");
+
+    // An interrupt during a cycle aborts the cycle and stops watching
+    // afterwards.
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let interrupt_handler = {
+        let interrupted = interrupted.clone();
+        tokio::spawn(async move {
+            if signal::ctrl_c().await.is_ok() {
+                interrupted.store(true, Ordering::SeqCst);
+            }
+        })
+    };
+    let mut cycle = 0;
+
+    loop {
+        cycle += 1;
+        println!("Start watch cycle {cycle}");
+
+        watch_cycle(config, args).await?;
+
+        if interrupted.load(Ordering::SeqCst) || args.cycles.is_some_and(|cycles| cycle >= cycles) {
+            println!("Finished watch cycle {cycle}");
+            break;
+        }
+
+        println!(
+            "Finished watch cycle {cycle}, next cycle in {}",
+            humanize_duration(args.interval)
+        );
+
+        tokio::select! {
+            _ = signal::ctrl_c() => break,
+            _ = time::sleep(args.interval) => (),
+        }
+    }
+
+    interrupt_handler.abort();
+
+    Ok(())
+}
+
+/// Synchronize the bookmarks with the sources, and fetch the bookmarks again.
+async fn watch_cycle(config: &Config, args: &WatchArgs) -> Result<(), anyhow::Error> {
+    let cache_mode = CacheMode::new(&args.mode, &config.settings.cache_mode);
+    let cache = Cache::new(&config.cache_path, cache_mode)
+        .with_text_extractor(config.settings.extractor)
+        .with_file_mode(config.settings.cache_file_mode);
+    let client_config = ClientConfig::new(&config.settings);
+    let client = Client::new(&client_config)?;
+    let target_reader_writer = utils::open_file_in_read_write_mode(&config.target_bookmark_file)?;
+    let now = Utc::now();
+    let service_config = ServiceConfig::new(
+        RunMode::Sync,
+        &config.settings.ignored_urls,
+        config.max_concurrent_requests(None),
+    )?
+    .with_settings(&config.settings);
+    let mut bookmark_manager = BookmarkManager::new(Box::new(target_reader_writer))
+        .with_soft_delete(config.settings.removal_grace_period.is_some())
+        .with_strip_query(&config.settings.strip_query, &config.settings.include_query);
+    bookmark_manager.add_sources(&config.settings.sources)?;
+    let bookmark_service = BookmarkService::new(service_config, client, cache);
+
+    bookmark_service.run(&mut bookmark_manager, now).await?;
+
+    Ok(())
+}
+
+/// Format a duration in the largest unit without remainder.
+fn humanize_duration(duration: time::Duration) -> String {
+    let millis = duration.as_millis();
+
+    [
+        (86_400_000, "d"),
+        (3_600_000, "h"),
+        (60_000, "m"),
+        (1_000, "s"),
+    ]
+    .into_iter()
+    .find(|(unit, _)| millis >= *unit && millis % unit == 0)
+    .map(|(unit, suffix)| format!("{}{suffix}", millis / unit))
+    .unwrap_or_else(|| format!("{millis}ms"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_humanize_duration() {
+        assert_eq!(humanize_duration(Duration::from_millis(100)), "100ms");
+        assert_eq!(humanize_duration(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(humanize_duration(Duration::from_secs(30)), "30s");
+        assert_eq!(humanize_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(humanize_duration(Duration::from_secs(300)), "5m");
+        assert_eq!(humanize_duration(Duration::from_secs(7200)), "2h");
+        assert_eq!(humanize_duration(Duration::from_secs(86400)), "1d");
+    }
+}
@@ -0,0 +1,48 @@
+use clap::ValueEnum;
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+/// When to colorize the output of match highlights and diffs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Colorize the output if stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    /// Always colorize the output, e.g. for paging through `less -R`.
+    Always,
+    /// Never colorize the output.
+    Never,
+}
+
+impl ColorChoice {
+    /// Enable or disable the colorized output globally.
+    pub fn init(&self) {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|no_color| !no_color.is_empty());
+        let colorize = self.should_colorize(no_color, io::stdout().is_terminal());
+        colored::control::set_override(colorize);
+    }
+
+    fn should_colorize(&self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => !no_color && is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_colorize() {
+        assert!(ColorChoice::Auto.should_colorize(false, true));
+        assert!(!ColorChoice::Auto.should_colorize(true, true));
+        assert!(!ColorChoice::Auto.should_colorize(false, false));
+        assert!(ColorChoice::Always.should_colorize(true, false));
+        assert!(!ColorChoice::Never.should_colorize(false, true));
+    }
+}
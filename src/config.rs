@@ -1,6 +1,6 @@
 use crate::{json, JsonBookmarks, Settings};
 use anyhow::{anyhow, Context};
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use std::{
     env,
     fs::{self, File},
@@ -13,6 +13,10 @@ const SETTINGS_FILE: &str = "settings.json";
 const BOOKMARKS_FILE: &str = "bookmarks.json";
 const BOOKMARKS_LOCK_FILE: &str = "bookmarks-lock.json";
 const CACHE_DIR: &str = "cache";
+const MANIFEST_FILE: &str = "manifest.json";
+const INDEX_FILE: &str = "index.json";
+/// The file descriptors reserved in addition to open files and network sockets.
+const RESERVED_FILE_DESCRIPTORS: u64 = 100;
 
 /// A configuration for running Bogrep.
 // TODO: remove `target_bookmark_lock_file` (not used).
@@ -26,6 +30,10 @@ pub struct Config {
     pub target_bookmark_file: PathBuf,
     /// The path to the lock file to write bookmarks.
     pub target_bookmark_lock_file: PathBuf,
+    /// The path to the manifest of the cached files.
+    pub manifest_file: PathBuf,
+    /// The path to the inverted index of the cached files.
+    pub index_file: PathBuf,
     /// The configured settings.
     pub settings: Settings,
 }
@@ -36,6 +44,8 @@ impl Config {
         cache_path: &Path,
         target_bookmark_file: &Path,
         target_bookmark_lock_file: &Path,
+        manifest_file: &Path,
+        index_file: &Path,
         settings: Settings,
     ) -> Self {
         Self {
@@ -43,6 +53,8 @@ impl Config {
             cache_path: cache_path.to_owned(),
             target_bookmark_file: target_bookmark_file.to_owned(),
             target_bookmark_lock_file: target_bookmark_lock_file.to_owned(),
+            manifest_file: manifest_file.to_owned(),
+            index_file: index_file.to_owned(),
             settings,
         }
     }
@@ -58,7 +70,8 @@ impl Config {
         let settings_path = config_path.join(SETTINGS_FILE);
         let target_bookmark_path = config_path.join(BOOKMARKS_FILE);
         let target_bookmark_lock_path = config_path.join(BOOKMARKS_LOCK_FILE);
-        let cache_path = config_path.join(CACHE_DIR);
+        let manifest_path = config_path.join(MANIFEST_FILE);
+        let index_path = config_path.join(INDEX_FILE);
 
         if !config_path.exists() {
             debug!("Create config at {}", config_path.display());
@@ -69,12 +82,18 @@ impl Config {
         }
 
         let settings = Settings::init(&settings_path)?;
+        let cache_path = settings
+            .cache_path
+            .clone()
+            .unwrap_or_else(|| config_path.join(CACHE_DIR));
 
         // The file descriptor limit is determined by open files and network
-        // sockets. We are adding 100 more to be on the safe side.
+        // sockets. We are reserving some more to be on the safe side.
         #[cfg(not(any(target_os = "windows")))]
         set_file_descriptor_limit(
-            settings.max_open_files + settings.max_concurrent_requests as u64 + 100,
+            settings.max_open_files
+                + settings.max_concurrent_requests as u64
+                + RESERVED_FILE_DESCRIPTORS,
         )?;
 
         if !target_bookmark_path.exists() {
@@ -107,6 +126,8 @@ impl Config {
             &cache_path,
             &target_bookmark_path,
             &target_bookmark_lock_path,
+            &manifest_path,
+            &index_path,
             settings,
         );
 
@@ -114,6 +135,48 @@ impl Config {
 
         Ok(config)
     }
+
+    /// The maximum number of concurrent requests, given by `concurrency` or
+    /// the settings.
+    ///
+    /// If the file descriptor limit can't be raised sufficiently, the number
+    /// of concurrent requests is reduced to avoid "Too many open files".
+    pub fn max_concurrent_requests(&self, concurrency: Option<usize>) -> usize {
+        let max_concurrent_requests = concurrency.unwrap_or(self.settings.max_concurrent_requests);
+
+        #[cfg(not(any(target_os = "windows")))]
+        if let Ok((soft_limit, _)) = rlimit::getrlimit(rlimit::Resource::NOFILE) {
+            let capped_requests = cap_concurrent_requests(
+                max_concurrent_requests,
+                self.settings.max_open_files,
+                soft_limit,
+            );
+
+            if capped_requests < max_concurrent_requests {
+                warn!(
+                    "Reduce maximum number of concurrent requests from {max_concurrent_requests} to {capped_requests} because of the file descriptor limit ({soft_limit})"
+                );
+                return capped_requests;
+            }
+        }
+
+        max_concurrent_requests
+    }
+}
+
+/// Cap the number of concurrent requests so that the open files, network
+/// sockets, and reserved file descriptors don't exceed the file descriptor
+/// limit.
+#[cfg(not(any(target_os = "windows")))]
+fn cap_concurrent_requests(
+    max_concurrent_requests: usize,
+    max_open_files: u64,
+    file_descriptor_limit: u64,
+) -> usize {
+    let available_sockets =
+        file_descriptor_limit.saturating_sub(max_open_files + RESERVED_FILE_DESCRIPTORS);
+    let available_sockets = usize::try_from(available_sockets).unwrap_or(usize::MAX);
+    max_concurrent_requests.min(available_sockets).max(1)
 }
 
 #[cfg(not(any(target_os = "windows")))]
@@ -141,3 +204,24 @@ pub fn set_file_descriptor_limit(file_descriptor_limit: u64) -> Result<(), anyho
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(any(target_os = "windows")))]
+    fn test_cap_concurrent_requests() {
+        // The file descriptor limit is sufficient.
+        assert_eq!(cap_concurrent_requests(500, 100, 1024), 500);
+        assert_eq!(cap_concurrent_requests(500, 100, 700), 500);
+
+        // The file descriptor limit is exceeded.
+        assert_eq!(cap_concurrent_requests(500, 100, 699), 499);
+        assert_eq!(cap_concurrent_requests(500, 100, 256), 56);
+
+        // At least one request is allowed.
+        assert_eq!(cap_concurrent_requests(500, 100, 200), 1);
+        assert_eq!(cap_concurrent_requests(500, 100, 64), 1);
+    }
+}
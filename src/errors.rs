@@ -18,8 +18,16 @@ pub enum BogrepError {
     BinaryResponse(String),
     #[error("Can't fetch empty bookmark ({0})")]
     EmptyResponse(String),
+    #[error("Website not modified ({0})")]
+    NotModified(String),
+    #[error("Can't cache oversized bookmark ({url}): {size} bytes")]
+    OversizedResponse { url: String, size: usize },
+    #[error("Can't cache thin bookmark ({url}): {length} characters")]
+    ThinResponse { url: String, length: usize },
     #[error("Can't get host for url: {0}")]
     ConvertHost(String),
+    #[error("Invalid bookmark id: {0}")]
+    InvalidId(String),
     #[error("Can't serialize json: {0}")]
     SerializeJson(serde_json::Error),
     #[error("Can't deserialize json: {0}")]
@@ -64,6 +72,12 @@ pub enum BogrepError {
     ConvertToStr(#[from] ToStrError),
     #[error("Can't remove website ({url}) from cache: {err}")]
     RemoveCache { url: String, err: tokio::io::Error },
+    #[error("Invalid Firefox backup: missing `mozLz40` header")]
+    InvalidFirefoxBackup,
+    #[error("Invalid header for domain {domain}: {header}")]
+    InvalidHeader { domain: String, header: String },
+    #[error("Invalid glob pattern: {0}")]
+    InvalidGlob(globset::Error),
     #[error("Invalid input")]
     InvalidInput,
     #[error(transparent)]
@@ -3,6 +3,8 @@ use crate::{
     errors::BogrepError,
     UnderlyingType,
 };
+use clap::ValueEnum;
+use encoding_rs::{Encoding, UTF_8};
 use html5ever::{
     parse_document,
     serialize::{serialize, SerializeOpts},
@@ -14,12 +16,71 @@ use readability::{extract, ExtractOptions, ScorerOptions};
 use regex::Regex;
 use reqwest::Url;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 use std::{borrow::BorrowMut, io::Cursor, rc::Rc, sync::OnceLock};
 use tendril::TendrilSink;
 
 static UNLIKELY_CANDIDATES: OnceLock<Regex> = OnceLock::new();
 static NEGATIVE_CANDIDATES: OnceLock<Regex> = OnceLock::new();
 static POSITIVE_CANDIDATES: OnceLock<Regex> = OnceLock::new();
+static META_CHARSET: OnceLock<Regex> = OnceLock::new();
+static MARKDOWN_IMAGE: OnceLock<Regex> = OnceLock::new();
+static MARKDOWN_LINK: OnceLock<Regex> = OnceLock::new();
+
+/// The minimum number of characters extracted via readability before falling
+/// back to the text of the full DOM.
+const MIN_TEXT_LENGTH: usize = 20;
+
+/// The number of bytes to scan for a `<meta charset>` declaration.
+const META_CHARSET_SCAN_LIMIT: usize = 1024;
+
+/// Decode the bytes of a website to UTF-8.
+///
+/// The charset is detected from the content type header or the `<meta
+/// charset>` declaration of the html. Default to UTF-8 if no charset is
+/// detected.
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(detect_content_type_charset)
+        .or_else(|| detect_meta_charset(bytes))
+        .unwrap_or(UTF_8);
+    debug!("Decode html with encoding: {}", encoding.name());
+
+    let (html, _, has_errors) = encoding.decode(bytes);
+
+    if has_errors {
+        debug!(
+            "Replaced malformed sequences for encoding: {}",
+            encoding.name()
+        );
+    }
+
+    html.into_owned()
+}
+
+fn detect_content_type_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type
+        .split(';')
+        .skip(1)
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("charset"))
+        .and_then(|(_, value)| Encoding::for_label(value.trim().trim_matches('"').as_bytes()))
+}
+
+fn detect_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(META_CHARSET_SCAN_LIMIT)];
+    // The charset declaration is ASCII-compatible, so a lossy conversion is
+    // sufficient.
+    let head = String::from_utf8_lossy(head);
+    let meta_charset = META_CHARSET.get_or_init(|| {
+        Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_:.-]+)"#).unwrap()
+    });
+
+    meta_charset
+        .captures(&head)
+        .and_then(|captures| captures.get(1))
+        .and_then(|charset| Encoding::for_label(charset.as_str().as_bytes()))
+}
 
 pub fn filter_html(html: &str) -> Result<String, BogrepError> {
     let dom = parse_document(RcDom::default(), ParseOpts::default())
@@ -105,7 +166,131 @@ fn is_filtered_tag(tag_name: &QualName) -> bool {
         || tag_name.local.contains("script")
 }
 
-pub fn convert_to_text(html: &str, bookmark_url: &Url) -> Result<String, BogrepError> {
+/// The strategy to extract the text from HTML.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextExtractor {
+    /// Extract the main content via readability, and fall back to the text of
+    /// the full DOM if readability yields (nearly) empty content.
+    #[default]
+    Readability,
+    /// Extract the text of the full DOM by stripping the tags.
+    DomText,
+    /// Convert to markdown and strip the markdown syntax.
+    Markdown,
+}
+
+pub fn convert_to_text(
+    html: &str,
+    bookmark_url: &Url,
+    text_extractor: &TextExtractor,
+) -> Result<String, BogrepError> {
+    let text = match text_extractor {
+        TextExtractor::Readability => {
+            let (text, text_extractor) = extract_text(html, bookmark_url)?;
+            debug!("Converted bookmark ({bookmark_url}) to text via {text_extractor:?}");
+            text
+        }
+        TextExtractor::DomText => extract_dom_text(html),
+        TextExtractor::Markdown => strip_markdown(&convert_to_markdown(html)),
+    };
+
+    Ok(text)
+}
+
+/// Normalize the whitespace of a text, i.e. replace non-breaking spaces and
+/// collapse runs of whitespace by a single space, trim the lines, and collapse
+/// runs of empty lines by a single empty line.
+pub fn normalize_whitespace(text: &str) -> String {
+    let mut lines = vec![];
+
+    for line in text.lines() {
+        // Non-breaking spaces are whitespace too.
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if !line.is_empty() || lines.last().is_some_and(|last: &String| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+
+    if lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+
+    lines.join("\n")
+}
+
+/// Extract the text via readability, and fall back to the text of the full DOM
+/// if readability yields (nearly) empty content.
+pub fn extract_text(
+    html: &str,
+    bookmark_url: &Url,
+) -> Result<(String, TextExtractor), BogrepError> {
+    let text = extract_readable_text(html, bookmark_url)?;
+
+    if text.trim().chars().count() >= MIN_TEXT_LENGTH {
+        return Ok((text, TextExtractor::Readability));
+    }
+
+    let fallback_text = extract_dom_text(html);
+
+    if fallback_text.chars().count() > text.trim().chars().count() {
+        Ok((fallback_text, TextExtractor::DomText))
+    } else {
+        Ok((text, TextExtractor::Readability))
+    }
+}
+
+/// Extract the text of all nodes, except for scripts and styles, and collapse
+/// the whitespace.
+fn extract_dom_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut words = vec![];
+
+    for node in document.root_element().descendants() {
+        if let Some(text) = node.value().as_text() {
+            let is_hidden = node
+                .parent()
+                .and_then(|parent| parent.value().as_element())
+                .is_some_and(|element| {
+                    matches!(element.name(), "script" | "style" | "noscript" | "template")
+                });
+
+            if !is_hidden {
+                words.extend(text.split_whitespace());
+            }
+        }
+    }
+
+    words.join(" ")
+}
+
+/// Strip the markdown syntax for links, images, headings, emphasis, and
+/// escaped characters.
+fn strip_markdown(markdown: &str) -> String {
+    let markdown_image =
+        MARKDOWN_IMAGE.get_or_init(|| Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap());
+    let markdown_link = MARKDOWN_LINK.get_or_init(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+    let markdown = markdown_image.replace_all(markdown, "");
+    let markdown = markdown_link.replace_all(&markdown, "$1");
+
+    markdown
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(['#', '>'])
+                .replace("**", "")
+                .replace('\\', "")
+                .trim()
+                .to_owned()
+        })
+        // Remove empty lines, and underlines of headings and horizontal rules.
+        .filter(|line| !line.chars().all(|char| char == '=' || char == '-'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn extract_readable_text(html: &str, bookmark_url: &Url) -> Result<String, BogrepError> {
     let mut cursor = Cursor::new(html);
     let options =  ExtractOptions { parse_options: Default::default(), scorer_options: ScorerOptions {
         unlikely_candidates: UNLIKELY_CANDIDATES.get_or_init(|| {
@@ -186,6 +371,39 @@ fn select_underlying_reddit(html: &str) -> Result<Option<Url>, BogrepError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_html_content_type() {
+        // "Café" encoded as ISO-8859-1.
+        let bytes = b"<html><body><p>Caf\xe9</p></body></html>";
+
+        let html = decode_html(bytes, Some("text/html; charset=ISO-8859-1"));
+        assert_eq!(html, "<html><body><p>Café</p></body></html>");
+    }
+
+    #[test]
+    fn test_decode_html_meta_charset() {
+        let bytes =
+            b"<html><head><meta charset=\"iso-8859-1\"></head><body><p>Caf\xe9</p></body></html>";
+        let html = decode_html(bytes, Some("text/html"));
+        assert_eq!(
+            html,
+            "<html><head><meta charset=\"iso-8859-1\"></head><body><p>Café</p></body></html>"
+        );
+
+        let bytes = b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\"></head><body><p>Caf\xe9</p></body></html>";
+        let html = decode_html(bytes, None);
+        assert!(html.contains("<p>Café</p>"));
+    }
+
+    #[test]
+    fn test_decode_html_default_utf8() {
+        let html = decode_html("<p>Café</p>".as_bytes(), Some("text/html"));
+        assert_eq!(html, "<p>Café</p>");
+
+        let html = decode_html("<p>Café</p>".as_bytes(), Some("text/html; charset=unknown"));
+        assert_eq!(html, "<p>Café</p>");
+    }
+
     #[test]
     fn test_select_underlying_reddit() {
         let html = r#"
@@ -309,7 +527,7 @@ mod tests {
         </html>
         "#;
         let url = Url::parse("https://example.net").unwrap();
-        let res = convert_to_text(html, &url);
+        let res = convert_to_text(html, &url, &TextExtractor::Readability);
         assert!(res.is_ok(), "{}", res.unwrap_err());
 
         let text = res.unwrap();
@@ -318,6 +536,130 @@ mod tests {
         assert_eq!(text, "title_contentparagraph_content_1");
     }
 
+    #[test]
+    fn test_normalize_whitespace() {
+        let html = "<html><body><p>non&nbsp;breaking&nbsp;&nbsp; space</p>\
+            <p>\u{a0}irregular \t whitespace </p></body></html>";
+        let url = Url::parse("https://example.net").unwrap();
+        let text = convert_to_text(html, &url, &TextExtractor::Markdown).unwrap();
+        assert!(text.contains('\u{a0}'));
+        assert_eq!(
+            normalize_whitespace(&text),
+            "non breaking space\nirregular whitespace"
+        );
+
+        assert_eq!(
+            normalize_whitespace("\n  line\u{a0}1 \n\n \u{a0} \n\nline  2\n\n"),
+            "line 1\n\nline 2"
+        );
+        assert_eq!(normalize_whitespace(""), "");
+    }
+
+    #[test]
+    fn test_extract_text_readability() {
+        let html = r#"
+        <html>
+        <head>
+            <title>title_content</title>
+        </head>
+        <body>
+            <div>
+                <p>paragraph_content_1 with enough readable content</p>
+            </div>
+        </body>
+        </html>
+        "#;
+        let url = Url::parse("https://example.net").unwrap();
+        let res = extract_text(html, &url);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            (
+                "paragraph_content_1 with enough readable content".to_owned(),
+                TextExtractor::Readability
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_text_fallback() {
+        // Readability discards the sidebar and the footer as unlikely
+        // candidates for the main content.
+        let html = r#"
+        <html>
+        <head>
+            <style>p { color: red; }</style>
+        </head>
+        <body>
+            <div class="sidebar">
+                <p>sidebar_content</p>
+            </div>
+            <footer>
+                <p>footer_content</p>
+            </footer>
+        </body>
+        </html>
+        "#;
+        let url = Url::parse("https://example.net").unwrap();
+        let res = extract_text(html, &url);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            (
+                "sidebar_content footer_content".to_owned(),
+                TextExtractor::DomText
+            )
+        );
+
+        let res = convert_to_text(html, &url, &TextExtractor::Readability);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(res.unwrap(), "sidebar_content footer_content");
+    }
+
+    #[test]
+    fn test_convert_to_text_extractors() {
+        let html = r#"
+        <html>
+
+        <head>
+            <title>title_content</title>
+            <meta>
+        </head>
+
+        <body>
+            <div>
+                <h1>heading_content</h1>
+                <p>paragraph_content_1 with a <a href="https://url.com">link_content</a></p>
+                <div>
+                    <p><strong>paragraph_content_2</strong></p>
+                </div>
+            </div>
+        </body>
+
+        </html>
+        "#;
+        let url = Url::parse("https://example.net").unwrap();
+
+        let res = convert_to_text(html, &url, &TextExtractor::Readability);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        // TODO: fix missing "paragraph_content_2"
+        assert_eq!(res.unwrap(), "paragraph_content_1 with a link_content");
+
+        let res = convert_to_text(html, &url, &TextExtractor::DomText);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            "title_content heading_content paragraph_content_1 with a link_content paragraph_content_2"
+        );
+
+        let res = convert_to_text(html, &url, &TextExtractor::Markdown);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            "title_content\nheading_content\nparagraph_content_1 with a link_content\nparagraph_content_2"
+        );
+    }
+
     #[test]
     fn test_convert_to_markdown() {
         let html = r#"
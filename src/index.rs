@@ -0,0 +1,166 @@
+use crate::{errors::BogrepError, json, utils, CacheMode, TargetBookmark, TargetBookmarks};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// An inverted index which maps the tokens of the cached websites to the ids
+/// of the bookmarks.
+///
+/// Tokens are separated by whitespace and lowercased.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Index {
+    /// The cache mode of the indexed files.
+    pub cache_mode: CacheMode,
+    /// The `last_cached` timestamp of the indexed bookmarks.
+    ///
+    /// A bookmark which was cached again after indexing is treated as not
+    /// indexed.
+    pub bookmarks: BTreeMap<String, Option<i64>>,
+    pub tokens: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Index {
+    pub fn new(cache_mode: CacheMode) -> Self {
+        Self {
+            cache_mode,
+            ..Default::default()
+        }
+    }
+
+    /// Read the index from a json file.
+    pub fn read(index_path: &Path) -> Result<Self, BogrepError> {
+        let index = utils::read_file(index_path)?;
+        let index = json::deserialize(&index)?;
+        Ok(index)
+    }
+
+    /// Write the index as json file.
+    pub fn write(&self, index_path: &Path) -> Result<(), BogrepError> {
+        let index = json::serialize(self)?;
+        utils::write_file_atomically(index_path, &index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bookmarks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Add the tokens of the cached content of a bookmark to the index.
+    pub fn insert(&mut self, bookmark: &TargetBookmark, content: &str) {
+        let bookmark_id = bookmark.id().to_owned();
+
+        for token in tokenize(content) {
+            self.tokens
+                .entry(token)
+                .or_default()
+                .insert(bookmark_id.clone());
+        }
+
+        self.bookmarks.insert(bookmark_id, bookmark.last_cached());
+    }
+
+    /// Select the bookmarks which possibly contain the literal pattern.
+    ///
+    /// Every token of the pattern has to be part of a token of the cached
+    /// website. Bookmarks which are not indexed, or which were cached again
+    /// after indexing, are always selected.
+    pub fn candidates<'a>(
+        &self,
+        bookmarks: &'a TargetBookmarks,
+        pattern: &str,
+    ) -> Vec<&'a TargetBookmark> {
+        let mut bookmark_ids: Option<BTreeSet<&str>> = None;
+
+        for pattern_token in tokenize(pattern) {
+            let ids = self
+                .tokens
+                .iter()
+                .filter(|(token, _)| token.contains(&pattern_token))
+                .flat_map(|(_, ids)| ids.iter().map(|id| id.as_str()))
+                .collect::<BTreeSet<_>>();
+
+            bookmark_ids = Some(match bookmark_ids {
+                Some(bookmark_ids) => bookmark_ids.intersection(&ids).copied().collect(),
+                None => ids,
+            });
+        }
+
+        bookmarks
+            .values_sorted()
+            .into_iter()
+            .filter(|bookmark| match self.bookmarks.get(bookmark.id()) {
+                Some(last_cached) if *last_cached == bookmark.last_cached() => bookmark_ids
+                    .as_ref()
+                    .map_or(true, |ids| ids.contains(bookmark.id())),
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+/// Split the content by whitespace into lowercased tokens.
+fn tokenize(content: &str) -> impl Iterator<Item = String> + '_ {
+    content.split_whitespace().map(|token| token.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use url::Url;
+
+    #[test]
+    fn test_index_candidates() {
+        let now = Utc::now();
+        let url1 = Url::parse("https://url1.com").unwrap();
+        let url2 = Url::parse("https://url2.com").unwrap();
+        let url3 = Url::parse("https://url3.com").unwrap();
+        let mut bookmarks = TargetBookmarks::default();
+        let mut index = Index::new(CacheMode::Text);
+
+        for (url, content) in [
+            (&url1, "Reed-Solomon code"),
+            (&url2, "Polynomial interpolation"),
+        ] {
+            let mut bookmark = TargetBookmark::new(url.clone(), now);
+            bookmark.set_last_cached(now);
+            index.insert(&bookmark, content);
+            bookmarks.insert(bookmark);
+        }
+
+        // Bookmark 3 is not indexed.
+        bookmarks.insert(TargetBookmark::new(url3.clone(), now));
+
+        let candidates = index
+            .candidates(&bookmarks, "solomon")
+            .into_iter()
+            .map(|bookmark| bookmark.url().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(candidates, vec![url1.clone(), url3.clone()]);
+
+        let candidates = index
+            .candidates(&bookmarks, "Polynomial code")
+            .into_iter()
+            .map(|bookmark| bookmark.url().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(candidates, vec![url3.clone()]);
+
+        // Bookmark 2 was cached again after indexing.
+        bookmarks
+            .get_mut(&url2)
+            .unwrap()
+            .set_last_cached(now + chrono::Duration::seconds(1));
+
+        let candidates = index
+            .candidates(&bookmarks, "solomon")
+            .into_iter()
+            .map(|bookmark| bookmark.url().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(candidates, vec![url1, url2, url3]);
+    }
+}
@@ -30,6 +30,8 @@ mod cache;
 mod client;
 /// Available commands.
 pub mod cmd;
+/// Colorization of the output.
+mod color;
 /// The configuration used in Bogrep.
 mod config;
 /// A reference-counted DOM.
@@ -38,11 +40,17 @@ mod dom;
 pub mod errors;
 /// Helper functions to work with HTML.
 pub mod html;
+/// An inverted index of the cached files.
+mod index;
 /// Helper function to work with JSON.
 pub mod json;
 /// Initialize a simple logger based on the verbosity level (or the `RUST_LOG`
 /// environment variable).
 mod logger;
+/// A manifest of the cached files.
+mod manifest;
+/// The rules of `robots.txt` files.
+mod robots;
 /// The settings used in Bogrep.
 mod settings;
 /// Utilities used in testing.
@@ -52,15 +60,20 @@ pub mod utils;
 
 pub use args::{Args, ConfigArgs, FetchArgs, Subcommands};
 pub use bookmark_reader::{
-    ChromiumReader, FirefoxReader, ReadBookmark, SafariReader, SimpleReader, TargetReaderWriter,
+    ChromiumReader, FirefoxReader, PocketReader, ReadBookmark, SafariReader, SimpleReader,
+    TargetReaderWriter,
 };
 pub use bookmarks::{
-    Action, BookmarkManager, BookmarkService, JsonBookmark, JsonBookmarks, RunMode, ServiceConfig,
-    ServiceReport, Source, SourceBookmark, SourceBookmarks, SourceType, Status, TargetBookmark,
-    TargetBookmarkBuilder, TargetBookmarks, UnderlyingType,
+    Action, BookmarkManager, BookmarkService, FetchStrategy, JsonBookmark, JsonBookmarks,
+    ProcessReport, RunMode, ServiceConfig, ServiceReport, Source, SourceBookmark, SourceBookmarks,
+    SourceFolder, SourceFormat, SourceType, Status, TargetBookmark, TargetBookmarkBuilder,
+    TargetBookmarks, UnderlyingType, UrlFilter,
 };
 pub use cache::{Cache, CacheMode, Caching, MockCache};
-pub use client::{Client, Fetch, MockClient};
+pub use client::{CacheValidators, Client, DirClient, Fetch, FetchedWebsite, MockClient};
+pub use color::ColorChoice;
 pub use config::Config;
-pub use logger::Logger;
-pub use settings::Settings;
+pub use index::Index;
+pub use logger::{LogFormat, Logger};
+pub use manifest::{Manifest, ManifestEntry, ManifestMismatch};
+pub use settings::{OversizedContent, Settings};
@@ -1,9 +1,24 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
 use env_logger::{Builder, Env};
+use log::Record;
+use serde_json::json;
+use std::io::Write;
+
+/// The output format of the log records.
+#[derive(Debug, Default, Clone, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable log records.
+    #[default]
+    Text,
+    /// Structured log records with one JSON object per line.
+    Json,
+}
 
 pub struct Logger;
 
 impl Logger {
-    pub fn init(verbosity: u8) {
+    pub fn init(verbosity: u8, log_format: &LogFormat) {
         let log_filter = match verbosity {
             0 => "bogrep=info",
             1 => "bogrep=debug,info",
@@ -11,6 +26,58 @@ impl Logger {
         };
 
         // Default to INFO level logs if RUST_LOG is not set.
-        Builder::from_env(Env::default().default_filter_or(log_filter)).init();
+        let mut builder = Builder::from_env(Env::default().default_filter_or(log_filter));
+
+        if log_format == &LogFormat::Json {
+            builder
+                .format(|buf, record| writeln!(buf, "{}", Self::format_json(record, Utc::now())));
+        }
+
+        builder.init();
+    }
+
+    /// Format a log record as a single line of JSON.
+    fn format_json(record: &Record, timestamp: DateTime<Utc>) -> String {
+        json!({
+            "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": record.args().to_string(),
+        })
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+    use serde_json::Value;
+
+    #[test]
+    fn test_format_json() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-01-01T12:00:00.000Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let record = Record::builder()
+            .args(format_args!("Fetch bookmark: \"https://url1.com\""))
+            .level(Level::Debug)
+            .target("bogrep::client")
+            .build();
+
+        let line = Logger::format_json(&record, timestamp);
+        assert!(!line.contains('\n'));
+
+        let res = serde_json::from_str::<Value>(&line);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            res.unwrap(),
+            json!({
+                "timestamp": "2024-01-01T12:00:00.000Z",
+                "level": "DEBUG",
+                "target": "bogrep::client",
+                "message": "Fetch bookmark: \"https://url1.com\"",
+            })
+        );
     }
 }
@@ -5,7 +5,8 @@ use clap::Parser;
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let args = Args::parse();
-    Logger::init(args.verbose);
+    Logger::init(args.verbose, &args.log_format);
+    args.color.init();
     let config = Config::init()?;
 
     run_app(args, config).await?;
@@ -17,16 +18,26 @@ async fn run_app(args: Args, config: Config) -> Result<(), anyhow::Error> {
     if let Some(subcommands) = args.subcommands {
         match subcommands {
             Subcommands::Init(args) => cmd::init(config, args)?,
-            Subcommands::Config(args) => cmd::configure(config, args)?,
+            Subcommands::Config(args) => cmd::configure(config, *args)?,
             Subcommands::Import(args) => cmd::import(config, args).await?,
             Subcommands::Sync(args) => cmd::sync(&config, &args).await?,
             Subcommands::Fetch(args) => cmd::fetch(&config, &args).await?,
             Subcommands::Clean(args) => cmd::clean(&config, &args).await?,
             Subcommands::Add(args) => cmd::add(config, args).await?,
             Subcommands::Remove(args) => cmd::remove(config, args).await?,
+            Subcommands::Verify(args) => cmd::verify(&config, &args)?,
+            Subcommands::Reindex(args) => cmd::reindex(&config, &args).await?,
+            Subcommands::Index(args) => cmd::index(&config, &args)?,
+            Subcommands::Cat(args) => cmd::cat(&config, &args)?,
+            Subcommands::List(args) => cmd::list(&config, &args)?,
+            Subcommands::Watch(args) => cmd::watch(&config, &args).await?,
+            Subcommands::Merge(args) => cmd::merge(&config, &args)?,
+            Subcommands::Backup(args) => cmd::backup(&config, &args)?,
+            Subcommands::Restore(args) => cmd::restore(&config, &args)?,
+            Subcommands::Prune(args) => cmd::prune(&config, &args).await?,
         }
-    } else if let Some(pattern) = &args.pattern {
-        cmd::search(pattern, &config, &args)?;
+    } else if !args.patterns.is_empty() || args.exclude_cached {
+        cmd::search(&args.patterns, &config, &args)?;
     } else {
         return Err(anyhow!("Missing search pattern: `bogrep <pattern>`"));
     }
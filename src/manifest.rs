@@ -0,0 +1,148 @@
+use crate::{errors::BogrepError, json, utils, CacheMode};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::Path,
+};
+
+/// A cached file of a bookmark in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// The filename of the cached file in the cache directory.
+    pub filename: String,
+    /// The cache mode of the cached file.
+    pub cache_mode: CacheMode,
+    /// The size of the cached file in bytes.
+    pub size: u64,
+    /// The hex-encoded SHA-256 hash of the cached file.
+    pub sha256: String,
+}
+
+impl ManifestEntry {
+    pub fn new(filename: String, cache_mode: CacheMode, content: &[u8]) -> Self {
+        Self {
+            filename,
+            cache_mode,
+            size: content.len() as u64,
+            sha256: utils::hash_content(content),
+        }
+    }
+}
+
+/// The differences between a manifest and the files in the cache directory.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ManifestMismatch {
+    /// The filenames of the entries whose file is missing, or whose size or
+    /// hash doesn't match the file.
+    pub missing: Vec<String>,
+    /// The filenames of the cached files without an entry.
+    pub extra: Vec<String>,
+}
+
+impl ManifestMismatch {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// A machine-readable manifest which maps the bookmark ids to their cached
+/// files.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub bookmarks: BTreeMap<String, Vec<ManifestEntry>>,
+}
+
+impl Manifest {
+    pub fn insert(&mut self, bookmark_id: &str, entries: Vec<ManifestEntry>) {
+        if !entries.is_empty() {
+            self.bookmarks.insert(bookmark_id.to_owned(), entries);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bookmarks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Read the manifest from a json file.
+    pub fn read(manifest_path: &Path) -> Result<Self, BogrepError> {
+        let manifest = utils::read_file(manifest_path)?;
+        json::deserialize(&manifest)
+    }
+
+    /// Write the manifest as json file.
+    pub fn write(&self, manifest_path: &Path) -> Result<(), BogrepError> {
+        let manifest = json::serialize(self)?;
+        let manifest = String::from_utf8(manifest)?;
+        utils::write_file(manifest_path, manifest)
+    }
+
+    /// Compare the entries of the manifest with the cached files in the cache
+    /// directory.
+    pub fn verify(&self, cache_path: &Path) -> Result<ManifestMismatch, BogrepError> {
+        let mut mismatch = ManifestMismatch::default();
+        let mut filenames = HashSet::new();
+
+        for entry in self.bookmarks.values().flatten() {
+            filenames.insert(entry.filename.as_str());
+            let path = cache_path.join(&entry.filename);
+
+            let is_matching = path.is_file()
+                && &ManifestEntry::new(
+                    entry.filename.clone(),
+                    entry.cache_mode.clone(),
+                    &utils::read_file(&path)?,
+                ) == entry;
+
+            if !is_matching {
+                mismatch.missing.push(entry.filename.clone());
+            }
+        }
+
+        if cache_path.is_dir() {
+            for dir_entry in fs::read_dir(cache_path).map_err(BogrepError::ReadFile)? {
+                let path = dir_entry.map_err(BogrepError::ReadFile)?.path();
+                // Ignore files which are not cached bookmarks, e.g. `.DS_Store`.
+                let is_cache_file = path
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .and_then(CacheMode::from_extension)
+                    .is_some();
+
+                if let Some(filename) = path.file_name().and_then(|filename| filename.to_str()) {
+                    if path.is_file() && is_cache_file && !filenames.contains(filename) {
+                        mismatch.extra.push(filename.to_owned());
+                    }
+                }
+            }
+        }
+
+        mismatch.missing.sort();
+        mismatch.extra.sort();
+
+        Ok(mismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_entry() {
+        let entry = ManifestEntry::new(
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7.txt".to_owned(),
+            CacheMode::Text,
+            b"Test content",
+        );
+        assert_eq!(entry.size, 12);
+        assert_eq!(
+            entry.sha256,
+            "9d9595c5d94fb65b824f56e9999527dba9542481580d69feb89056aabaa0aa87"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use url::Url;
+
+/// The user agent used to select the rules of a `robots.txt` file.
+const USER_AGENT: &str = "bogrep";
+
+/// The rules of a `robots.txt` file which apply to Bogrep.
+///
+/// The rules of groups for the user agent `bogrep` are used if present, and
+/// the rules of groups for all user agents (`*`) otherwise.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Robots {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+impl Robots {
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<Group> = vec![];
+        let mut is_new_group = true;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    // Consecutive user agents share the same group.
+                    if is_new_group {
+                        groups.push(Group::default());
+                        is_new_group = false;
+                    }
+
+                    if let Some(group) = groups.last_mut() {
+                        group.user_agents.push(value.to_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    is_new_group = true;
+
+                    // An empty `Disallow` allows everything.
+                    if value.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_owned(),
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let applies_to = |user_agent: &str| {
+            groups
+                .iter()
+                .filter(|group| group.user_agents.iter().any(|agent| agent == user_agent))
+                .flat_map(|group| group.rules.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+        let mut rules = applies_to(USER_AGENT);
+
+        if rules.is_empty() {
+            rules = applies_to("*");
+        }
+
+        Self { rules }
+    }
+
+    /// Check if the url is allowed. The most specific, i.e. longest, matching
+    /// rule wins, and `Allow` wins over `Disallow` for rules of the same
+    /// length.
+    pub fn is_allowed(&self, url: &Url) -> bool {
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_owned(),
+        };
+
+        self.rules
+            .iter()
+            .filter(|rule| matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map_or(true, |rule| rule.allow)
+    }
+}
+
+/// Match a path against a pattern, which may contain the wildcard `*` and end
+/// with the anchor `$`.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, is_anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return !is_anchored || rest.is_empty();
+    }
+
+    for (index, part) in parts.iter().enumerate() {
+        if is_anchored && index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_allowed(robots: &Robots, path: &str) -> bool {
+        robots.is_allowed(&Url::parse(&format!("https://url.com{path}")).unwrap())
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("/private", "/private"));
+        assert!(matches("/private", "/private/page"));
+        assert!(!matches("/private", "/public"));
+        assert!(matches("/*.pdf", "/docs/file.pdf"));
+        assert!(matches("/*.pdf$", "/docs/file.pdf"));
+        assert!(!matches("/*.pdf$", "/docs/file.pdf?page=2"));
+        assert!(matches("/page$", "/page"));
+        assert!(!matches("/page$", "/page/2"));
+        assert!(matches("/*/edit", "/posts/1/edit"));
+    }
+
+    #[test]
+    fn test_parse_robots() {
+        let content = r#"
+# Comment
+User-agent: *
+Disallow: /private
+Allow: /private/public
+Disallow: /*.pdf$
+
+User-agent: other
+Disallow: /
+"#;
+        let robots = Robots::parse(content);
+
+        assert!(is_allowed(&robots, "/"));
+        assert!(is_allowed(&robots, "/page"));
+        assert!(!is_allowed(&robots, "/private"));
+        assert!(!is_allowed(&robots, "/private/page"));
+        assert!(is_allowed(&robots, "/private/public/page"));
+        assert!(!is_allowed(&robots, "/docs/file.pdf"));
+        assert!(is_allowed(&robots, "/docs/file.pdf?page=2"));
+    }
+
+    #[test]
+    fn test_parse_robots_user_agent() {
+        let content = r#"
+User-agent: *
+Disallow: /
+
+User-agent: Googlebot
+User-agent: bogrep
+Disallow: /private
+Disallow:
+"#;
+        let robots = Robots::parse(content);
+
+        assert!(is_allowed(&robots, "/page"));
+        assert!(!is_allowed(&robots, "/private"));
+
+        let robots = Robots::parse("User-agent: other\nDisallow: /\n");
+        assert!(is_allowed(&robots, "/page"));
+
+        let robots = Robots::parse("");
+        assert!(is_allowed(&robots, "/page"));
+    }
+}
@@ -1,18 +1,30 @@
 use crate::{
     bookmarks::{RawSource, SUPPORTED_UNDERLYING_DOMAINS},
     cache::CacheMode,
-    json,
+    html::TextExtractor,
+    json, utils,
 };
 use anyhow::{anyhow, Context};
+use clap::ValueEnum;
 use log::debug;
-use reqwest::Url;
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Url,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::{
+    collections::BTreeMap,
     fs::File,
     io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
+/// The version of the settings format.
+///
+/// Increment the version if a migration of older settings is required.
+pub const SETTINGS_VERSION: u32 = 2;
+
 /// The default for `Settungs::max_open_files`.
 const MAX_OPEN_FILES_DEFAULT: u64 = 500;
 
@@ -31,6 +43,21 @@ const MAX_IDLE_CONNECTIONS_PER_HOST: usize = 1;
 /// The  default for `Setting::idle_connections_timeout`.
 const IDLE_CONNECTIONS_TIMEOUT: u64 = 5_000;
 
+/// The default for `Settings::underlying_depth`.
+pub(crate) const UNDERLYING_DEPTH_DEFAULT: usize = 1;
+
+/// The behavior for websites whose content exceeds
+/// `Settings::max_content_size_bytes`.
+#[derive(Debug, ValueEnum, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizedContent {
+    /// Skip caching the website.
+    #[default]
+    Skip,
+    /// Truncate the content to the maximum size before caching.
+    Truncate,
+}
+
 /// Optional settings configured via `ConfigArgs`.
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct SettingsArgs {
@@ -58,6 +85,35 @@ pub struct SettingsArgs {
     pub max_idle_connections_per_host: Option<usize>,
     /// The timeout for idle connections to be kept alive in milliseconds.
     pub idle_connections_timeout: Option<u64>,
+    /// The maximum size of the content of a website in bytes.
+    pub max_content_size_bytes: Option<u64>,
+    /// The behavior for websites whose content exceeds the maximum size.
+    pub oversized_content: Option<OversizedContent>,
+    /// The strategy to extract the text of websites.
+    pub extractor: Option<TextExtractor>,
+    /// The path to the cached websites.
+    pub cache_path: Option<PathBuf>,
+    /// Additional HTTP headers as pairs of domain and header.
+    pub headers: Vec<(String, String)>,
+    /// The maximum number of levels to resolve underlyings of underlyings.
+    pub underlying_depth: Option<usize>,
+    /// The folders to be imported for a configured source, which is given by
+    /// its path, or is the most recently configured source otherwise.
+    pub import_folders: Option<(Option<PathBuf>, Vec<String>)>,
+    /// Skip urls which are disallowed by the `robots.txt` of their host.
+    pub respect_robots: Option<bool>,
+    /// The `Accept-Language` header to request a language version of
+    /// websites.
+    pub accept_language: Option<String>,
+    /// The permissions of the cached files on Unix.
+    pub cache_file_mode: Option<u32>,
+    /// The grace period in milliseconds to keep removed bookmarks.
+    pub removal_grace_period: Option<u64>,
+    /// The query parameters to strip from imported urls, or the entire query
+    /// if empty.
+    pub strip_query: Option<Vec<String>>,
+    /// The query parameters to keep when stripping the query.
+    pub include_query: Vec<String>,
 }
 
 impl SettingsArgs {
@@ -73,6 +129,19 @@ impl SettingsArgs {
         request_throttling: Option<u64>,
         max_idle_connections_per_host: Option<usize>,
         idle_connections_timeout: Option<u64>,
+        max_content_size_bytes: Option<u64>,
+        oversized_content: Option<OversizedContent>,
+        extractor: Option<TextExtractor>,
+        cache_path: Option<PathBuf>,
+        headers: Vec<(String, String)>,
+        underlying_depth: Option<usize>,
+        import_folders: Option<(Option<PathBuf>, Vec<String>)>,
+        respect_robots: Option<bool>,
+        accept_language: Option<String>,
+        cache_file_mode: Option<u32>,
+        removal_grace_period: Option<u64>,
+        strip_query: Option<Vec<String>>,
+        include_query: Vec<String>,
     ) -> Self {
         Self {
             source,
@@ -85,6 +154,19 @@ impl SettingsArgs {
             request_throttling,
             max_idle_connections_per_host,
             idle_connections_timeout,
+            max_content_size_bytes,
+            oversized_content,
+            extractor,
+            cache_path,
+            headers,
+            underlying_depth,
+            import_folders,
+            respect_robots,
+            accept_language,
+            cache_file_mode,
+            removal_grace_period,
+            strip_query,
+            include_query,
         }
     }
 }
@@ -92,6 +174,10 @@ impl SettingsArgs {
 /// Describes the settings used in Bogrep.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Settings {
+    /// The version of the settings format.
+    ///
+    /// Settings without a version are migrated from version 1.
+    pub version: u32,
     /// The paths to the configured bookmark files.
     ///
     /// Source could be Firefox or Chrome.
@@ -143,11 +229,106 @@ pub struct Settings {
     /// `idle_connections_timeout` to fix "Too many open files" and DNS errors
     /// (rate limit for DNS server).
     pub idle_connections_timeout: u64,
+    /// The maximum size of the content of a website in bytes.
+    ///
+    /// Websites whose content exceeds the maximum size are skipped or
+    /// truncated, depending on `oversized_content`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_content_size_bytes: Option<u64>,
+    /// The behavior for websites whose content exceeds
+    /// `max_content_size_bytes`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub oversized_content: OversizedContent,
+    /// The strategy to extract the text of websites if the cache mode is
+    /// `text`.
+    ///
+    /// Choose `dom-text` or `markdown` if readability mangles the content of
+    /// websites.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub extractor: TextExtractor,
+    /// The path to the cached websites.
+    ///
+    /// Defaults to the `cache` directory in the configuration path. Configure
+    /// a directory on a larger disk for huge bookmark collections.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_path: Option<PathBuf>,
+    /// Additional HTTP headers by domain, e.g. a `Cookie` header to fetch
+    /// websites behind a login.
+    ///
+    /// The headers are only sent to the domain and its subdomains.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, BTreeMap<String, String>>,
+    /// The maximum number of levels to resolve underlyings of underlyings.
+    ///
+    /// An underlying can itself be a Hacker News or Reddit post. Defaults to
+    /// 1, i.e. only the underlyings of the bookmarks are fetched.
+    #[serde(
+        default = "default_underlying_depth",
+        skip_serializing_if = "is_default_underlying_depth"
+    )]
+    pub underlying_depth: usize,
+    /// Skip urls which are disallowed by the `robots.txt` of their host.
+    ///
+    /// Defaults to `false`, i.e. `robots.txt` is ignored.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub respect_robots: bool,
+    /// The `Accept-Language` header to request a language version of
+    /// websites, e.g. `de-DE,de;q=0.9`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_language: Option<String>,
+    /// Skip websites whose extracted text is shorter than the given number
+    /// of characters, e.g. cookie banners or consent pages.
+    ///
+    /// Defaults to `None`, i.e. websites of any length are cached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_text_length: Option<usize>,
+    /// The permissions of the cached files on Unix, e.g. `384` for `0o600`
+    /// to make the cached files readable by the owner only.
+    ///
+    /// Defaults to `None`, i.e. the default permissions of the operating
+    /// system.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_file_mode: Option<u32>,
+    /// The grace period in milliseconds to keep bookmarks which were removed
+    /// from the sources, together with their cache, until they are cleaned
+    /// up via `bogrep clean --expired`.
+    ///
+    /// Defaults to `None`, i.e. removed bookmarks are removed immediately.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removal_grace_period: Option<u64>,
+    /// The query parameters which are stripped from the urls of imported
+    /// bookmarks, e.g. tracking parameters like `utm_*` or `fbclid`. A
+    /// trailing `*` matches any suffix, and `*` strips the entire query.
+    ///
+    /// Defaults to an empty list, i.e. the urls are imported as they are.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strip_query: Vec<String>,
+    /// The query parameters which are kept when stripping the query, e.g.
+    /// `id`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_query: Vec<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn default_underlying_depth() -> usize {
+    UNDERLYING_DEPTH_DEFAULT
+}
+
+fn is_default_underlying_depth(underlying_depth: &usize) -> bool {
+    *underlying_depth == UNDERLYING_DEPTH_DEFAULT
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             sources: Vec::new(),
             ignored_urls: Vec::new(),
             underlying_urls: Vec::new(),
@@ -158,6 +339,19 @@ impl Default for Settings {
             request_throttling: REQUEST_THROTTLING_DEFAULT,
             max_idle_connections_per_host: MAX_IDLE_CONNECTIONS_PER_HOST,
             idle_connections_timeout: IDLE_CONNECTIONS_TIMEOUT,
+            max_content_size_bytes: None,
+            oversized_content: OversizedContent::default(),
+            extractor: TextExtractor::default(),
+            cache_path: None,
+            headers: BTreeMap::new(),
+            underlying_depth: UNDERLYING_DEPTH_DEFAULT,
+            respect_robots: false,
+            accept_language: None,
+            min_text_length: None,
+            cache_file_mode: None,
+            removal_grace_period: None,
+            strip_query: vec![],
+            include_query: vec![],
         }
     }
 }
@@ -175,8 +369,22 @@ impl Settings {
         request_throttling: u64,
         max_idle_connections_per_host: usize,
         idle_connections_timeout: u64,
+        max_content_size_bytes: Option<u64>,
+        oversized_content: OversizedContent,
+        extractor: TextExtractor,
+        cache_path: Option<PathBuf>,
+        headers: BTreeMap<String, BTreeMap<String, String>>,
+        underlying_depth: usize,
+        respect_robots: bool,
+        accept_language: Option<String>,
+        min_text_length: Option<usize>,
+        cache_file_mode: Option<u32>,
+        removal_grace_period: Option<u64>,
+        strip_query: Vec<String>,
+        include_query: Vec<String>,
     ) -> Self {
         Self {
+            version: SETTINGS_VERSION,
             sources,
             ignored_urls,
             underlying_urls,
@@ -187,6 +395,19 @@ impl Settings {
             request_throttling,
             max_idle_connections_per_host,
             idle_connections_timeout,
+            max_content_size_bytes,
+            oversized_content,
+            extractor,
+            cache_path,
+            headers,
+            underlying_depth,
+            respect_robots,
+            accept_language,
+            min_text_length,
+            cache_file_mode,
+            removal_grace_period,
+            strip_query,
+            include_query,
         }
     }
 
@@ -198,7 +419,16 @@ impl Settings {
             settings_file
                 .read_to_end(&mut buf)
                 .context("Can't read `settings.json` file")?;
-            let settings = json::deserialize::<Settings>(&buf)?;
+            let mut value = json::deserialize::<Value>(&buf)?;
+            let is_migrated = Self::migrate(&mut value)?;
+            let settings = serde_json::from_value::<Settings>(value)
+                .context("Can't deserialize `settings.json` file")?;
+
+            if is_migrated {
+                let settings_json = json::serialize(&settings)?;
+                utils::write_file_atomically(settings_path, &settings_json)?;
+            }
+
             Ok(settings)
         } else {
             debug!("Create settings file at {}", settings_path.display());
@@ -215,6 +445,61 @@ impl Settings {
         }
     }
 
+    /// Upgrade settings of an older version to the current version, and return
+    /// `true` if the settings were migrated.
+    pub fn migrate(value: &mut Value) -> Result<bool, anyhow::Error> {
+        let Value::Object(settings) = value else {
+            return Err(anyhow!("Invalid settings: expected a json object"));
+        };
+        let version = match settings.get("version") {
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or(anyhow!("Invalid settings version: {version}"))?,
+            None => 1,
+        };
+
+        if version > SETTINGS_VERSION {
+            return Err(anyhow!(
+                "Settings version {version} is not supported, expected version {SETTINGS_VERSION} or lower; please update bogrep"
+            ));
+        }
+
+        if version == SETTINGS_VERSION {
+            return Ok(false);
+        }
+
+        debug!("Migrate settings from version {version} to version {SETTINGS_VERSION}");
+
+        if version < 2 {
+            Self::migrate_v1(settings)?;
+        }
+
+        settings.insert("version".to_owned(), Value::from(SETTINGS_VERSION));
+
+        Ok(true)
+    }
+
+    /// Migrate settings without a version.
+    fn migrate_v1(settings: &mut Map<String, Value>) -> Result<(), anyhow::Error> {
+        let Value::Object(defaults) = serde_json::to_value(Settings::default())? else {
+            return Err(anyhow!("Invalid default settings"));
+        };
+
+        // Settings which were added later are missing in older settings files.
+        for (key, value) in defaults {
+            settings.entry(key).or_insert(value);
+        }
+
+        // The markdown cache mode was removed in favor of converting the html
+        // cache to markdown.
+        if settings.get("cache_mode").and_then(Value::as_str) == Some("markdown") {
+            settings.insert("cache_mode".to_owned(), Value::from("html"));
+        }
+
+        Ok(())
+    }
+
     pub fn add_ignored_url(&mut self, url: &str) -> Result<(), anyhow::Error> {
         let url = Url::parse(url).context(format!("Invalid url {url}"))?;
         let normalized_url = url.to_string();
@@ -250,9 +535,35 @@ impl Settings {
         Ok(())
     }
 
-    pub fn set_source(&mut self, source: RawSource) -> Result<(), anyhow::Error> {
+    /// Add a header in the format `Name: value` for the given domain.
+    pub fn add_header(&mut self, domain: &str, header: &str) -> Result<(), anyhow::Error> {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or(anyhow!("Invalid header `{header}`: expected `Name: value`"))?;
+        let name = name.trim();
+        let value = value.trim();
+        HeaderName::from_bytes(name.as_bytes()).context(format!("Invalid header name `{name}`"))?;
+        HeaderValue::from_str(value).context(format!("Invalid header value for `{name}`"))?;
+        let domain = domain.trim().to_lowercase();
+
+        if domain.is_empty() {
+            return Err(anyhow!("Missing domain for header `{name}`"));
+        }
+
+        debug!("Add header `{name}` for domain {domain}");
+        self.headers
+            .entry(domain)
+            .or_default()
+            .insert(name.to_owned(), value.to_owned());
+
+        Ok(())
+    }
+
+    pub fn set_source(&mut self, mut source: RawSource) -> Result<(), anyhow::Error> {
         debug!("Set source to {}", source.path.display());
 
+        Self::trim_folders(&source.path, &mut source.folders)?;
+
         if let Some(s) = self.sources.iter_mut().find(|s| s.path == source.path) {
             *s = source;
         } else {
@@ -262,6 +573,50 @@ impl Settings {
         Ok(())
     }
 
+    /// Set the folders of a configured source, or of the most recently
+    /// configured source if no path is given.
+    pub fn set_source_folders(
+        &mut self,
+        path: Option<&Path>,
+        mut folders: Vec<String>,
+    ) -> Result<(), anyhow::Error> {
+        let source = match path {
+            Some(path) => self
+                .sources
+                .iter_mut()
+                .find(|source| source.path == path)
+                .ok_or(anyhow!(
+                    "Invalid source: {} is not configured",
+                    path.display()
+                ))?,
+            None => self
+                .sources
+                .last_mut()
+                .ok_or(anyhow!("Invalid source: no source is configured"))?,
+        };
+        debug!("Set folders for source {}", source.path.display());
+
+        Self::trim_folders(&source.path, &mut folders)?;
+        source.folders = folders;
+
+        Ok(())
+    }
+
+    fn trim_folders(path: &Path, folders: &mut [String]) -> Result<(), anyhow::Error> {
+        for folder in folders.iter_mut() {
+            *folder = folder.trim().to_owned();
+
+            if folder.is_empty() {
+                return Err(anyhow!(
+                    "Invalid folder for source {}: folder names must not be empty",
+                    path.display()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn set_cache_mode(&mut self, cache_mode: CacheMode) {
         debug!("Set cache mode to {}", cache_mode);
         self.cache_mode = cache_mode;
@@ -282,6 +637,60 @@ impl Settings {
         self.request_throttling = request_throttling;
     }
 
+    pub fn set_respect_robots(&mut self, respect_robots: bool) {
+        debug!("Set `respect_robots` to {respect_robots}");
+        self.respect_robots = respect_robots;
+    }
+
+    /// Set the `Accept-Language` header, or unset it if the given language
+    /// is empty.
+    pub fn set_accept_language(&mut self, accept_language: &str) -> Result<(), anyhow::Error> {
+        let accept_language = accept_language.trim();
+
+        if accept_language.is_empty() {
+            debug!("Unset `accept_language`");
+            self.accept_language = None;
+        } else {
+            HeaderValue::from_str(accept_language)
+                .context(format!("Invalid accept language `{accept_language}`"))?;
+            debug!("Set `accept_language` to {accept_language}");
+            self.accept_language = Some(accept_language.to_owned());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_cache_file_mode(&mut self, cache_file_mode: u32) {
+        debug!("Set `cache_file_mode` to {cache_file_mode:o}");
+        self.cache_file_mode = Some(cache_file_mode);
+    }
+
+    pub fn set_removal_grace_period(&mut self, removal_grace_period: u64) {
+        debug!("Set `removal_grace_period` to {removal_grace_period}");
+        self.removal_grace_period = Some(removal_grace_period);
+    }
+
+    /// Strip the given query parameters from imported urls, or the entire
+    /// query if no query parameters are given.
+    pub fn set_strip_query(&mut self, strip_query: &[String]) {
+        debug!("Set `strip_query` to {strip_query:?}");
+        self.strip_query = if strip_query.is_empty() {
+            vec![String::from("*")]
+        } else {
+            strip_query.to_vec()
+        };
+    }
+
+    pub fn set_include_query(&mut self, include_query: &[String]) {
+        debug!("Set `include_query` to {include_query:?}");
+        self.include_query = include_query.to_vec();
+    }
+
+    pub fn set_underlying_depth(&mut self, underlying_depth: usize) {
+        debug!("Set `underlying_depth` to {underlying_depth}");
+        self.underlying_depth = underlying_depth;
+    }
+
     pub fn set_max_concurrent_requests(&mut self, max_concurrent_requests: usize) {
         debug!("Set `max_concurrent_requests` to {max_concurrent_requests}");
         self.max_concurrent_requests = max_concurrent_requests;
@@ -296,13 +705,121 @@ impl Settings {
         debug!("Set `idle_connections_timeout` to {idle_connections_timeout}");
         self.idle_connections_timeout = idle_connections_timeout;
     }
+
+    pub fn set_max_content_size_bytes(&mut self, max_content_size_bytes: u64) {
+        debug!("Set `max_content_size_bytes` to {max_content_size_bytes}");
+        self.max_content_size_bytes = Some(max_content_size_bytes);
+    }
+
+    pub fn set_extractor(&mut self, extractor: TextExtractor) {
+        debug!("Set `extractor` to {extractor:?}");
+        self.extractor = extractor;
+    }
+
+    pub fn set_cache_path(&mut self, cache_path: PathBuf) {
+        debug!("Set `cache_path` to {}", cache_path.display());
+        self.cache_path = Some(cache_path);
+    }
+
+    pub fn set_oversized_content(&mut self, oversized_content: OversizedContent) {
+        debug!("Set `oversized_content` to {oversized_content:?}");
+        self.oversized_content = oversized_content;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bookmarks::{HACKER_NEWS_DOMAINS, REDDIT_DOMAINS};
+    use crate::{
+        bookmarks::{HACKER_NEWS_DOMAINS, REDDIT_DOMAINS},
+        SourceFormat,
+    };
     use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    /// Settings without a version, as written by bogrep v0.3.
+    const SETTINGS_V1: &str = r#"{
+    "sources": [
+        {
+            "source": "path/to/source",
+            "folders": ["dev"]
+        }
+    ],
+    "ignored_urls": ["https://youtube.com/"],
+    "underlying_urls": [],
+    "cache_mode": "markdown",
+    "max_open_files": 100,
+    "max_concurrent_requests": 100,
+    "request_timeout": 60000,
+    "request_throttling": 3000
+}"#;
+
+    #[test]
+    fn test_migrate_v1() {
+        let mut value = json::deserialize::<Value>(SETTINGS_V1.as_bytes()).unwrap();
+
+        let res = Settings::migrate(&mut value);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(res.unwrap());
+
+        let settings = serde_json::from_value::<Settings>(value).unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                sources: vec![RawSource::new("path/to/source", vec!["dev".to_owned()])],
+                ignored_urls: vec!["https://youtube.com/".to_owned()],
+                cache_mode: CacheMode::Html,
+                max_open_files: 100,
+                max_concurrent_requests: 100,
+                ..Default::default()
+            }
+        );
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_current_version() {
+        let settings = Settings::default();
+        let mut value = serde_json::to_value(&settings).unwrap();
+
+        let res = Settings::migrate(&mut value);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(!res.unwrap());
+        assert_eq!(serde_json::from_value::<Settings>(value).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_migrate_unsupported_version() {
+        let mut value = serde_json::to_value(Settings::default()).unwrap();
+        value["version"] = Value::from(SETTINGS_VERSION + 1);
+
+        let res = Settings::migrate(&mut value);
+        assert!(res.is_err());
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .starts_with("Settings version 3 is not supported"));
+    }
+
+    #[test]
+    fn test_init_migrate() {
+        let temp_dir = tempdir().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        utils::write_file(&settings_path, SETTINGS_V1.to_owned()).unwrap();
+
+        let res = Settings::init(&settings_path);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        let settings = res.unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.cache_mode, CacheMode::Html);
+
+        // The migrated settings are written to disk.
+        let settings_json = utils::read_file(&settings_path).unwrap();
+        assert_eq!(
+            json::deserialize::<Settings>(&settings_json).unwrap(),
+            settings
+        );
+    }
 
     #[test]
     fn test_add_ignored_urls() {
@@ -324,6 +841,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_header() {
+        let mut settings = Settings::default();
+        let res = settings.add_header("Example.com", "Cookie: session=1; theme=dark");
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        let res = settings.add_header("example.com", "X-Api-Key:key");
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+
+        assert_eq!(
+            settings.headers,
+            BTreeMap::from_iter([(
+                "example.com".to_owned(),
+                BTreeMap::from_iter([
+                    ("Cookie".to_owned(), "session=1; theme=dark".to_owned()),
+                    ("X-Api-Key".to_owned(), "key".to_owned()),
+                ])
+            )])
+        );
+
+        assert!(settings.add_header("example.com", "Cookie").is_err());
+        assert!(settings
+            .add_header("example.com", "Invalid name: value")
+            .is_err());
+        assert!(settings.add_header("", "Cookie: session=1").is_err());
+    }
+
+    #[test]
+    fn test_set_accept_language() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.accept_language, None);
+
+        let res = settings.set_accept_language(" de-DE,de;q=0.9 ");
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(settings.accept_language.as_deref(), Some("de-DE,de;q=0.9"));
+
+        // The previous language is kept for an invalid header value.
+        let res = settings.set_accept_language("de\nDE");
+        assert!(res.is_err());
+        assert_eq!(settings.accept_language.as_deref(), Some("de-DE,de;q=0.9"));
+
+        let res = settings.set_accept_language("");
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(settings.accept_language, None);
+    }
+
     #[test]
     fn test_add_ignored_urls_duplicate() {
         let mut settings = Settings::default();
@@ -451,6 +1013,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_source_trim_folders() {
+        let mut settings = Settings::default();
+        let res = settings.set_source(RawSource::new(
+            PathBuf::from("path/to/source"),
+            vec![" dev".to_string(), "articles ".to_string()],
+        ));
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            settings.sources,
+            vec![RawSource::new(
+                PathBuf::from("path/to/source"),
+                vec!["dev".to_string(), "articles".to_string()],
+            )]
+        );
+    }
+
+    #[test]
+    fn test_set_source_empty_folder() {
+        let mut settings = Settings::default();
+
+        for folder in ["", "  "] {
+            let res = settings.set_source(RawSource::new(
+                PathBuf::from("path/to/source"),
+                vec!["dev".to_string(), folder.to_string()],
+            ));
+            assert!(res.is_err());
+            assert_eq!(
+                res.unwrap_err().to_string(),
+                "Invalid folder for source path/to/source: folder names must not be empty"
+            );
+        }
+
+        assert!(settings.sources.is_empty());
+    }
+
+    #[test]
+    fn test_set_source_folders() {
+        let mut settings = Settings::default();
+        let res = settings.set_source_folders(None, vec!["dev".to_string()]);
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid source: no source is configured"
+        );
+
+        let source1 = RawSource::new(PathBuf::from("path/to/source1"), vec![]);
+        let source2 = RawSource::new(PathBuf::from("path/to/source2"), vec![]);
+        settings.set_source(source1).unwrap();
+        settings
+            .set_source(source2.with_format(Some(SourceFormat::Txt)))
+            .unwrap();
+
+        // Without a path, the folders of the most recently configured source
+        // are replaced.
+        let res = settings.set_source_folders(None, vec![" articles".to_string()]);
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert!(settings.sources[0].folders.is_empty());
+        assert_eq!(settings.sources[1].folders, vec!["articles".to_string()]);
+        assert_eq!(settings.sources[1].format, Some(SourceFormat::Txt));
+
+        let res = settings.set_source_folders(
+            Some(Path::new("path/to/source1")),
+            vec!["dev".to_string(), "news".to_string()],
+        );
+        assert!(res.is_ok(), "{}", res.unwrap_err());
+        assert_eq!(
+            settings.sources[0].folders,
+            vec!["dev".to_string(), "news".to_string()]
+        );
+
+        let res = settings
+            .set_source_folders(Some(Path::new("path/to/source3")), vec!["dev".to_string()]);
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "Invalid source: path/to/source3 is not configured"
+        );
+    }
+
     #[test]
     fn test_set_cache_mode() {
         let mut settings = Settings::default();
@@ -157,6 +157,9 @@ pub mod tests {
 
                 let browser_dir = home_dir.join("Library/Application Support/Google/Chrome");
                 create_chromium_dirs(&browser_dir);
+
+                let browser_dir = home_dir.join("Library/Application Support/Microsoft Edge");
+                create_chromium_dirs(&browser_dir);
             }
             SourceOs::Windows => {
                 let browser_dir = home_dir.join("AppData\\Local\\Google\\Chrome\\User Data");
@@ -1,7 +1,9 @@
 use crate::{bookmark_reader::SourceOs, errors::BogrepError, json, Settings};
 use anyhow::anyhow;
-use log::debug;
+use log::{debug, warn};
+use sha2::{Digest, Sha256};
 use std::{
+    fmt::Write as _,
     fs::{self, File, OpenOptions},
     io::{Read, Write},
     path::Path,
@@ -13,6 +15,55 @@ pub fn convert_to_duration(millis: i64) -> Result<chrono::Duration, anyhow::Erro
     chrono::Duration::try_milliseconds(millis).ok_or(anyhow!("Can't convert i64 to Duration"))
 }
 
+/// Parse a date in the format `YYYY-MM-DD`.
+pub fn parse_date(date: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{date}': expected format YYYY-MM-DD"))
+}
+
+/// Parse a duration with a unit, e.g. `500ms`, `30s`, `5m`, `1h`, or `1d`.
+pub fn parse_duration(duration: &str) -> Result<std::time::Duration, String> {
+    let invalid_duration = || {
+        format!("Invalid duration '{duration}': expected a number followed by ms, s, m, h, or d")
+    };
+    let index = duration
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid_duration)?;
+    let (value, unit) = duration.split_at(index);
+    let value = value.parse::<u64>().map_err(|_| invalid_duration())?;
+    let millis = match unit {
+        "ms" => Some(value),
+        "s" => value.checked_mul(1_000),
+        "m" => value.checked_mul(60_000),
+        "h" => value.checked_mul(3_600_000),
+        "d" => value.checked_mul(86_400_000),
+        _ => None,
+    }
+    .ok_or_else(invalid_duration)?;
+
+    if millis == 0 {
+        return Err(format!(
+            "Invalid duration '{duration}': must be greater than zero"
+        ));
+    }
+
+    Ok(std::time::Duration::from_millis(millis))
+}
+
+/// Parse octal file permissions, e.g. `600` or `0o600`.
+pub fn parse_file_mode(file_mode: &str) -> Result<u32, String> {
+    let invalid_file_mode =
+        || format!("Invalid file mode '{file_mode}': expected octal permissions, e.g. 600");
+    let digits = file_mode.strip_prefix("0o").unwrap_or(file_mode);
+    let mode = u32::from_str_radix(digits, 8).map_err(|_| invalid_file_mode())?;
+
+    if digits.is_empty() || mode > 0o777 {
+        return Err(invalid_file_mode());
+    }
+
+    Ok(mode)
+}
+
 pub fn get_supported_os() -> Option<SourceOs> {
     let source_os = match std::env::consts::OS {
         "linux" => Some(SourceOs::Linux),
@@ -32,6 +83,118 @@ pub fn parse_urls(urls: &[String]) -> Result<Vec<Url>, BogrepError> {
     Ok(parsed_urls)
 }
 
+/// Read a newline-delimited list of URLs from a file.
+///
+/// Empty lines and lines starting with `#` are skipped, and invalid URLs are
+/// skipped with a warning.
+pub fn read_urls(path: &Path) -> Result<Vec<Url>, BogrepError> {
+    let content = read_file_to_string(path)?;
+    let mut urls = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match Url::parse(line) {
+            Ok(url) => urls.push(url),
+            Err(err) => warn!(
+                "Skipping invalid URL in {} (line {}): {err}",
+                path.display(),
+                index + 1
+            ),
+        }
+    }
+
+    Ok(urls)
+}
+
+/// Check that a bookmark id can be used as filename in the cache directory.
+///
+/// Ids are UUIDs, but ids containing path separators or `..` would write
+/// outside of the cache directory and are rejected.
+pub fn safe_filename(id: &str) -> Result<&str, BogrepError> {
+    if id.is_empty() || id == "." || id == ".." || id.contains(['/', '\\', '\0']) {
+        Err(BogrepError::InvalidId(id.to_owned()))
+    } else {
+        Ok(id)
+    }
+}
+
+/// Create a human-readable filename from the host and path of a url, followed
+/// by the first `hash_len` characters of the url's hash to distinguish urls
+/// with the same slug.
+pub fn readable_filename(url: &Url, hash_len: usize) -> String {
+    const MAX_SLUG_LEN: usize = 64;
+
+    let mut slug = String::new();
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    for char in host.chars().chain(url.path().chars()) {
+        if char.is_ascii_alphanumeric() {
+            slug.push(char.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.truncate(MAX_SLUG_LEN);
+    let slug = slug.trim_end_matches('-');
+    let hash = hash_content(url.as_str().as_bytes());
+    let hash = &hash[..hash_len.min(hash.len())];
+
+    if slug.is_empty() {
+        hash.to_owned()
+    } else {
+        format!("{slug}-{hash}")
+    }
+}
+
+/// Strip the query parameters from a url which match one of the given
+/// parameters, unless they match one of the included parameters.
+///
+/// A trailing `*` matches any suffix, e.g. `utm_*`, and `*` matches all query
+/// parameters. Returns `true` if the url was changed.
+pub fn strip_tracking_params(url: &mut Url, params: &[String], include: &[String]) -> bool {
+    let matches = |patterns: &[String], name: &str| {
+        patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == pattern,
+            })
+    };
+
+    let Some(query) = url.query() else {
+        return false;
+    };
+    let query_params = query.split('&').collect::<Vec<_>>();
+    let kept_params = query_params
+        .iter()
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !matches(params, name) || matches(include, name)
+        })
+        .copied()
+        .collect::<Vec<_>>();
+
+    if kept_params.len() == query_params.len() {
+        return false;
+    }
+
+    if kept_params.is_empty() {
+        url.set_query(None);
+    } else {
+        let query = kept_params.join("&");
+        url.set_query(Some(&query));
+    }
+
+    true
+}
+
 /// Helper function to read a file that logs the path of the file in case of an
 /// error.
 pub fn read_file(path: &Path) -> Result<Vec<u8>, BogrepError> {
@@ -68,11 +231,74 @@ pub fn write_file(path: &Path, content: String) -> Result<(), BogrepError> {
     Ok(())
 }
 
+/// Hash the content with SHA-256 and encode the hash as hex string.
+pub fn hash_content(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .fold(String::new(), |mut hash, byte| {
+            let _ = write!(hash, "{byte:02x}");
+            hash
+        })
+}
+
+/// Write a file atomically by writing to a temporary file first which is then
+/// renamed to the given path.
+pub fn write_file_atomically(path: &Path, content: &[u8]) -> Result<(), BogrepError> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = Path::new(&temp_path);
+
+    debug!("Write file to {}", temp_path.display());
+    let mut temp_file = create_file(temp_path)?;
+    temp_file
+        .write_all(content)
+        .map_err(|err| BogrepError::WriteFilePath {
+            path: temp_path.to_string_lossy().to_string(),
+            err,
+        })?;
+    temp_file.flush().map_err(BogrepError::FlushFile)?;
+    temp_file
+        .sync_all()
+        .map_err(|err| BogrepError::WriteFilePath {
+            path: temp_path.to_string_lossy().to_string(),
+            err,
+        })?;
+    drop(temp_file);
+
+    debug!(
+        "Rename file from {} to {}",
+        temp_path.display(),
+        path.display()
+    );
+    fs::rename(temp_path, path).map_err(|err| BogrepError::RenameFile {
+        from: temp_path.to_string_lossy().to_string(),
+        to: path.to_string_lossy().to_string(),
+        err,
+    })?;
+
+    Ok(())
+}
+
 /// Helper function to write a file that logs the path of the file in case of an
 /// error.
 pub async fn write_file_async(path: &Path, content: &[u8]) -> Result<(), BogrepError> {
+    write_file_with_mode_async(path, content, None).await
+}
+
+/// Helper function to write a file asynchronously with the given permissions.
+///
+/// The permissions are applied when the file is opened, i.e. before any
+/// content is written.
+pub async fn write_file_with_mode_async(
+    path: &Path,
+    content: &[u8],
+    file_mode: Option<u32>,
+) -> Result<(), BogrepError> {
     debug!("Write file to {}", path.display());
-    let mut file = create_file_async(path).await?;
+    let mut file = match file_mode {
+        Some(file_mode) => create_file_with_mode_async(path, file_mode).await?,
+        None => create_file_async(path).await?,
+    };
     file.write_all(content)
         .await
         .map_err(|err| BogrepError::WriteFilePath {
@@ -146,6 +372,39 @@ pub fn create_file(path: &Path) -> Result<File, BogrepError> {
     Ok(file)
 }
 
+/// Helper function to create a file with the given permissions that logs the
+/// path of the file in case of an error.
+///
+/// The permissions are also applied if the file exists already, and aren't
+/// restricted by the umask.
+#[cfg(unix)]
+pub fn create_file_with_mode(path: &Path, file_mode: u32) -> Result<File, BogrepError> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    debug!("Create file at {} with mode {file_mode:o}", path.display());
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(file_mode)
+        .open(path)
+        .map_err(|err| BogrepError::CreateFile {
+            path: path.to_string_lossy().to_string(),
+            err,
+        })?;
+    file.set_permissions(fs::Permissions::from_mode(file_mode))
+        .map_err(|err| BogrepError::CreateFile {
+            path: path.to_string_lossy().to_string(),
+            err,
+        })?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+pub fn create_file_with_mode(path: &Path, _file_mode: u32) -> Result<File, BogrepError> {
+    create_file(path)
+}
+
 /// Helper function to create a file that logs the path of the file in case of an error.
 pub async fn create_file_async(path: &Path) -> Result<tokio::fs::File, BogrepError> {
     debug!("Create file at {}", path.display());
@@ -158,6 +417,47 @@ pub async fn create_file_async(path: &Path) -> Result<tokio::fs::File, BogrepErr
     Ok(file)
 }
 
+/// Helper function to create a file with the given permissions that logs the
+/// path of the file in case of an error.
+///
+/// The permissions are also applied if the file exists already, and aren't
+/// restricted by the umask.
+#[cfg(unix)]
+pub async fn create_file_with_mode_async(
+    path: &Path,
+    file_mode: u32,
+) -> Result<tokio::fs::File, BogrepError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    debug!("Create file at {} with mode {file_mode:o}", path.display());
+    let file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(file_mode)
+        .open(path)
+        .await
+        .map_err(|err| BogrepError::CreateFile {
+            path: path.to_string_lossy().to_string(),
+            err,
+        })?;
+    file.set_permissions(fs::Permissions::from_mode(file_mode))
+        .await
+        .map_err(|err| BogrepError::CreateFile {
+            path: path.to_string_lossy().to_string(),
+            err,
+        })?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+pub async fn create_file_with_mode_async(
+    path: &Path,
+    _file_mode: u32,
+) -> Result<tokio::fs::File, BogrepError> {
+    create_file_async(path).await
+}
+
 /// Helper function to append a file that logs the path of the file in case of an error.
 pub fn append_file(path: &Path) -> Result<File, BogrepError> {
     debug!("Append file at {}", path.display());
@@ -224,3 +524,111 @@ pub fn write_settings(settings_path: &Path, settings: &Settings) -> Result<(), a
     settings_file.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_file_mode() {
+        assert_eq!(parse_file_mode("600"), Ok(0o600));
+        assert_eq!(parse_file_mode("0o640"), Ok(0o640));
+        assert_eq!(parse_file_mode("0600"), Ok(0o600));
+        assert!(parse_file_mode("").is_err());
+        assert!(parse_file_mode("0o").is_err());
+        assert!(parse_file_mode("800").is_err());
+        assert!(parse_file_mode("1777").is_err());
+    }
+
+    #[test]
+    fn test_safe_filename() {
+        assert_eq!(
+            safe_filename("dd30381b-8e67-4e84-9379-0852f60a7cd7").unwrap(),
+            "dd30381b-8e67-4e84-9379-0852f60a7cd7"
+        );
+        assert_eq!(safe_filename("id..v2").unwrap(), "id..v2");
+
+        for id in ["", ".", "..", "../id", "dir/id", "/id", "dir\\id", "id\0"] {
+            assert!(safe_filename(id).is_err(), "Unexpected filename: {id:?}");
+        }
+    }
+
+    #[test]
+    fn test_readable_filename() {
+        let url = Url::parse("https://www.example.com/Blog/Posts/My_Post.html?page=2").unwrap();
+        let filename = readable_filename(&url, 8);
+        let (slug, hash) = filename.rsplit_once('-').unwrap();
+        assert_eq!(slug, "example-com-blog-posts-my-post-html");
+        assert_eq!(hash.len(), 8);
+        assert!(safe_filename(&filename).is_ok());
+
+        // Urls with the same slug are distinguished by their hash.
+        let other_url = Url::parse("https://example.com/blog/posts/my-post.html").unwrap();
+        let other_filename = readable_filename(&other_url, 8);
+        assert!(other_filename.starts_with("example-com-blog-posts-my-post-html-"));
+        assert_ne!(filename, other_filename);
+
+        // Long slugs are truncated.
+        let url = Url::parse(&format!("https://example.com/{}", "a".repeat(100))).unwrap();
+        assert_eq!(readable_filename(&url, 8).len(), 64 + 1 + 8);
+
+        // Non-ascii characters are replaced.
+        let url = Url::parse("https://bücher.de/ärger").unwrap();
+        let filename = readable_filename(&url, 8);
+        assert!(filename.is_ascii());
+        assert!(safe_filename(&filename).is_ok());
+    }
+
+    #[test]
+    fn test_strip_tracking_params() {
+        let params = vec![String::from("utm_*"), String::from("fbclid")];
+        let mut url = Url::parse(
+            "https://example.com/post?id=42&utm_source=newsletter&utm_medium=email&fbclid=abc",
+        )
+        .unwrap();
+        assert!(strip_tracking_params(&mut url, &params, &[]));
+        assert_eq!(url.as_str(), "https://example.com/post?id=42");
+
+        // The query is removed if all query parameters are stripped.
+        let mut url = Url::parse("https://example.com/post?utm_source=newsletter#top").unwrap();
+        assert!(strip_tracking_params(&mut url, &params, &[]));
+        assert_eq!(url.as_str(), "https://example.com/post#top");
+
+        // Urls without matching query parameters are unchanged.
+        let mut url = Url::parse("https://example.com/post?id=42&utm").unwrap();
+        assert!(!strip_tracking_params(&mut url, &params, &[]));
+        assert_eq!(url.as_str(), "https://example.com/post?id=42&utm");
+
+        let mut url = Url::parse("https://example.com/post").unwrap();
+        assert!(!strip_tracking_params(&mut url, &params, &[]));
+        assert_eq!(url.as_str(), "https://example.com/post");
+    }
+
+    #[test]
+    fn test_strip_tracking_params_entire_query() {
+        let params = vec![String::from("*")];
+        let mut url = Url::parse("https://example.com/post?id=42&page=2&utm_source=x").unwrap();
+        assert!(strip_tracking_params(&mut url, &params, &[]));
+        assert_eq!(url.as_str(), "https://example.com/post");
+
+        // Included query parameters are kept.
+        let include = vec![String::from("id")];
+        let mut url = Url::parse("https://example.com/post?id=42&page=2&utm_source=x").unwrap();
+        assert!(strip_tracking_params(&mut url, &params, &include));
+        assert_eq!(url.as_str(), "https://example.com/post?id=42");
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("2d"), Ok(Duration::from_secs(172800)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("5w").is_err());
+    }
+}
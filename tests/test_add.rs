@@ -1,7 +1,13 @@
+mod common;
+
 use assert_cmd::Command;
-use bogrep::{json, utils, JsonBookmarks, SourceType};
+use bogrep::{json, utils, JsonBookmarks, SourceFolder, SourceType};
 use predicates::str;
 use tempfile::tempdir;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
 
 #[test]
 fn test_add() {
@@ -42,3 +48,155 @@ fn test_add() {
         assert!(bookmark.sources.contains(&SourceType::Internal))
     }
 }
+
+#[test]
+fn test_add_title_and_folder() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let url = "https://url1.com";
+
+    println!("Execute 'bogrep add {url} --title Title --folder dev'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", url, "--title", "Title", "--folder", "dev"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Added 1 bookmark"));
+
+    let bookmarks_path = temp_path.join("bookmarks.json");
+    let bookmarks = utils::read_file(&bookmarks_path).unwrap();
+    let res = json::deserialize::<JsonBookmarks>(&bookmarks);
+    assert!(res.is_ok());
+
+    let bookmarks = res.unwrap();
+    assert_eq!(bookmarks.len(), 1);
+
+    for bookmark in bookmarks {
+        assert_eq!(bookmark.title, Some("Title".to_owned()));
+        assert!(bookmark
+            .folders
+            .contains(&SourceFolder::new(SourceType::Internal, "dev".to_owned())));
+    }
+}
+
+#[test]
+fn test_add_title_multiple_urls() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let url1 = "https://url1.com";
+    let url2 = "https://url2.com";
+
+    println!("Execute 'bogrep add {url1} {url2} --title Title'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", url1, url2, "--title", "Title"]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("`--title` can only be used for a single URL"));
+}
+
+#[tokio::test]
+async fn test_add_fetch() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 1).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --request-throttling 1 --cache-mode html'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--request-throttling",
+        "1",
+        "--cache-mode",
+        "html",
+    ]);
+    cmd.assert().success();
+
+    let (url, content) = mocks.iter().next().unwrap();
+
+    println!("Execute 'bogrep add {url} --fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", url, "--fetch"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Added 1 bookmark"));
+
+    let bookmarks_path = temp_path.join("bookmarks.json");
+    let bookmarks = utils::read_file(&bookmarks_path).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+    assert_eq!(bookmarks.len(), 1);
+
+    for bookmark in &bookmarks {
+        assert!(bookmark.sources.contains(&SourceType::Internal));
+        assert!(bookmark.last_cached.is_some());
+
+        let cache_file = temp_path.join(format!("cache/{}.html", bookmark.id));
+        assert!(
+            cache_file.exists(),
+            "Missing path: {}",
+            cache_file.display()
+        );
+
+        let cached_content = utils::read_file_to_string(&cache_file).unwrap();
+        assert!(cached_content.contains(content.as_str()));
+    }
+}
+
+#[tokio::test]
+async fn test_add_fetch_respect_robots() {
+    let mock_server = common::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /\n"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/private"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<!DOCTYPE html><html><body>Private content</body></html>"),
+        )
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+    let url = format!("{}/private", mock_server.uri());
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --respect-robots true'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--respect-robots", "true"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep add {url} --fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", &url, "--fetch"]);
+    cmd.assert().success().stdout(str::contains(
+        "Skipped 1 bookmarks disallowed by robots.txt",
+    ));
+
+    let bookmarks_path = temp_path.join("bookmarks.json");
+    let bookmarks = utils::read_file(&bookmarks_path).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+    assert_eq!(bookmarks.len(), 1);
+
+    for bookmark in &bookmarks {
+        assert!(bookmark.last_cached.is_none());
+        assert_eq!(
+            bookmark.last_status.as_deref(),
+            Some("disallowed by robots.txt")
+        );
+    }
+}
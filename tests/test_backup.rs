@@ -0,0 +1,160 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::str;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_backup_restore() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let backup_home = temp_path.join("backup");
+    let restore_home = temp_path.join("restore");
+    let archive_path = temp_path.join("bogrep.tar.gz");
+
+    let url1 = "https://url1.com/";
+    let url2 = "https://url2.com/";
+
+    println!("Execute 'bogrep add {url1} {url2}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &backup_home);
+    cmd.args(["add", url1, url2]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(&backup_home);
+    assert_eq!(bookmarks.len(), 2);
+    let cache_path = backup_home.join("cache");
+    fs::create_dir_all(&cache_path).unwrap();
+    let cache_file = format!("{}.txt", bookmarks.bookmarks[0].id);
+    fs::write(cache_path.join(&cache_file), "Cached content").unwrap();
+
+    println!(
+        "Execute 'bogrep backup --output {}'",
+        archive_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &backup_home);
+    cmd.args(["backup", "--output", &archive_path.to_string_lossy()]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Backed up 1 cached files"));
+    assert!(archive_path.exists());
+
+    println!(
+        "Execute 'bogrep restore --input {}'",
+        archive_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &restore_home);
+    cmd.args(["restore", "--input", &archive_path.to_string_lossy()]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Restored 1 cached files"));
+
+    assert_eq!(common::test_bookmarks(&restore_home), bookmarks);
+    assert_eq!(
+        fs::read_to_string(restore_home.join("cache").join(&cache_file)).unwrap(),
+        "Cached content"
+    );
+    assert_eq!(
+        fs::read_to_string(restore_home.join("settings.json")).unwrap(),
+        fs::read_to_string(backup_home.join("settings.json")).unwrap()
+    );
+
+    // Existing bookmarks are not overwritten without `--force`.
+    println!(
+        "Execute 'bogrep restore --input {}'",
+        archive_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &restore_home);
+    cmd.args(["restore", "--input", &archive_path.to_string_lossy()]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("Bookmarks already exist"));
+
+    // Stale cache files are removed with `--force`, and restored cache files
+    // get the configured file mode.
+    let stale_file = restore_home.join("cache").join("stale.txt");
+    fs::write(&stale_file, "Stale content").unwrap();
+
+    println!("Execute 'bogrep config --cache-file-mode 600'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &restore_home);
+    cmd.args(["config", "--cache-file-mode", "600"]);
+    cmd.assert().success();
+
+    println!(
+        "Execute 'bogrep restore --input {} --force'",
+        archive_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &restore_home);
+    cmd.args([
+        "restore",
+        "--input",
+        &archive_path.to_string_lossy(),
+        "--force",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Restored 1 cached files"));
+
+    assert!(!stale_file.exists());
+    assert_eq!(
+        fs::read_to_string(restore_home.join("cache").join(&cache_file)).unwrap(),
+        "Cached content"
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let metadata = fs::metadata(restore_home.join("cache").join(&cache_file)).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn test_restore_invalid_entry() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let restore_home = temp_path.join("restore");
+    let archive_path = temp_path.join("bogrep.tar.gz");
+
+    // The valid entry precedes the invalid one, and must not be unpacked
+    // either.
+    let bookmarks = br#"{"bookmarks":[{"id":"dd30381b-8e0d-4a06-bd3f-2e3d0e3e5d8c","url":"https://url1.com/","last_imported":1694989714351,"last_cached":null,"sources":[],"cache_modes":[]}]}"#;
+    let file = fs::File::create(&archive_path).unwrap();
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    for (path, content) in [
+        ("bookmarks.json", bookmarks.as_slice()),
+        ("other.json", b"{}".as_slice()),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+
+    println!(
+        "Execute 'bogrep restore --input {}'",
+        archive_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", &restore_home);
+    cmd.args(["restore", "--input", &archive_path.to_string_lossy()]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("Invalid entry in archive: other.json"));
+
+    assert!(common::test_bookmarks(&restore_home).is_empty());
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::str;
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_cat() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 1).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --cache-mode html'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--cache-mode",
+        "html",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch"]);
+    cmd.assert().success();
+
+    let (url, content) = mocks.iter().next().unwrap();
+
+    println!("Execute 'bogrep cat {url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["cat", url]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains(content.as_str()));
+
+    println!("Execute 'bogrep cat {url} --mode text'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["cat", url, "--mode", "text"]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("is not cached in text mode"));
+
+    println!("Execute 'bogrep cat https://url.com'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["cat", "https://url.com"]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("Bookmark not found: https://url.com"));
+}
@@ -1,8 +1,10 @@
 mod common;
 
 use assert_cmd::Command;
-use bogrep::{json, utils, JsonBookmarks};
+use bogrep::{json, utils, CacheMode, JsonBookmarks};
+use predicates::str;
 use std::{
+    collections::HashSet,
     fs::{self, File},
     io::Write,
 };
@@ -176,3 +178,254 @@ async fn test_clean_all() {
         assert!(!cache_file.exists());
     }
 }
+
+#[tokio::test]
+async fn test_clean_cache_mode() {
+    let request_throttling = "1";
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let cache_path = temp_path.join("cache");
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --request-throttling {request_throttling}'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        request_throttling,
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch --mode text'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--mode", "text"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch --mode html'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--mode", "html"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    let bookmarks_path = temp_dir.path().join("bookmarks.json");
+    let bookmarks = utils::read_file(&bookmarks_path).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+
+    for bookmark in &bookmarks {
+        let cache_file = temp_path.join(format!("cache/{}.txt", bookmark.id));
+        assert!(cache_file.exists());
+
+        let cache_file = temp_path.join(format!("cache/{}.html", bookmark.id));
+        assert!(cache_file.exists());
+    }
+
+    println!("Execute 'bogrep clean --cache-mode html'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["clean", "--cache-mode", "html"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+    assert!(cache_path.exists());
+
+    let bookmarks = utils::read_file(&bookmarks_path).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+
+    for bookmark in &bookmarks {
+        assert_eq!(bookmark.cache_modes, HashSet::from_iter([CacheMode::Text]));
+
+        let cache_file = temp_path.join(format!("cache/{}.txt", bookmark.id));
+        // Text files are kept.
+        assert!(cache_file.exists());
+
+        let cache_file = temp_path.join(format!("cache/{}.html", bookmark.id));
+        // HTML files are now deleted.
+        assert!(!cache_file.exists());
+    }
+}
+
+#[tokio::test]
+async fn test_clean_unreferenced() {
+    let request_throttling = "1";
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let cache_path = temp_path.join("cache");
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --request-throttling {request_throttling}'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        request_throttling,
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    // Seed files of bookmarks which are not in `bookmarks.json` anymore.
+    let orphaned_files = [
+        cache_path.join("25b6357e-6eda-4367-8212-84376c6efe05.txt"),
+        cache_path.join("a4d8f19b-92c1-4e68-a6e9-7d60b54024bc.html"),
+    ];
+
+    for orphaned_file in &orphaned_files {
+        fs::write(orphaned_file, "Test content").unwrap();
+    }
+
+    println!("Execute 'bogrep clean --unreferenced'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["clean", "--unreferenced"]);
+    cmd.assert().success().stdout(str::contains(
+        "Removed 2 unreferenced files, freed 24 bytes",
+    ));
+
+    for orphaned_file in &orphaned_files {
+        assert!(!orphaned_file.exists());
+    }
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    for bookmark in &bookmarks {
+        let cache_file = temp_path.join(format!("cache/{}.txt", bookmark.id));
+        assert!(cache_file.exists());
+    }
+}
+
+#[tokio::test]
+async fn test_clean_expired() {
+    let request_throttling = "1";
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+    let urls = mocks.keys().collect::<Vec<_>>();
+
+    for url in &urls {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --request-throttling {request_throttling} --removal-grace-period 1ms'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        request_throttling,
+        "--removal-grace-period",
+        "1ms",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    // Remove the first bookmark from the source.
+    let mut file = File::create(source).unwrap();
+
+    for url in &urls[1..] {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Marked 1 bookmarks as removed"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+    let removed_bookmark = bookmarks
+        .iter()
+        .find(|bookmark| &bookmark.url == urls[0])
+        .unwrap();
+    assert!(removed_bookmark.removed_at.is_some());
+
+    println!("Execute 'bogrep clean --expired'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["clean", "--expired"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Removed 1 expired bookmarks"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+    assert!(bookmarks
+        .iter()
+        .all(|bookmark| &bookmark.url != urls[0] && bookmark.removed_at.is_none()));
+}
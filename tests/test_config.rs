@@ -26,6 +26,8 @@ fn test_config() {
             cache_path: temp_path.join("cache"),
             target_bookmark_file: temp_path.join("bookmarks.json"),
             target_bookmark_lock_file: temp_path.join("bookmarks-lock.json"),
+            manifest_file: temp_path.join("manifest.json"),
+            index_file: temp_path.join("index.json"),
             settings: Settings::default()
         }
     );
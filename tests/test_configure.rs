@@ -1,6 +1,7 @@
 use assert_cmd::Command;
 use bogrep::{json, utils, JsonBookmarks, Settings};
-use std::path::Path;
+use predicates::str;
+use std::{fs, path::Path};
 use tempfile::tempdir;
 
 fn test_configure_source(temp_path: &Path, source: &str, folder: Option<&str>) {
@@ -165,3 +166,183 @@ fn test_configure_underlying_urls() {
     let settings = res.unwrap();
     assert!(!settings.underlying_urls.is_empty());
 }
+
+#[test]
+fn test_configure_reset() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let url = "https://url1";
+
+    println!("Execute 'bogrep config --ignore {url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--ignore", url]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep config --reset'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--reset"]);
+    cmd.write_stdin("y\n");
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    let settings_path = temp_dir.path().join("settings.json");
+    let settings = utils::read_file(&settings_path).unwrap();
+    let res = json::deserialize::<Settings>(&settings);
+    assert!(res.is_ok());
+
+    let settings = res.unwrap();
+    assert_eq!(settings, Settings::default());
+}
+
+#[test]
+fn test_configure_source_unsupported_format() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let source_path = temp_path.join("bookmarks.pdf");
+    fs::write(&source_path, "%PDF-1.7").unwrap();
+
+    println!("Execute 'bogrep config --source {}'", source_path.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source_path.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stderr(str::contains("File type pdf not supported"));
+
+    let settings_path = temp_dir.path().join("settings.json");
+    let settings = utils::read_file(&settings_path).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.sources.len(), 1);
+}
+
+#[test]
+fn test_configure_source_missing_path() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let source_path = temp_path.join("missing/bookmarks.json");
+
+    println!("Execute 'bogrep config --source {}'", source_path.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source_path.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stderr(str::contains("doesn't exist yet"));
+
+    let settings_path = temp_dir.path().join("settings.json");
+    let settings = utils::read_file(&settings_path).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.sources.len(), 1);
+}
+
+#[test]
+fn test_configure_source_empty_folder() {
+    let source = "./test_data/bookmarks_netscape.html";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    test_configure_source(temp_path, source, Some("dev"));
+
+    println!("Execute 'bogrep config --source {source} --folders dev,\" \"'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source, "--folders", "dev, "]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("folder names must not be empty"));
+
+    // The previous settings are kept.
+    let settings_path = temp_path.join("settings.json");
+    let settings =
+        json::deserialize::<Settings>(&utils::read_file(&settings_path).unwrap()).unwrap();
+    assert_eq!(settings.sources.len(), 1);
+    assert_eq!(settings.sources[0].folders, vec!["dev".to_owned()]);
+}
+
+#[test]
+fn test_configure_import_folders() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source = "./test_data/bookmarks_firefox.json";
+    test_configure_source(temp_path, source, Some("dev"));
+
+    println!("Execute 'bogrep config --import-folders articles,news'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--import-folders", "articles,news"]);
+    cmd.assert().success();
+
+    let settings = utils::read_file(&temp_path.join("settings.json")).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.sources.len(), 1);
+    assert_eq!(settings.sources[0].folders, vec!["articles", "news"]);
+
+    println!("Execute 'bogrep config --source unknown.json --import-folders dev'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        "unknown.json",
+        "--import-folders",
+        "dev",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("is not configured"));
+}
+
+#[test]
+fn test_configure_idle_connections() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!(
+        "Execute 'bogrep config --max-idle-connections-per-host 5 --idle-connections-timeout 10000'"
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--max-idle-connections-per-host",
+        "5",
+        "--idle-connections-timeout",
+        "10000",
+    ]);
+    cmd.assert().success();
+
+    let settings = utils::read_file(&temp_path.join("settings.json")).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.max_idle_connections_per_host, 5);
+    assert_eq!(settings.idle_connections_timeout, 10_000);
+
+    for (arg, value) in [
+        ("--max-idle-connections-per-host", "2000"),
+        ("--idle-connections-timeout", "0"),
+        ("--idle-connections-timeout", "3600001"),
+    ] {
+        println!("Execute 'bogrep config {arg} {value}'");
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+        cmd.env("BOGREP_HOME", temp_path);
+        cmd.args(["config", arg, value]);
+        cmd.assert().failure();
+    }
+
+    // Invalid values don't change the settings.
+    let settings = utils::read_file(&temp_path.join("settings.json")).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.max_idle_connections_per_host, 5);
+    assert_eq!(settings.idle_connections_timeout, 10_000);
+}
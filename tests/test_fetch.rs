@@ -1,13 +1,18 @@
 mod common;
 
 use assert_cmd::Command;
-use bogrep::utils;
+use bogrep::{json, utils, Manifest, ManifestEntry};
 use predicates::{prelude::PredicateBooleanExt, str};
 use std::{
     fs::{self, File},
     io::Write,
 };
 use tempfile::tempdir;
+use url::Url;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
 
 #[tokio::test]
 async fn test_fetch() {
@@ -188,6 +193,48 @@ async fn test_fetch_diff() {
     );
 }
 
+#[tokio::test]
+async fn test_fetch_diff_summary() {
+    let mock_server = common::start_mock_server().await;
+    let mock_website_1 = common::mount_mock_scoped(&mock_server, 1, 10).await;
+    let mock_website_2 = common::mount_mock_scoped(&mock_server, 2, 20).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --request-throttling 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--request-throttling", "1"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep fetch --urls'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--urls", &mock_website_1.url, &mock_website_2.url]);
+    cmd.assert().success();
+
+    // Change the content of the first mock website only.
+    drop(mock_website_1);
+    let mock_website_1 = common::mount_mock_scoped(&mock_server, 1, 11).await;
+
+    println!("Execute 'bogrep fetch --diff --summary'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "fetch",
+        "--diff",
+        &mock_website_1.url,
+        &mock_website_2.url,
+        "--summary",
+    ]);
+    cmd.assert().success().stdout(
+        str::contains(format!("{}: +1 -1", mock_website_1.url))
+            .and(str::contains(format!("{}: +0 -0", mock_website_2.url)))
+            .and(str::contains("Test content 10").not()),
+    );
+}
+
 // Test fetching if the cache directory was removed manually, leading to
 // inconsistent state as the target bookmarks are still marked as last cached.
 #[tokio::test]
@@ -282,3 +329,810 @@ async fn test_fetch_empty_cache() {
         assert_eq!(&actual_content, expected_content);
     }
 }
+
+#[tokio::test]
+async fn test_fetch_without_tty() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep config --source {}'", source.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch"]);
+    // Stdout is not a terminal, so the plain progress is printed.
+    cmd.assert().success().stdout(str::starts_with(
+        "Processing bookmarks (1/3)\rProcessing bookmarks (2/3)\rProcessing bookmarks (3/3)\r\n\
+        Processed 3 bookmarks, 3 cached, 0 ignored, 0 failed\n",
+    ));
+}
+
+#[tokio::test]
+async fn test_fetch_write_manifest() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep config --source {}'", source.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch --write-manifest'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--write-manifest"]);
+    cmd.assert().success();
+
+    let manifest_path = temp_path.join("manifest.json");
+    assert!(
+        manifest_path.exists(),
+        "Missing path: {}",
+        manifest_path.display()
+    );
+    let manifest = utils::read_file(&manifest_path).unwrap();
+    let manifest = json::deserialize::<Manifest>(&manifest).unwrap();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+    assert_eq!(manifest.len(), 3);
+
+    for bookmark in &bookmarks {
+        let entries = manifest.bookmarks.get(&bookmark.id).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        let entry = &entries[0];
+        let cache_path = temp_path.join("cache").join(&entry.filename);
+        let content = fs::read(&cache_path).unwrap();
+        assert_eq!(
+            entry,
+            &ManifestEntry::new(entry.filename.clone(), entry.cache_mode.clone(), &content)
+        );
+        assert_eq!(
+            cache_path,
+            temp_path.join(format!("cache/{}.txt", bookmark.id))
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_concurrency() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep config --source {}'", source.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch --concurrency 0'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--concurrency", "0"]);
+    cmd.assert().failure();
+
+    println!("Execute 'bogrep fetch --concurrency 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--concurrency", "1"]);
+    cmd.assert().success().stdout(str::contains(
+        "Processed 3 bookmarks, 3 cached, 0 ignored, 0 failed",
+    ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    for bookmark in bookmarks {
+        assert!(bookmark.last_cached.is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_checkpoint_every() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep config --source {}'", source.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch --checkpoint-every 2'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--checkpoint-every", "2"]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    for bookmark in bookmarks {
+        assert!(bookmark.last_cached.is_some());
+    }
+
+    assert!(!temp_path.join("bookmarks.json.tmp").exists());
+}
+
+#[tokio::test]
+async fn test_fetch_cache_path() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let cache_dir = tempdir().unwrap();
+    let cache_path = cache_dir.path().join("bogrep-cache");
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --cache-path {}'",
+        source.display(),
+        cache_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+        "--cache-path",
+        cache_path.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+    assert!(cache_path.exists());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch"]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    for bookmark in &bookmarks {
+        assert!(cache_path.join(format!("{}.txt", bookmark.id)).exists());
+        assert!(!temp_path
+            .join(format!("cache/{}.txt", bookmark.id))
+            .exists());
+    }
+
+    let migrated_path = cache_dir.path().join("bogrep-cache-migrated");
+
+    println!(
+        "Execute 'bogrep config --cache-path {} --migrate'",
+        migrated_path.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--cache-path",
+        migrated_path.to_str().unwrap(),
+        "--migrate",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Migrated 3 files"));
+
+    for bookmark in &bookmarks {
+        assert!(migrated_path.join(format!("{}.txt", bookmark.id)).exists());
+        assert!(!cache_path.join(format!("{}.txt", bookmark.id)).exists());
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_only_new() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 5).await;
+    let mut urls = mocks.keys().collect::<Vec<_>>();
+    urls.sort();
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in &urls[..3] {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep config --source {}'", source.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source.to_str().unwrap()]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert().success();
+
+    for url in &urls[3..] {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!("Execute 'bogrep fetch --only-new'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--only-new"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Added 2 bookmarks"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 5);
+
+    for bookmark in bookmarks {
+        let is_new = urls[3..].contains(&&bookmark.url);
+        assert_eq!(bookmark.last_cached.is_some(), is_new, "{}", bookmark.url);
+    }
+
+    let cached_files = fs::read_dir(temp_path.join("cache")).unwrap().count();
+    assert_eq!(cached_files, 2);
+}
+
+#[tokio::test]
+async fn test_fetch_input_file() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 2).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let input_file = temp_path.join("urls.txt");
+    let mut file = File::create(&input_file).unwrap();
+    writeln!(file, "# Reading list").unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    writeln!(file, "not a url").unwrap();
+
+    println!("Execute 'bogrep config --request-throttling 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--request-throttling", "1"]);
+    cmd.assert().success();
+
+    println!(
+        "Execute 'bogrep fetch --input-file {}'",
+        input_file.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--input-file", input_file.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stderr(str::contains("Skipping invalid URL").and(str::contains("line 4")));
+
+    // The urls were fetched, but not added to the bookmarks.
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert!(bookmarks.is_empty());
+
+    let cached_files = fs::read_dir(temp_path.join("cache")).unwrap().count();
+    assert_eq!(cached_files, 2);
+
+    println!(
+        "Execute 'bogrep fetch --input-file {} --add'",
+        input_file.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "fetch",
+        "--input-file",
+        input_file.to_str().unwrap(),
+        "--add",
+    ]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+
+    for bookmark in &bookmarks {
+        assert!(mocks.contains_key(&bookmark.url));
+        assert!(bookmark.last_cached.is_some());
+        assert!(temp_path
+            .join(format!("cache/{}.txt", bookmark.id))
+            .exists());
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_urls_and_input_file() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 2).await;
+    let mut urls = mocks.keys().collect::<Vec<_>>();
+    urls.sort();
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let input_file = temp_path.join("urls.txt");
+    let mut file = File::create(&input_file).unwrap();
+    writeln!(file, "{}", urls[1]).unwrap();
+
+    println!("Execute 'bogrep config --request-throttling 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--request-throttling", "1"]);
+    cmd.assert().success();
+
+    println!(
+        "Execute 'bogrep fetch --urls {} --input-file {}'",
+        urls[0],
+        input_file.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "fetch",
+        "--urls",
+        urls[0],
+        "--input-file",
+        input_file.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    // Only the url of the input file is fetched without adding it.
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 1);
+    assert_eq!(&bookmarks.bookmarks[0].url, urls[0]);
+    assert!(temp_path
+        .join(format!("cache/{}.txt", bookmarks.bookmarks[0].id))
+        .exists());
+
+    let cached_files = fs::read_dir(temp_path.join("cache")).unwrap().count();
+    assert_eq!(cached_files, 2);
+}
+
+#[tokio::test]
+async fn test_fetch_continue_on_empty() {
+    let mock_server = common::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/empty"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-type", "text/html"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/error"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+    let empty_url = format!("{}/empty", mock_server.uri());
+    let error_url = format!("{}/error", mock_server.uri());
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --request-throttling 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--request-throttling", "1"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep fetch --urls {empty_url} {error_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--urls", &empty_url, &error_url]);
+    cmd.assert().success().stdout(
+        str::contains("Processed 2 bookmarks, 0 cached, 1 ignored, 1 failed")
+            .and(str::contains("Skipped 1 empty bookmarks")),
+    );
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+    assert!(bookmarks
+        .iter()
+        .all(|bookmark| bookmark.last_cached.is_none()));
+
+    println!("Execute 'bogrep fetch --continue-on-empty'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--continue-on-empty"]);
+    cmd.assert().success().stdout(
+        str::contains("Processed 2 bookmarks, 1 cached, 0 ignored, 1 failed")
+            .and(str::contains("Cached 1 empty bookmarks")),
+    );
+
+    let bookmarks = common::test_bookmarks(temp_path);
+
+    for bookmark in &bookmarks {
+        let cache_file = temp_path.join(format!("cache/{}.txt", bookmark.id));
+
+        if bookmark.url == empty_url {
+            assert!(bookmark.last_cached.is_some());
+            assert_eq!(fs::read_to_string(cache_file).unwrap(), "");
+        } else {
+            assert!(bookmark.last_cached.is_none());
+            assert!(!cache_file.exists());
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_last_status() {
+    let mock_server = common::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<!DOCTYPE html><html><body>Test content</body></html>"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    let ok_url = format!("{}/ok", mock_server.uri());
+    let missing_url = format!("{}/missing", mock_server.uri());
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --request-throttling 1'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--request-throttling", "1"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep fetch --urls {ok_url} {missing_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--urls", &ok_url, &missing_url]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+
+    for bookmark in &bookmarks {
+        if bookmark.url == ok_url {
+            assert!(bookmark.last_cached.is_some());
+            assert_eq!(bookmark.last_status.as_deref(), Some("ok"));
+        } else {
+            assert!(bookmark.last_cached.is_none());
+            assert_eq!(bookmark.last_status.as_deref(), Some("status 404"));
+        }
+    }
+}
+
+#[test]
+fn test_fetch_file_url() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let archive_path = temp_path.join("archive");
+    fs::create_dir_all(&archive_path).unwrap();
+    let archive_file = archive_path.join("website.html");
+    fs::write(
+        &archive_file,
+        "<!DOCTYPE html><html><body><p>Archived content</p></body></html>",
+    )
+    .unwrap();
+    let file_url = Url::from_file_path(&archive_file).unwrap().to_string();
+    let missing_url = Url::from_file_path(archive_path.join("missing.html"))
+        .unwrap()
+        .to_string();
+
+    println!("Execute 'bogrep fetch --urls {file_url} {missing_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--urls", &file_url, &missing_url]);
+    cmd.assert().success().stdout(str::contains(
+        "Processed 2 bookmarks, 1 cached, 0 ignored, 1 failed",
+    ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+
+    for bookmark in &bookmarks {
+        let cache_file = temp_path.join(format!("cache/{}.txt", bookmark.id));
+
+        if bookmark.url == file_url {
+            assert!(bookmark.last_cached.is_some());
+            assert_eq!(
+                fs::read_to_string(cache_file).unwrap().trim(),
+                "Archived content"
+            );
+        } else {
+            assert!(bookmark.last_cached.is_none());
+            assert_eq!(bookmark.last_status.as_deref(), Some("missing file"));
+        }
+    }
+}
+
+async fn mount_pdf(mock_server: &wiremock::MockServer) -> String {
+    let pdf = fs::read("test_data/website.pdf").unwrap();
+    Mock::given(method("GET"))
+        .and(path("/website.pdf"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(pdf, "application/pdf"))
+        .mount(mock_server)
+        .await;
+    format!("{}/website.pdf", mock_server.uri())
+}
+
+#[tokio::test]
+async fn test_fetch_binary_skipped() {
+    let mock_server = common::start_mock_server().await;
+    let pdf_url = mount_pdf(&mock_server).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep fetch --urls {pdf_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--urls", &pdf_url]);
+    cmd.assert().success().stdout(str::contains(
+        "Processed 1 bookmarks, 0 cached, 1 ignored, 0 failed",
+    ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 1);
+    assert!(bookmarks.bookmarks[0].last_cached.is_none());
+    assert_eq!(
+        bookmarks.bookmarks[0].last_status.as_deref(),
+        Some("binary response")
+    );
+}
+
+#[cfg(feature = "pdf-extract")]
+#[tokio::test]
+async fn test_fetch_binary_included() {
+    let mock_server = common::start_mock_server().await;
+    let pdf_url = mount_pdf(&mock_server).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep fetch --include-binary --urls {pdf_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--include-binary", "--urls", &pdf_url]);
+    cmd.assert().success().stdout(str::contains(
+        "Processed 1 bookmarks, 1 cached, 0 ignored, 0 failed",
+    ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 1);
+    let cache_file = temp_path.join(format!("cache/{}.txt", bookmarks.bookmarks[0].id));
+    let content = fs::read_to_string(cache_file).unwrap();
+    assert!(content.contains("Hello from PDF"), "{content}");
+}
+
+#[tokio::test]
+async fn test_fetch_human_readable() {
+    let mock_server = common::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/blog/post"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<!DOCTYPE html><html><body>Test content</body></html>"),
+        )
+        .mount(&mock_server)
+        .await;
+    let url = format!("{}/blog/post", mock_server.uri());
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep fetch --human-readable --urls {url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch", "--human-readable", "--urls", &url]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 1);
+    let filename = bookmarks.bookmarks[0].filename.as_ref().unwrap();
+    assert!(filename.starts_with("127-0-0-1-blog-post-"), "{filename}");
+
+    let cache_file = temp_path.join("cache").join(format!("{filename}.txt"));
+    assert_eq!(
+        fs::read_to_string(cache_file).unwrap().trim(),
+        "Test content"
+    );
+    assert!(!temp_path
+        .join("cache")
+        .join(format!("{}.txt", bookmarks.bookmarks[0].id))
+        .exists());
+}
+
+#[tokio::test]
+async fn test_fetch_respect_robots() {
+    let mock_server = common::start_mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/robots.txt"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private\n"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/public"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<!DOCTYPE html><html><body>Public content</body></html>"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/private/page"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<!DOCTYPE html><html><body>Private content</body></html>"),
+        )
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+    let public_url = format!("{}/public", mock_server.uri());
+    let private_url = format!("{}/private/page", mock_server.uri());
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep fetch --respect-robots --urls {public_url} {private_url}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "fetch",
+        "--respect-robots",
+        "--urls",
+        &public_url,
+        &private_url,
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains(
+            "Skipped 1 bookmarks disallowed by robots.txt",
+        ))
+        .stdout(str::contains(
+            "Processed 1 bookmarks, 1 cached, 0 ignored, 0 failed",
+        ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+
+    for bookmark in &bookmarks {
+        if bookmark.url == private_url {
+            assert!(bookmark.last_cached.is_none());
+            assert_eq!(
+                bookmark.last_status.as_deref(),
+                Some("disallowed by robots.txt")
+            );
+        } else {
+            assert!(bookmark.last_cached.is_some());
+        }
+    }
+}
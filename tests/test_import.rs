@@ -1,7 +1,7 @@
 mod common;
 
 use assert_cmd::Command;
-use bogrep::{json, test_utils, utils, JsonBookmarks};
+use bogrep::{json, test_utils, utils, JsonBookmarks, Settings, SourceType};
 use predicates::{prelude::PredicateBooleanExt, str};
 use std::{collections::HashSet, fs, io::Write, path::Path};
 use tempfile::tempdir;
@@ -51,6 +51,16 @@ fn test_import_simple() {
     test_import(source_path, temp_path, 4);
 }
 
+#[test]
+fn test_import_csv() {
+    let source_path = "./test_data/bookmarks.csv";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    test_import(source_path, temp_path, 4);
+}
+
 #[test]
 fn test_import_firefox() {
     let source_path = "./test_data/bookmarks_firefox.json";
@@ -61,6 +71,16 @@ fn test_import_firefox() {
     test_import(source_path, temp_path, 4);
 }
 
+#[test]
+fn test_import_netscape() {
+    let source_path = "./test_data/bookmarks_netscape.html";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    test_import(source_path, temp_path, 4);
+}
+
 #[test]
 fn test_import_firefox_compressed() {
     let source_path = "./test_data/bookmarks_firefox.jsonlz4";
@@ -110,6 +130,16 @@ fn test_import_chrome() {
     test_import(source_path, temp_path, 4);
 }
 
+#[test]
+fn test_import_pocket() {
+    let source_path = "./test_data/bookmarks_pocket.json";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    test_import(source_path, temp_path, 4);
+}
+
 #[test]
 fn test_import_safari_xml() {
     let source_path = "./test_data/bookmarks_safari_xml.plist";
@@ -192,3 +222,318 @@ fn test_import_simple_consecutive() {
     let bookmarks = common::test_bookmarks(temp_path);
     assert_eq!(bookmarks.len(), 1);
 }
+
+#[test]
+fn test_import_format() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let source_path = temp_path.join("bookmarks.export");
+    fs::copy("./test_data/bookmarks_firefox.json", &source_path).unwrap();
+    let source_path = source_path.to_str().unwrap();
+
+    println!("Execute 'bogrep config --source {source_path} --format json'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source_path, "--format", "json"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Imported 4 bookmarks from 1 source"));
+}
+
+#[test]
+fn test_import_source_type() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = "test_data/bookmarks_simple.txt";
+
+    println!("Execute 'bogrep config --source {source_path} --source-type work'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source_path, "--source-type", "work"]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert().success();
+
+    let bookmarks = utils::read_file(&temp_path.join("bookmarks.json")).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+    assert!(!bookmarks.is_empty());
+    assert!(bookmarks.iter().all(|bookmark| {
+        bookmark.sources == HashSet::from([SourceType::Custom("work".to_owned())])
+    }));
+
+    println!("Execute 'bogrep list --source work'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["list", "--source", "work"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains(bookmarks.iter().next().unwrap().url.as_str()));
+}
+
+#[tokio::test]
+async fn test_import_replace_source() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 4).await;
+    let mut urls = mocks.keys().collect::<Vec<_>>();
+    urls.sort();
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let old_source = temp_path.join("old_bookmarks.txt");
+    let new_source = temp_path.join("new_bookmarks.txt");
+    fs::write(
+        &old_source,
+        format!("{}\n{}\n{}\n", urls[0], urls[1], urls[2]),
+    )
+    .unwrap();
+    fs::write(
+        &new_source,
+        format!("{}\n{}\n{}\n", urls[1], urls[2], urls[3]),
+    )
+    .unwrap();
+    let old_source = old_source.to_str().unwrap();
+    let new_source = new_source.to_str().unwrap();
+
+    println!("Execute 'bogrep config --source {old_source}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", old_source]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep sync'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["sync"]);
+    cmd.assert().success();
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    let removed_id = bookmarks
+        .iter()
+        .find(|bookmark| &&bookmark.url == urls.first().unwrap())
+        .unwrap()
+        .id
+        .clone();
+    assert!(temp_path.join(format!("cache/{removed_id}.txt")).exists());
+
+    println!("Execute 'bogrep import --replace-source {old_source} {new_source}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import", "--replace-source", old_source, new_source]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Added 1 bookmarks").and(str::contains("Removed 1 bookmarks")));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    let mut actual_urls = bookmarks
+        .iter()
+        .map(|bookmark| &bookmark.url)
+        .collect::<Vec<_>>();
+    actual_urls.sort();
+    assert_eq!(actual_urls, urls[1..]);
+
+    // Fetched bookmarks which are present in both sources are still cached.
+    for bookmark in bookmarks {
+        assert_eq!(bookmark.last_cached.is_some(), &bookmark.url != urls[3]);
+    }
+
+    assert!(!temp_path.join(format!("cache/{removed_id}.txt")).exists());
+
+    let settings = utils::read_file(&temp_path.join("settings.json")).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.sources.len(), 1);
+    assert_eq!(
+        settings.sources[0].path,
+        fs::canonicalize(new_source).unwrap()
+    );
+}
+
+#[test]
+fn test_import_folder_not_found() {
+    let source_path = "./test_data/bookmarks_netscape.html";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --source {source_path} --folders dev,unknown'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source_path,
+        "--folders",
+        "dev,unknown",
+    ]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Imported 2 bookmarks"))
+        .stderr(
+            str::contains("Folders not found in source")
+                .and(str::contains("bookmarks_netscape.html: unknown\n")),
+        );
+}
+
+#[test]
+fn test_import_ignored_report() {
+    let source_path = "./test_data/bookmarks_simple.txt";
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    println!("Execute 'bogrep config --source {source_path} --ignore https://en.wikipedia.org'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source_path,
+        "--ignore",
+        "https://en.wikipedia.org",
+    ]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import --ignored-report'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import", "--ignored-report"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Ignored bookmarks:").and(str::contains(
+            "https://en.wikipedia.org/wiki/Design_Patterns (matches https://en.wikipedia.org/)",
+        )));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+}
+
+#[test]
+fn test_import_strip_query() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("bookmarks_simple.txt");
+    fs::write(
+        &source_path,
+        "https://example.com/post?id=1&utm_source=newsletter\n\
+        https://example.com/post?id=1&utm_source=feed\n\
+        https://example.com/other?page=2&fbclid=abc\n",
+    )
+    .unwrap();
+    let source_path = source_path.to_str().unwrap();
+
+    println!("Execute 'bogrep config --source {source_path} --strip-query utm_* fbclid'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source_path,
+        "--strip-query",
+        "utm_*",
+        "fbclid",
+    ]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert().success();
+
+    // Duplicates which differ only in tracking parameters are merged.
+    let bookmarks = utils::read_file(&temp_path.join("bookmarks.json")).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+    assert_eq!(
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.url.as_str())
+            .collect::<HashSet<_>>(),
+        HashSet::from([
+            "https://example.com/post?id=1",
+            "https://example.com/other?page=2"
+        ])
+    );
+
+    println!("Execute 'bogrep config --strip-query --include-query id'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--strip-query", "--include-query", "id"]);
+    cmd.assert().success();
+
+    let settings = utils::read_file(&temp_path.join("settings.json")).unwrap();
+    let settings = json::deserialize::<Settings>(&settings).unwrap();
+    assert_eq!(settings.strip_query, vec!["*"]);
+    assert_eq!(settings.include_query, vec!["id"]);
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert().success();
+
+    let bookmarks = utils::read_file(&temp_path.join("bookmarks.json")).unwrap();
+    let bookmarks = json::deserialize::<JsonBookmarks>(&bookmarks).unwrap();
+    assert_eq!(
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.url.as_str())
+            .collect::<HashSet<_>>(),
+        HashSet::from(["https://example.com/post?id=1", "https://example.com/other"])
+    );
+}
+
+#[test]
+fn test_import_large_source() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("bookmarks_simple.txt");
+    let mut file = fs::File::create(&source_path).unwrap();
+
+    for i in 0..10000 {
+        writeln!(file, "https://url{i}.com/page").unwrap();
+    }
+
+    let source_path = source_path.to_str().unwrap();
+
+    println!("Execute 'bogrep config --source {source_path}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["config", "--source", source_path]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Imported 10000 bookmarks from 1 source"));
+
+    println!("Execute 'bogrep import --quiet'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import", "--quiet"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Bookmarks are already up to date"));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use assert_cmd::Command;
+use bogrep::{json, JsonBookmark, JsonBookmarks};
+use predicates::str;
+use std::{collections::HashSet, fs};
+use tempfile::tempdir;
+
+#[test]
+fn test_merge() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let url1 = "https://url1.com/";
+    let url2 = "https://url2.com/";
+    let url3 = "https://url3.com/";
+
+    println!("Execute 'bogrep add {url1} {url2}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", url1, url2]);
+    cmd.assert().success();
+
+    // The other bookmarks overlap in url2, which was cached on the other
+    // machine, and whose cached file was copied.
+    let mut other_bookmark2 =
+        JsonBookmark::new(url2.to_owned(), 0, Some(1), HashSet::new(), HashSet::new());
+    other_bookmark2.id = "other-id".to_owned();
+    fs::write(temp_path.join("cache/other-id.txt"), "content").unwrap();
+    // The cached file of url3 wasn't copied.
+    let other_bookmark3 =
+        JsonBookmark::new(url3.to_owned(), 0, Some(1), HashSet::new(), HashSet::new());
+    let other_bookmarks = JsonBookmarks {
+        bookmarks: vec![other_bookmark2, other_bookmark3],
+    };
+    let other_path = temp_path.join("other-bookmarks.json");
+    fs::write(&other_path, json::serialize(&other_bookmarks).unwrap()).unwrap();
+
+    println!("Execute 'bogrep merge {}'", other_path.display());
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["merge", &other_path.to_string_lossy()]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Merged bookmarks: 1 added, 1 updated"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    let urls = bookmarks
+        .iter()
+        .map(|bookmark| bookmark.url.as_str())
+        .collect::<HashSet<_>>();
+    assert_eq!(urls, HashSet::from_iter([url1, url2, url3]));
+
+    let bookmark2 = bookmarks
+        .iter()
+        .find(|bookmark| bookmark.url == url2)
+        .unwrap();
+    assert_eq!(bookmark2.id, "other-id");
+    assert_eq!(bookmark2.last_cached, Some(1));
+
+    let bookmark3 = bookmarks
+        .iter()
+        .find(|bookmark| bookmark.url == url3)
+        .unwrap();
+    assert_eq!(bookmark3.last_cached, None);
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use assert_cmd::Command;
+use bogrep::json;
+use predicates::str;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_prune() {
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+
+    let url1 = "https://url1.com/";
+    let url2 = "https://url2.com/";
+
+    println!("Execute 'bogrep add {url1} {url2}'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["add", url1, url2]);
+    cmd.assert().success();
+
+    // Pretend that url2 was last imported a long time ago.
+    let mut bookmarks = common::test_bookmarks(temp_path);
+    for bookmark in bookmarks.bookmarks.iter_mut() {
+        if bookmark.url == url2 {
+            bookmark.last_imported = 0;
+        }
+    }
+    let bookmarks_path = temp_path.join("bookmarks.json");
+    fs::write(&bookmarks_path, json::serialize(&bookmarks).unwrap()).unwrap();
+
+    println!("Execute 'bogrep prune --older-than 30d --dry-run'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["prune", "--older-than", "30d", "--dry-run"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains(url2))
+        .stdout(str::contains("Pruned 1 bookmarks (dry run)"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+
+    println!("Execute 'bogrep prune --older-than 30d'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["prune", "--older-than", "30d"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Pruned 1 bookmarks"));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 1);
+    assert_eq!(bookmarks.bookmarks[0].url, url1);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::str;
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_reindex() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 3).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --cache-mode html'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+        "--cache-mode",
+        "html",
+    ]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep import'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["import"]);
+    let res = cmd.output();
+    assert!(res.is_ok(), "Can't execute command: {}", res.unwrap_err());
+
+    println!("Execute 'bogrep fetch'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["fetch"]);
+    cmd.assert().success();
+
+    // Stop the mock server to make sure that the websites are not refetched.
+    drop(mock_server);
+
+    println!("Execute 'bogrep reindex --mode text'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["reindex", "--mode", "text"]);
+    cmd.assert().success().stdout(str::contains(
+        "Converted 3 bookmarks to text, 0 bookmarks need to be refetched",
+    ));
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 3);
+
+    for bookmark in bookmarks {
+        assert!(bookmark.cache_modes.contains(&bogrep::CacheMode::Text));
+        let text_path = temp_path.join(format!("cache/{}.txt", bookmark.id));
+        let content = fs::read_to_string(text_path).unwrap();
+        assert!(mocks.values().any(|mock_content| mock_content == &content));
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use assert_cmd::Command;
+use predicates::{prelude::PredicateBooleanExt, str};
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_watch() {
+    let mock_server = common::start_mock_server().await;
+    let mocks = common::mount_mocks(&mock_server, 2).await;
+    let temp_dir = tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    assert!(temp_path.exists(), "Missing path: {}", temp_path.display());
+    let source_path = temp_path.join("test_data");
+    let source = &source_path.join("bookmarks_simple.txt");
+    fs::create_dir_all(&source_path).unwrap();
+    let mut file = File::create(source).unwrap();
+
+    for url in mocks.keys() {
+        writeln!(file, "{}", url).unwrap();
+    }
+
+    println!(
+        "Execute 'bogrep config --source {} --request-throttling 1'",
+        source.display()
+    );
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args([
+        "config",
+        "--source",
+        source.to_str().unwrap(),
+        "--request-throttling",
+        "1",
+    ]);
+    cmd.assert().success();
+
+    println!("Execute 'bogrep watch --interval 100ms --cycles 2'");
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap();
+    cmd.env("BOGREP_HOME", temp_path);
+    cmd.args(["watch", "--interval", "100ms", "--cycles", "2"]);
+    cmd.assert().success().stdout(
+        str::contains("Finished watch cycle 1, next cycle in 100ms")
+            .and(str::contains("Start watch cycle 2"))
+            .and(str::contains("Start watch cycle 3").not()),
+    );
+
+    // The bookmarks are fetched in every cycle.
+    let requests = mock_server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 4);
+
+    let bookmarks = common::test_bookmarks(temp_path);
+    assert_eq!(bookmarks.len(), 2);
+    for bookmark in &bookmarks {
+        assert!(bookmark.last_cached.is_some());
+    }
+}